@@ -2,7 +2,12 @@
 //
 // This module contains handler functions for API endpoints.
 
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
 use chrono::Utc;
 use log::{debug, error};
 use std::fs;
@@ -10,7 +15,8 @@ use std::sync::Arc;
 
 use super::models::{
     ApiResponse, ApiState, CalendarResponse, CreateEventRequest, CreateNoteRequest,
-    CreateTodoRequest, NoteResponse, StatusResponse, TodoResponse,
+    CreateTodoRequest, EventRangeQuery, NoteResponse, StatusResponse, TodoResponse,
+    UpdateNoteRequest,
 };
 
 /// Handle health check requests
@@ -127,19 +133,124 @@ pub async fn create_calendar_event(Json(payload): Json<CreateEventRequest>) -> i
     }
 }
 
+/// Build an error response with an `ApiResponse` body
+fn error_response(status: StatusCode, message: String) -> Response {
+    error!("{}", message);
+    let response = ApiResponse { success: false, message, data: None };
+    (status, Json(response)).into_response()
+}
+
+/// List calendar events grouped by day
+///
+/// Returns the agenda for the requested range in the `data` field
+pub async fn list_calendar_events(Query(query): Query<EventRangeQuery>) -> Response {
+    debug!("List events request: {:?}", query);
+
+    let (from, to) = match query.date_range(6) {
+        Ok(range) => range,
+        Err(e) => {
+            return error_response(StatusCode::BAD_REQUEST, format!("Invalid date range: {}", e));
+        }
+    };
+    let agenda = match crate::calendar::get_agenda(&query.calendars(), from, to).await {
+        Ok(agenda) => agenda,
+        Err(e) => {
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list events: {}", e),
+            );
+        }
+    };
+    match serde_json::to_value(&agenda) {
+        Ok(data) => {
+            let response = ApiResponse {
+                success: true,
+                message: format!("Found {} event(s)", agenda.event_count()),
+                data: Some(data),
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to serialize events: {}", e),
+        ),
+    }
+}
+
+/// Export calendar events as iCalendar data
+///
+/// Returns a `text/calendar` body with every event in the requested range,
+/// including recurring series that started before it
+pub async fn export_calendar_events(Query(query): Query<EventRangeQuery>) -> Response {
+    debug!("Export events request: {:?}", query);
+
+    let (from, to) = match query.date_range(30) {
+        Ok(range) => range,
+        Err(e) => {
+            return error_response(StatusCode::BAD_REQUEST, format!("Invalid date range: {}", e));
+        }
+    };
+    let ics = crate::calendar::export_events(&query.calendars(), from, to)
+        .await
+        .and_then(|events| crate::calendar::events_to_ics(&events));
+    match ics {
+        Ok(ics) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+                (header::CONTENT_DISPOSITION, "attachment; filename=\"ducktape.ics\""),
+            ],
+            ics,
+        )
+            .into_response(),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to export events: {}", e),
+        ),
+    }
+}
+
 /// Create a new todo item
 ///
 /// Creates a todo in Reminders.app
 pub async fn create_todo(Json(payload): Json<CreateTodoRequest>) -> impl IntoResponse {
     debug!("Create todo request: {:?}", payload);
 
-    // This is a stub - would connect to actual todo module
-    let response = TodoResponse {
-        success: true,
-        message: format!("Todo '{}' created successfully", payload.title),
+    let respond = |status: StatusCode, message: String| {
+        if !status.is_success() {
+            error!("{}", message);
+        }
+        (status, Json(TodoResponse { success: status.is_success(), message }))
     };
 
-    (StatusCode::CREATED, Json(response))
+    let mut config = crate::reminder::ReminderConfig::new(&payload.title);
+    if let Some(lists) = &payload.lists {
+        config.lists = lists.iter().map(String::as_str).collect();
+    }
+    config.reminder_time = payload.reminder_time.as_deref();
+    config.notes = payload.notes.clone();
+    config.due_date = payload.due_date.as_deref();
+    config.priority = payload.priority;
+    config.flagged = payload.flagged;
+    if let Some(repeat) = &payload.repeat {
+        match crate::reminder::parse_reminder_recurrence(repeat) {
+            Ok(pattern) => config.recurrence = Some(pattern),
+            Err(e) => return respond(StatusCode::BAD_REQUEST, format!("Invalid repeat: {}", e)),
+        }
+    }
+
+    match crate::reminder::create_reminder(config).await {
+        Ok(_) => {
+            respond(StatusCode::CREATED, format!("Todo '{}' created successfully", payload.title))
+        }
+        Err(e) => {
+            let status = match e.downcast_ref::<crate::reminder::ReminderError>() {
+                Some(crate::reminder::ReminderError::InvalidInput(_)) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            respond(status, format!("Failed to create todo: {}", e))
+        }
+    }
 }
 
 /// Create a new note
@@ -157,6 +268,46 @@ pub async fn create_note(Json(payload): Json<CreateNoteRequest>) -> impl IntoRes
     (StatusCode::CREATED, Json(response))
 }
 
+/// Update a note
+///
+/// Replaces the body of the note with the given Notes.app ID, or appends to it
+pub async fn update_note(
+    Path(id): Path<String>,
+    Json(payload): Json<UpdateNoteRequest>,
+) -> impl IntoResponse {
+    debug!("Update note request for {}: {:?}", id, payload);
+
+    let respond = |status: StatusCode, message: String| {
+        if !status.is_success() {
+            error!("{}", message);
+        }
+        (status, Json(NoteResponse { success: status.is_success(), message }))
+    };
+
+    let mut update = if payload.append {
+        crate::notes::NoteUpdate::append(&payload.content)
+    } else {
+        crate::notes::NoteUpdate::replace(&payload.content)
+    };
+    if let Some(title) = &payload.title {
+        update = update.with_title(title);
+    }
+    if let Err(e) = crate::notes::validate_note_update(&update) {
+        return respond(StatusCode::BAD_REQUEST, format!("Invalid note update: {}", e));
+    }
+
+    match crate::notes::update_note(&id, update).await {
+        Ok(_) => respond(StatusCode::OK, format!("Note '{}' updated successfully", id)),
+        Err(e) => {
+            let status = match e.downcast_ref::<crate::notes::NotesError>() {
+                Some(crate::notes::NotesError::NoteNotFound(_)) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            respond(status, format!("Failed to update note: {}", e))
+        }
+    }
+}
+
 /// Serve the OpenAPI documentation
 ///
 /// Returns the OpenAPI JSON specification
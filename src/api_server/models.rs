@@ -2,9 +2,12 @@
 //
 // This module contains data structures for API requests and responses.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::preview::PreviewField;
+use crate::reminder::ReminderPriority;
+
 /// Shared application state for the API server
 #[derive(Clone)]
 pub struct ApiState {
@@ -86,8 +89,39 @@ pub struct CreateEventRequest {
     pub create_zoom_meeting: Option<bool>,
 }
 
+/// Query parameters for listing or exporting calendar events
+#[derive(Deserialize, Debug)]
+pub struct EventRangeQuery {
+    /// First day to include (YYYY-MM-DD, today or tomorrow); defaults to today
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Last day to include; defaults to a week (listing) or 30 days (export) after `from`
+    #[serde(default)]
+    pub to: Option<String>,
+    /// Comma-separated calendar names; every calendar when omitted
+    #[serde(default)]
+    pub calendars: Option<String>,
+}
+
+impl EventRangeQuery {
+    /// Resolve the requested range, defaulting `to` to `default_days` after `from`
+    pub fn date_range(&self, default_days: i64) -> anyhow::Result<(NaiveDate, NaiveDate)> {
+        crate::calendar::resolve_date_range(self.from.as_deref(), self.to.as_deref(), default_days)
+    }
+
+    /// Requested calendar names; empty means every calendar
+    pub fn calendars(&self) -> Vec<String> {
+        self.calendars
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect()
+    }
+}
+
 /// Create todo request
-#[allow(dead_code)]
 #[derive(Deserialize, Debug)]
 pub struct CreateTodoRequest {
     /// Todo item title
@@ -101,6 +135,18 @@ pub struct CreateTodoRequest {
     /// Additional notes
     #[serde(default)]
     pub notes: Option<String>,
+    /// Due date in YYYY-MM-DD or YYYY-MM-DD HH:MM format
+    #[serde(default)]
+    pub due_date: Option<String>,
+    /// Priority: low, medium or high
+    #[serde(default)]
+    pub priority: Option<ReminderPriority>,
+    /// Whether to flag the todo item
+    #[serde(default)]
+    pub flagged: bool,
+    /// Repeat rule, e.g. "daily", "every 2 weeks" or "every Monday"
+    #[serde(default)]
+    pub repeat: Option<String>,
 }
 
 /// Todo response
@@ -125,6 +171,19 @@ pub struct CreateNoteRequest {
     pub folder: Option<String>,
 }
 
+/// Update note request
+#[derive(Deserialize, Debug)]
+pub struct UpdateNoteRequest {
+    /// New note title
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Note content
+    pub content: String,
+    /// Append the content to the note instead of replacing it
+    #[serde(default)]
+    pub append: bool,
+}
+
 /// Note response
 #[derive(Serialize)]
 pub struct NoteResponse {
@@ -197,6 +256,37 @@ pub struct SwiftEventResponse {
     pub event_id: Option<String>,
 }
 
+/// WebSocket request to confirm a command generated from natural language
+#[derive(Debug, Serialize)]
+pub struct SwiftConfirmRequest {
+    /// Message type (always "confirm")
+    pub message_type: String,
+    /// Id the client sends back in its reply
+    pub confirmation_id: String,
+    /// Readable preview of the command
+    pub content: String,
+    /// Equivalent ducktape command line
+    pub command: String,
+    /// Fields the client may edit
+    pub fields: Vec<PreviewField>,
+    /// Event time in the time zone of the request, if it named one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_time: Option<String>,
+}
+
+/// Data of a client's "confirm" message (action "accept", "edit" or "cancel")
+#[derive(Debug, Deserialize)]
+pub struct SwiftConfirmReply {
+    /// Id from the confirm request being answered
+    pub confirmation_id: String,
+    /// Field to change when editing
+    #[serde(default)]
+    pub field: Option<String>,
+    /// New value of the field; empty clears optional fields
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
 /// WebSocket error response
 #[derive(Debug, Serialize)]
 pub struct SwiftErrorResponse {
@@ -6,7 +6,7 @@
 use axum::{
     Router,
     http::Method,
-    routing::{get, post},
+    routing::{get, post, put},
 };
 use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
@@ -19,7 +19,7 @@ use super::websocket::websocket_handler;
 pub fn create_routes(state: Arc<ApiState>) -> Router {
     // Configure CORS for web and mobile clients
     let cors = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::OPTIONS])
         .allow_headers(Any)
         .allow_origin(Any);
 
@@ -32,10 +32,13 @@ pub fn create_routes(state: Arc<ApiState>) -> Router {
         // Calendar APIs
         .route("/calendars", get(handlers::list_calendars))
         .route("/calendar/event", post(handlers::create_calendar_event))
+        .route("/calendar/events", get(handlers::list_calendar_events))
+        .route("/calendar/export", get(handlers::export_calendar_events))
         // Todo API
         .route("/todo", post(handlers::create_todo))
         // Notes API
         .route("/note", post(handlers::create_note))
+        .route("/note/:id", put(handlers::update_note))
         // WebSocket endpoint for real-time communications
         .route("/chat", get(websocket_handler))
         // API docs
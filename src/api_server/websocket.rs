@@ -3,6 +3,10 @@
 // This module provides WebSocket functionality for the API server,
 // allowing real-time commands and notifications.
 
+// Commands reach CommandProcessor as CommandArgs, deprecated in favour of the
+// parser module.
+#![allow(deprecated)]
+
 use axum::{
     extract::WebSocketUpgrade,
     extract::ws::{Message, WebSocket},
@@ -15,17 +19,48 @@ use std::time::Duration;
 use tokio::time::interval;
 use uuid::Uuid;
 
-use crate::calendar::{EventConfig, create_event, import_csv_events, import_ics_events};
+use crate::calendar::{
+    CsvImportOptions, EventConfig, create_event, import_csv_events, import_ics_events,
+};
 use crate::cli;
 use crate::command_processor::CommandArgs;
 use crate::parser::ParseResult;
+use crate::parser::natural_language::context::ConversationContext;
+use crate::preview::{self, CommandPreview};
 use anyhow::{Result, anyhow};
 use std::path::Path;
 
 use super::models::{
-    SwiftChatMessage, SwiftErrorResponse, SwiftEventData, SwiftEventResponse, SwiftMessage,
+    SwiftChatMessage, SwiftConfirmReply, SwiftConfirmRequest, SwiftErrorResponse, SwiftEventData,
+    SwiftEventResponse, SwiftMessage,
 };
 
+/// State kept for one WebSocket connection
+#[derive(Debug, Default)]
+struct Session {
+    /// Command from natural language waiting for the client's confirmation
+    pending: Option<PendingCommand>,
+    /// What the connection has done, for follow-ups like "move it to 4pm"
+    context: ConversationContext,
+}
+
+/// A command generated from natural language, waiting for the client to confirm it
+#[derive(Debug)]
+struct PendingCommand {
+    id: Uuid,
+    /// The request the command was generated from
+    input: String,
+    args: CommandArgs,
+}
+
+/// What to do after the client answers a confirm request
+#[derive(Debug)]
+enum Confirmation {
+    Run(PendingCommand),
+    Preview(SwiftConfirmRequest),
+    Cancelled,
+}
+
 /// WebSocket handler for chat interface
 ///
 /// Upgrades an HTTP request to a WebSocket connection
@@ -55,6 +90,8 @@ async fn handle_socket(mut socket: WebSocket) {
         }
     }
 
+    let mut session = Session::default();
+
     // Set up a heartbeat timer using socket.ping()
     let mut interval = interval(Duration::from_secs(45));
 
@@ -76,7 +113,7 @@ async fn handle_socket(mut socket: WebSocket) {
                         info!("WebSocket[{}]: Received text message ({} bytes)", connection_id, text.len());
                         debug!("WebSocket[{}]: Message content: {}", connection_id, text);
 
-                        process_message(connection_id, text, &mut socket).await;
+                        process_message(connection_id, text, &mut socket, &mut session).await;
                     },
                     Some(Ok(Message::Binary(bin))) => {
                         info!("WebSocket[{}]: Received binary message of {} bytes", connection_id, bin.len());
@@ -84,7 +121,7 @@ async fn handle_socket(mut socket: WebSocket) {
                         match String::from_utf8(bin) {
                             Ok(text) => {
                                 debug!("WebSocket[{}]: Decoded binary content: {}", connection_id, text);
-                                process_message(connection_id, text, &mut socket).await;
+                                process_message(connection_id, text, &mut socket, &mut session).await;
                             },
                             Err(e) => {
                                 error!("WebSocket[{}]: Failed to decode binary as UTF-8: {}", connection_id, e);
@@ -133,7 +170,12 @@ async fn handle_socket(mut socket: WebSocket) {
 /// Process received WebSocket messages
 ///
 /// Handles both natural language commands and structured JSON messages
-async fn process_message(connection_id: Uuid, message: String, socket: &mut WebSocket) {
+async fn process_message(
+    connection_id: Uuid,
+    message: String,
+    socket: &mut WebSocket,
+    session: &mut Session,
+) {
     match serde_json::from_str::<SwiftMessage>(&message) {
         Ok(swift_message) => {
             // Check if it's a chat message with natural language command
@@ -144,6 +186,14 @@ async fn process_message(connection_id: Uuid, message: String, socket: &mut WebS
                 if is_command_message(&content) {
                     info!("WebSocket[{}]: Processing as DuckTape command", connection_id);
 
+                    // Clients can send `"data": {"yes": true}` to run without confirming
+                    let skip_confirmation = swift_message
+                        .data
+                        .as_ref()
+                        .and_then(|data| data.get("yes"))
+                        .and_then(serde_json::Value::as_bool)
+                        .unwrap_or(false);
+
                     // Create a parser using the factory instead of directly using OpenAI parser
                     let parser = match crate::parser::ParserFactory::create_parser() {
                         Ok(parser) => parser,
@@ -160,15 +210,32 @@ async fn process_message(connection_id: Uuid, message: String, socket: &mut WebS
                         }
                     };
 
-                    // Parse the input using the configured parser
-                    match parser.parse_input(&content).await {
+                    // Parse the input using the configured parser, with this
+                    // connection's earlier requests for follow-ups
+                    match parser.parse_input_with_context(&content, &session.context).await {
                         Ok(ParseResult::CommandString(command)) => {
                             info!("WebSocket[{}]: Parsed command: {}", connection_id, command);
-                            handle_parsed_command(connection_id, command, socket).await;
+                            handle_parsed_command(
+                                connection_id,
+                                &content,
+                                command,
+                                skip_confirmation,
+                                session,
+                                socket,
+                            )
+                            .await;
                         }
                         Ok(ParseResult::StructuredCommand(args)) => {
                             info!("WebSocket[{}]: Got structured command directly", connection_id);
-                            handle_websocket_command(connection_id, args, socket).await;
+                            run_or_confirm(
+                                connection_id,
+                                &content,
+                                args,
+                                skip_confirmation,
+                                session,
+                                socket,
+                            )
+                            .await;
                         }
                         Err(e) => {
                             error!("WebSocket[{}]: Failed to parse command: {}", connection_id, e);
@@ -198,6 +265,8 @@ async fn process_message(connection_id: Uuid, message: String, socket: &mut WebS
                 // Check if it's an event creation request
                 if message_type == "create" && action == "event" {
                     handle_event_creation(connection_id, data.clone(), socket).await;
+                } else if message_type == "confirm" {
+                    handle_confirmation(connection_id, action, data.clone(), session, socket).await;
                 } else {
                     // If we got here, it's an unknown message type
                     error!("WebSocket[{}]: Unknown message format", connection_id);
@@ -270,7 +339,14 @@ async fn handle_event_creation(
 }
 
 /// Handle parsed commands from natural language input
-async fn handle_parsed_command(connection_id: Uuid, command: String, socket: &mut WebSocket) {
+async fn handle_parsed_command(
+    connection_id: Uuid,
+    input: &str,
+    command: String,
+    skip_confirmation: bool,
+    session: &mut Session,
+    socket: &mut WebSocket,
+) {
     // Parse the command into arguments using Clap first
     match parse_command_string(&command) {
         Ok(args) => {
@@ -280,7 +356,7 @@ async fn handle_parsed_command(connection_id: Uuid, command: String, socket: &mu
                 connection_id, args.command, args.args, args.flags
             );
 
-            handle_websocket_command(connection_id, args, socket).await;
+            run_or_confirm(connection_id, input, args, skip_confirmation, session, socket).await;
         }
         Err(_) => {
             // Fall back to legacy parser if Clap fails
@@ -291,7 +367,8 @@ async fn handle_parsed_command(connection_id: Uuid, command: String, socket: &mu
                         connection_id, args.command, args.args, args.flags
                     );
 
-                    handle_websocket_command(connection_id, args, socket).await;
+                    run_or_confirm(connection_id, input, args, skip_confirmation, session, socket)
+                        .await;
                 }
                 Err(e) => {
                     error!(
@@ -314,6 +391,125 @@ async fn handle_parsed_command(connection_id: Uuid, command: String, socket: &mu
     }
 }
 
+/// Run a command generated from natural language, or keep it pending and ask
+/// the client to confirm it first
+async fn run_or_confirm(
+    connection_id: Uuid,
+    input: &str,
+    args: CommandArgs,
+    skip_confirmation: bool,
+    session: &mut Session,
+    socket: &mut WebSocket,
+) {
+    if skip_confirmation {
+        run_command(connection_id, input, args, &mut session.context, socket).await;
+        return;
+    }
+
+    let command = PendingCommand { id: Uuid::new_v4(), input: input.to_string(), args };
+    info!("WebSocket[{}]: Asking client to confirm command {}", connection_id, command.id);
+    send_response(socket, confirm_request(&command)).await;
+    // A new request replaces any command still waiting for an answer
+    session.pending = Some(command);
+}
+
+/// Run a command and remember it for follow-up requests on this connection
+async fn run_command(
+    connection_id: Uuid,
+    input: &str,
+    args: CommandArgs,
+    context: &mut ConversationContext,
+    socket: &mut WebSocket,
+) {
+    if handle_websocket_command(connection_id, args.clone(), socket).await {
+        context.record(input, &args);
+    }
+}
+
+/// Handle the client's answer to a confirm request
+async fn handle_confirmation(
+    connection_id: Uuid,
+    action: &str,
+    data: serde_json::Value,
+    session: &mut Session,
+    socket: &mut WebSocket,
+) {
+    let reply = match serde_json::from_value::<SwiftConfirmReply>(data) {
+        Ok(reply) => reply,
+        Err(e) => {
+            send_error_response(socket, &format!("Invalid confirm data format: {}", e)).await;
+            return;
+        }
+    };
+
+    match apply_confirmation(&mut session.pending, action, reply) {
+        Ok(Confirmation::Run(command)) => {
+            info!("WebSocket[{}]: Command confirmed", connection_id);
+            run_command(connection_id, &command.input, command.args, &mut session.context, socket)
+                .await;
+        }
+        Ok(Confirmation::Preview(request)) => send_response(socket, request).await,
+        Ok(Confirmation::Cancelled) => {
+            info!("WebSocket[{}]: Command cancelled", connection_id);
+            let response = SwiftChatMessage {
+                sender: "ducktape".to_string(),
+                content: "Cancelled".to_string(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                message_type: "chat".to_string(),
+            };
+            send_response(socket, response).await;
+        }
+        Err(e) => {
+            error!("WebSocket[{}]: Invalid confirmation: {}", connection_id, e);
+            send_error_response(socket, &e.to_string()).await;
+        }
+    }
+}
+
+/// Apply an "accept", "edit" or "cancel" reply to the pending command
+fn apply_confirmation(
+    pending: &mut Option<PendingCommand>,
+    action: &str,
+    reply: SwiftConfirmReply,
+) -> Result<Confirmation> {
+    let Some(command) = pending.as_mut() else {
+        return Err(anyhow!("There is no command waiting for confirmation"));
+    };
+    if command.id.to_string() != reply.confirmation_id {
+        return Err(anyhow!("Unknown confirmation id '{}'", reply.confirmation_id));
+    }
+
+    match action {
+        "accept" => pending
+            .take()
+            .map(Confirmation::Run)
+            .ok_or_else(|| anyhow!("There is no command waiting for confirmation")),
+        "cancel" => {
+            *pending = None;
+            Ok(Confirmation::Cancelled)
+        }
+        "edit" => {
+            let field = reply.field.ok_or_else(|| anyhow!("Edits need a field"))?;
+            preview::edit(&mut command.args, &field, reply.value.as_deref().unwrap_or_default())?;
+            Ok(Confirmation::Preview(confirm_request(command)))
+        }
+        other => Err(anyhow!("Unknown confirm action '{}': use accept, edit or cancel", other)),
+    }
+}
+
+/// Build the confirm request the client is sent for `command`
+fn confirm_request(command: &PendingCommand) -> SwiftConfirmRequest {
+    let preview = CommandPreview::new(&command.args);
+    SwiftConfirmRequest {
+        message_type: "confirm".to_string(),
+        confirmation_id: command.id.to_string(),
+        content: preview.to_string(),
+        command: preview.command,
+        fields: preview.fields,
+        original_time: preview.original_time,
+    }
+}
+
 /// Check if a message looks like a command
 fn is_command_message(message: &str) -> bool {
     // Simple heuristic: any message with action words is a command
@@ -418,17 +614,28 @@ fn parse_command_string(input: &str) -> Result<CommandArgs, anyhow::Error> {
         .ok_or_else(|| anyhow!("Failed to convert parsed command to CommandArgs"))
 }
 
-/// Function to handle websocket commands
-async fn handle_websocket_command(connection_id: Uuid, args: CommandArgs, socket: &mut WebSocket) {
+/// Build CSV import options from the configured mapping and any --map flag
+fn csv_import_options(args: &CommandArgs) -> Result<CsvImportOptions> {
+    let mut options = CsvImportOptions::from_config(&crate::config::Config::load()?)?;
+    if let Some(Some(spec)) = args.flags.get("map") {
+        options = options.with_mapping_spec(spec.trim_matches('"'))?;
+    }
+    Ok(options
+        .with_dry_run(args.flags.contains_key("dry_run") || args.flags.contains_key("dry-run")))
+}
+
+/// Function to handle websocket commands, returning whether the command succeeded
+async fn handle_websocket_command(
+    connection_id: Uuid,
+    args: CommandArgs,
+    socket: &mut WebSocket,
+) -> bool {
     if args.command == "calendar" {
         // Handle different calendar subcommands
         match args.args.get(0).map(|s| s.as_str()) {
-            Some("create") => {
-                handle_calendar_create(connection_id, args, socket).await;
-            }
-            Some("import") => {
-                handle_calendar_import(connection_id, args, socket).await;
-            }
+            Some("create") => handle_calendar_create(connection_id, args, socket).await,
+            Some("update") => handle_calendar_update(connection_id, args, socket).await,
+            Some("import") => handle_calendar_import(connection_id, args, socket).await,
             Some(cmd) => {
                 // Handle other calendar commands (list, delete, etc.)
                 let response = SwiftChatMessage {
@@ -441,6 +648,7 @@ async fn handle_websocket_command(connection_id: Uuid, args: CommandArgs, socket
                     message_type: "chat".to_string(),
                 };
                 send_response(socket, response).await;
+                false
             }
             None => {
                 let response = SwiftChatMessage {
@@ -450,6 +658,7 @@ async fn handle_websocket_command(connection_id: Uuid, args: CommandArgs, socket
                     message_type: "error".to_string(),
                 };
                 send_response(socket, response).await;
+                false
             }
         }
     } else {
@@ -464,11 +673,16 @@ async fn handle_websocket_command(connection_id: Uuid, args: CommandArgs, socket
             message_type: "chat".to_string(),
         };
         send_response(socket, response).await;
+        false
     }
 }
 
 /// Handle calendar create command
-async fn handle_calendar_create(connection_id: Uuid, args: CommandArgs, socket: &mut WebSocket) {
+async fn handle_calendar_create(
+    connection_id: Uuid,
+    args: CommandArgs,
+    socket: &mut WebSocket,
+) -> bool {
     // Skip "create" (which is args[0]) and process the rest of the args
     if args.args.len() >= 4 {
         // Needs at least title, date, start_time
@@ -534,6 +748,7 @@ async fn handle_calendar_create(connection_id: Uuid, args: CommandArgs, socket:
                     message_type: "chat".to_string(),
                 };
                 send_response(socket, response).await;
+                true
             }
             Err(e) => {
                 error!("WebSocket[{}]: Failed to create event: {}", connection_id, e);
@@ -544,6 +759,7 @@ async fn handle_calendar_create(connection_id: Uuid, args: CommandArgs, socket:
                     message_type: "error".to_string(),
                 };
                 send_response(socket, response).await;
+                false
             }
         }
     } else {
@@ -555,11 +771,117 @@ async fn handle_calendar_create(connection_id: Uuid, args: CommandArgs, socket:
             message_type: "error".to_string(),
         };
         send_response(socket, response).await;
+        false
+    }
+}
+
+/// Handle calendar update command for an event given by title and date
+async fn handle_calendar_update(
+    connection_id: Uuid,
+    args: CommandArgs,
+    socket: &mut WebSocket,
+) -> bool {
+    match update_event_from_args(&args).await {
+        Ok(updated) => {
+            info!("WebSocket[{}]: Event updated: {}", connection_id, updated.title);
+            let mut content = format!(
+                "✅ Updated event \"{}\": {} {}-{}",
+                updated.title,
+                updated.start_date,
+                updated.start_time,
+                updated.end_time.as_deref().unwrap_or("?")
+            );
+            if !updated.emails.is_empty() {
+                content.push_str(&format!("\nAttendees: {}", updated.emails.join(", ")));
+            }
+            let response = SwiftChatMessage {
+                sender: "ducktape".to_string(),
+                content,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                message_type: "chat".to_string(),
+            };
+            send_response(socket, response).await;
+            true
+        }
+        Err(e) => {
+            error!("WebSocket[{}]: Failed to update event: {}", connection_id, e);
+            let response = SwiftChatMessage {
+                sender: "ducktape".to_string(),
+                content: format!("❌ Failed to update event: {}", e),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                message_type: "error".to_string(),
+            };
+            send_response(socket, response).await;
+            false
+        }
+    }
+}
+
+/// Find the one event matching the title and date in `args` and apply the update flags
+async fn update_event_from_args(args: &CommandArgs) -> Result<crate::calendar::EventConfig> {
+    let (Some(title), Some(date)) = (args.args.get(1), args.args.get(2)) else {
+        return Err(anyhow!("Give the title and date of the event to update"));
+    };
+    let title = title.trim_matches('"');
+    let date = crate::calendar::parse_date_argument(date.trim_matches('"'))?;
+    let flag = |name: &str| args.flags.get(name).cloned().flatten();
+    let list = |name: &str| -> Vec<String> {
+        flag(name)
+            .map(|list| {
+                list.split(',')
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let matches = crate::calendar::find_events_by_title(title, date, &list("calendar")).await?;
+    let event = match matches.as_slice() {
+        [event] => event,
+        [] => return Err(anyhow!("No event titled '{}' found on {}", title, date)),
+        _ => {
+            return Err(anyhow!(
+                "{} events titled '{}' on {}; update one with `ducktape calendar update --index`",
+                matches.len(),
+                title,
+                date
+            ));
+        }
+    };
+    let uid = event
+        .uid
+        .as_deref()
+        .ok_or_else(|| anyhow!("Event '{}' has no UID and cannot be updated", event.title))?;
+
+    let mut add_emails = list("add_email");
+    let contacts = list("add_contacts");
+    if !contacts.is_empty() {
+        let names: Vec<&str> = contacts.iter().map(String::as_str).collect();
+        add_emails.extend(crate::calendar::lookup_contact_emails(&names).await);
     }
+    let new_date = match flag("date") {
+        Some(date) => {
+            Some(crate::calendar::parse_date_argument(&date)?.format("%Y-%m-%d").to_string())
+        }
+        None => None,
+    };
+    let update = crate::calendar::EventUpdate {
+        date: new_date,
+        start_time: flag("start"),
+        end_time: flag("end"),
+        location: flag("location"),
+        add_emails,
+    };
+    crate::calendar::update_event_by_uid(uid, &update).await
 }
 
 /// Handle calendar import command
-async fn handle_calendar_import(connection_id: Uuid, args: CommandArgs, socket: &mut WebSocket) {
+async fn handle_calendar_import(
+    connection_id: Uuid,
+    args: CommandArgs,
+    socket: &mut WebSocket,
+) -> bool {
     info!("WebSocket[{}]: Processing calendar import command", connection_id);
 
     if args.args.len() < 2 {
@@ -570,7 +892,7 @@ async fn handle_calendar_import(connection_id: Uuid, args: CommandArgs, socket:
             message_type: "error".to_string(),
         };
         send_response(socket, response).await;
-        return;
+        return false;
     }
 
     // Get the file path and expand it if needed
@@ -594,7 +916,7 @@ async fn handle_calendar_import(connection_id: Uuid, args: CommandArgs, socket:
             message_type: "error".to_string(),
         };
         send_response(socket, response).await;
-        return;
+        return false;
     }
 
     // Get format from --format flag, default to csv
@@ -613,7 +935,7 @@ async fn handle_calendar_import(connection_id: Uuid, args: CommandArgs, socket:
             message_type: "error".to_string(),
         };
         send_response(socket, response).await;
-        return;
+        return false;
     }
 
     // Get target calendar if specified
@@ -630,20 +952,40 @@ async fn handle_calendar_import(connection_id: Uuid, args: CommandArgs, socket:
 
     // Call the appropriate import function
     let result = match format.as_str() {
-        "csv" => import_csv_events(file_path, calendar).await,
+        "csv" => match csv_import_options(&args) {
+            Ok(options) => import_csv_events(file_path, calendar, &options).await,
+            Err(e) => Err(e),
+        },
         "ics" => import_ics_events(file_path, calendar).await,
         _ => unreachable!(),
     };
 
     match result {
-        Ok(_) => {
+        Ok(report) => {
+            let mut content = format!(
+                "✅ {} {} of {} events from {}",
+                if report.dry_run { "Would import" } else { "Imported" },
+                report.imported.len(),
+                report.total(),
+                file_path_str
+            );
+            for failure in &report.failed {
+                content.push_str(&format!(
+                    "\n❌ Failed {} {} ({}): {}",
+                    report.position_unit(),
+                    failure.position,
+                    failure.title.as_deref().unwrap_or("untitled"),
+                    failure.reason
+                ));
+            }
             let response = SwiftChatMessage {
                 sender: "ducktape".to_string(),
-                content: format!("✅ Successfully imported events from {}", file_path_str),
+                content,
                 timestamp: chrono::Utc::now().to_rfc3339(),
                 message_type: "chat".to_string(),
             };
             send_response(socket, response).await;
+            true
         }
         Err(e) => {
             error!("WebSocket[{}]: Failed to import events: {}", connection_id, e);
@@ -654,6 +996,86 @@ async fn handle_calendar_import(connection_id: Uuid, args: CommandArgs, socket:
                 message_type: "error".to_string(),
             };
             send_response(socket, response).await;
+            false
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn pending_event() -> Option<PendingCommand> {
+        let args = ["create", "Budget review", "2025-06-03", "15:00", "16:00"];
+        let args = CommandArgs::new(
+            "calendar".to_string(),
+            args.map(String::from).to_vec(),
+            HashMap::new(),
+        );
+        Some(PendingCommand { id: Uuid::new_v4(), input: "budget review at 3".to_string(), args })
+    }
+
+    fn reply(id: &str, field: Option<&str>, value: &str) -> SwiftConfirmReply {
+        SwiftConfirmReply {
+            confirmation_id: id.to_string(),
+            field: field.map(String::from),
+            value: Some(value.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_edit_then_accept() -> Result<()> {
+        let mut pending = pending_event();
+        let id = pending.as_ref().unwrap().id.to_string();
+
+        let Confirmation::Preview(request) =
+            apply_confirmation(&mut pending, "edit", reply(&id, Some("start"), "15:30"))?
+        else {
+            panic!("Expected a new preview after an edit");
+        };
+        assert_eq!(request.message_type, "confirm");
+        assert!(request.command.contains(" 15:30 16:00"));
+        assert!(request.content.contains("Start:     15:30"));
+
+        let Confirmation::Run(command) =
+            apply_confirmation(&mut pending, "accept", reply(&id, None, ""))?
+        else {
+            panic!("Expected the command to run");
+        };
+        assert_eq!(command.args.args[3], "15:30");
+        assert!(pending.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejected_replies_keep_the_command() {
+        let mut pending = pending_event();
+        let id = pending.as_ref().unwrap().id.to_string();
+
+        let wrong_id = SwiftConfirmReply {
+            confirmation_id: Uuid::new_v4().to_string(),
+            field: None,
+            value: None,
+        };
+        assert!(apply_confirmation(&mut pending, "accept", wrong_id).is_err());
+        assert!(apply_confirmation(&mut pending, "edit", reply(&id, Some("end"), "5pm")).is_err());
+        assert!(apply_confirmation(&mut pending, "maybe", reply(&id, None, "")).is_err());
+        assert!(pending.is_some());
+
+        let cancel = reply(&id, None, "");
+        assert!(matches!(
+            apply_confirmation(&mut pending, "cancel", cancel),
+            Ok(Confirmation::Cancelled)
+        ));
+        assert!(pending.is_none());
+        assert!(
+            apply_confirmation(
+                &mut None,
+                "accept",
+                SwiftConfirmReply { confirmation_id: String::new(), field: None, value: None }
+            )
+            .is_err()
+        );
+    }
+}
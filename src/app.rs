@@ -1,17 +1,33 @@
 use crate::command_processor::{CommandArgs, CommandProcessor};
 use crate::config::{Config, LLMProvider};
 use crate::parser::ParserFactory;
+use crate::parser::natural_language::context::ConversationContext;
 use anyhow::{Result, anyhow};
 use clap::Parser as ClapParser;
 use rustyline::DefaultEditor;
+use std::io::IsTerminal;
+use std::sync::Mutex;
 
 pub struct Application {
     command_processor: CommandProcessor,
+    assume_yes: bool,
+    /// What this session has done, for follow-up requests like "move it to 4pm"
+    context: Mutex<ConversationContext>,
 }
 
 impl Application {
     pub fn new() -> Self {
-        Self { command_processor: CommandProcessor::new() }
+        Self {
+            command_processor: CommandProcessor::new(),
+            assume_yes: false,
+            context: Mutex::new(ConversationContext::new()),
+        }
+    }
+
+    /// Run commands generated from natural language without asking first
+    pub fn with_assume_yes(mut self, assume_yes: bool) -> Self {
+        self.assume_yes = assume_yes;
+        self
     }
 
     pub async fn run(&self) -> Result<()> {
@@ -32,6 +48,12 @@ impl Application {
             Some(LLMProvider::DeepSeek) => {
                 log::info!("Using DeepSeek for natural language processing");
             }
+            Some(LLMProvider::OpenAICompatible) => {
+                log::info!(
+                    "Using OpenAI-compatible endpoint {} for natural language processing",
+                    config.language_model.base_url.as_deref().unwrap_or("(not set)")
+                );
+            }
             None => {
                 log::info!("Terminal Mode enabled (no API key required)");
             }
@@ -126,7 +148,7 @@ impl Application {
         Ok(())
     }
 
-    async fn process_input(&self, input: &str, _use_natural_language: bool) -> Result<()> {
+    async fn process_input(&self, input: &str, use_natural_language: bool) -> Result<()> {
         log::debug!("Hybrid input mode: auto-detecting command vs natural language");
 
         // Check for direct exit command regardless of mode
@@ -145,29 +167,8 @@ impl Application {
             return self.command_processor.execute(command_args).await;
         }
 
-        // List of known DuckTape commands (expanded from README)
-        const KNOWN_COMMANDS: &[&str] = &[
-            "calendar",
-            "reminder",
-            "reminders",
-            "todo",
-            "todos",
-            "note",
-            "config",
-            "contact",
-            "help",
-            "exit",
-            "version",
-            "--help",
-            "--version",
-        ];
-        let trimmed = preprocessed.trim_start();
-        let is_direct_command = KNOWN_COMMANDS.iter().any(|cmd| {
-            trimmed.starts_with(cmd) || trimmed.starts_with(&format!("ducktape {}", cmd))
-        });
-
-        if is_direct_command {
-            log::info!("Detected direct DuckTape command: {}", trimmed);
+        if is_direct_command(&preprocessed, use_natural_language) {
+            log::info!("Detected direct DuckTape command: {}", preprocessed.trim_start());
             return self.process_command(input).await;
         }
 
@@ -190,47 +191,17 @@ impl Application {
         // Preprocess the input for normalization
         let preprocessed_input = crate::command_processor::preprocess_input(input);
 
-        if Config::load()?.language_model.provider.is_none() {
-            log::info!("Terminal Mode: Direct command processing only");
-            // Try to parse with Clap first
-            let command_args = match self.parse_command_string(&preprocessed_input) {
-                Ok(args) => args,
-                Err(_) => {
-                    // Fall back to the legacy parser if Clap parsing fails
-                    // This is useful for backward compatibility
-                    CommandArgs::parse(&preprocessed_input)?
-                }
-            };
-            return self.command_processor.execute(command_args).await;
-        }
-
-        // Create appropriate parser using factory
-        let parser = ParserFactory::create_parser()?;
-
-        // Process input through parser
-        match parser.parse_input(&preprocessed_input).await? {
-            crate::parser::ParseResult::CommandString(cmd) => {
-                log::debug!("Processed command string: {}", cmd);
-
-                // Try to parse with Clap first
-                let command_args = match self.parse_command_string(&cmd) {
-                    Ok(args) => args,
-                    Err(_) => {
-                        // Fall back to legacy parser
-                        CommandArgs::parse(&cmd)?
-                    }
-                };
-
-                // Execute the command
-                self.command_processor.execute(command_args).await
-            }
-            crate::parser::ParseResult::StructuredCommand(args) => {
-                log::debug!("Got pre-parsed command arguments: {:?}", args);
-
-                // Execute directly with the structured command
-                self.command_processor.execute(args).await
+        // Direct commands never go through the language model, so they work
+        // (including `config`) whatever provider is configured
+        let command_args = match self.parse_command_string(&preprocessed_input) {
+            Ok(args) => args,
+            Err(_) => {
+                // Fall back to the legacy parser if Clap parsing fails
+                // This is useful for backward compatibility
+                CommandArgs::parse(&preprocessed_input)?
             }
-        }
+        };
+        self.execute_and_remember(input, command_args).await
     }
 
     /// Process a natural language command string.
@@ -264,7 +235,7 @@ impl Application {
                     match self.parse_command_string(&command) {
                         Ok(args) => {
                             log::debug!("Final parsed arguments for note command: {:?}", args);
-                            return self.command_processor.execute(args).await;
+                            return self.confirm_and_execute(input, args).await;
                         }
                         Err(_) => {
                             // Fallback to legacy parsing
@@ -278,7 +249,7 @@ impl Application {
                                 "Final parsed arguments for note command (legacy): {:?}",
                                 args
                             );
-                            return self.command_processor.execute(args).await;
+                            return self.confirm_and_execute(input, args).await;
                         }
                     }
                 }
@@ -292,8 +263,9 @@ impl Application {
         // Create appropriate parser using factory
         let parser = ParserFactory::create_parser()?;
 
-        // Process input through parser
-        match parser.parse_input(input).await {
+        // Process input through parser, with the session so far for follow-ups
+        let context = self.context.lock().map(|context| context.clone()).unwrap_or_default();
+        match parser.parse_input_with_context(input, &context).await {
             Ok(crate::parser::ParseResult::CommandString(command)) => {
                 println!("Translated to command: {}", command);
 
@@ -334,7 +306,7 @@ impl Application {
                     match self.parse_command_string(&enhanced_command) {
                         Ok(args) => {
                             log::debug!("Final parsed arguments: {:?}", args);
-                            self.command_processor.execute(args).await
+                            self.confirm_and_execute(input, args).await
                         }
                         Err(_) => {
                             let mut args = CommandArgs::parse(&enhanced_command)?;
@@ -344,7 +316,7 @@ impl Application {
                                 .map(|arg| arg.trim_matches('"').to_string())
                                 .collect();
                             log::debug!("Final parsed arguments (legacy): {:?}", args);
-                            self.command_processor.execute(args).await
+                            self.confirm_and_execute(input, args).await
                         }
                     }
                 } else {
@@ -358,7 +330,7 @@ impl Application {
             Ok(crate::parser::ParseResult::StructuredCommand(args)) => {
                 log::debug!("Got pre-parsed structured command: {:?}", args);
                 println!("Processed command structure from natural language");
-                self.command_processor.execute(args).await
+                self.confirm_and_execute(input, args).await
             }
             Err(e) => {
                 println!("Error processing natural language: {}", e);
@@ -368,6 +340,44 @@ impl Application {
         }
     }
 
+    /// Show what a command generated from natural language will do and run it
+    /// once the user accepts, possibly after editing it
+    #[allow(deprecated)]
+    async fn confirm_and_execute(&self, input: &str, mut args: CommandArgs) -> Result<()> {
+        if self.assume_yes {
+            return self.execute_and_remember(input, args).await;
+        }
+        if !std::io::stdin().is_terminal() {
+            println!("{}", crate::preview::CommandPreview::new(&args));
+            println!("Not running the command without confirmation. Pass --yes to run it.");
+            return Ok(());
+        }
+
+        let confirmed = crate::preview::confirm(
+            &mut args,
+            &mut std::io::stdin().lock(),
+            &mut std::io::stdout().lock(),
+        )?;
+        if confirmed {
+            self.execute_and_remember(input, args).await
+        } else {
+            println!("Cancelled");
+            Ok(())
+        }
+    }
+
+    /// Run a command and remember it, and what it refers to, for follow-up
+    /// requests in this session
+    #[allow(deprecated)]
+    async fn execute_and_remember(&self, input: &str, args: CommandArgs) -> Result<()> {
+        let executed = args.clone();
+        self.command_processor.execute(args).await?;
+        if let Ok(mut context) = self.context.lock() {
+            context.record(input, &executed);
+        }
+        Ok(())
+    }
+
     /// Helper method to parse a command string using Clap instead of the deprecated CommandArgs::parse
     fn parse_command_string(&self, input: &str) -> Result<CommandArgs> {
         // Format the input into argv style for clap
@@ -409,6 +419,8 @@ impl Application {
                 "note",
                 "config",
                 "contact",
+                "search",
+                "state",
                 "help",
                 "exit",
                 "version",
@@ -436,6 +448,42 @@ impl Application {
     }
 }
 
+/// Whether a preprocessed REPL line is a DuckTape command rather than natural language
+///
+/// `search` reads like natural language ("search for my dentist appointment"),
+/// so without the `ducktape` prefix it is only a command when no language
+/// model is configured to interpret it.
+fn is_direct_command(preprocessed: &str, use_natural_language: bool) -> bool {
+    // List of known DuckTape commands (expanded from README)
+    const KNOWN_COMMANDS: &[&str] = &[
+        "calendar",
+        "reminder",
+        "reminders",
+        "todo",
+        "todos",
+        "note",
+        "notes",
+        "config",
+        "contact",
+        "contacts",
+        "state",
+        "help",
+        "exit",
+        "version",
+        "--help",
+        "--version",
+    ];
+    let trimmed = preprocessed.trim_start();
+    // Match whole words, so "searching for..." and "noted that..." stay natural language
+    let (prefixed, command) = match trimmed.strip_prefix("ducktape ") {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, trimmed),
+    };
+    let is_command = |cmd: &str| command == cmd || command.starts_with(&format!("{} ", cmd));
+    KNOWN_COMMANDS.iter().any(|cmd| is_command(cmd))
+        || (is_command("search") && (prefixed || !use_natural_language))
+}
+
 #[allow(dead_code)] // Kept for future use when logging is expanded
 pub fn init_logger() {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
@@ -451,3 +499,22 @@ pub fn init_logger() {
         })
         .init();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_direct_command() {
+        assert!(is_direct_command("calendar list", true));
+        assert!(is_direct_command("ducktape note list", true));
+        assert!(is_direct_command("notes list", true));
+        assert!(is_direct_command("contacts", true));
+        assert!(!is_direct_command("noted that yesterday", false));
+
+        assert!(is_direct_command("ducktape search dentist", true));
+        assert!(is_direct_command("search dentist", false));
+        assert!(!is_direct_command("search for my dentist appointment next week", true));
+        assert!(!is_direct_command("searching for dentist", false));
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use ducktape::command_processor::{CommandArgs, CommandProcessor};
-use ducktape::parser::natural_language::NaturalLanguageParser;
 use ducktape::parser::natural_language::grok::GrokParser;
+use ducktape::parser::{ParseResult, Parser};
 use log::{debug, info};
 use std::env;
 use std::path::Path;
@@ -171,25 +171,22 @@ async fn process_natural_language(args: &[String]) -> Result<()> {
     // Sanitize user input
     let sanitized_input = ducktape::parser::natural_language::utils::sanitize_user_input(&nl_query);
 
-    // Parse the natural language into a structured command
-    let structured_command = parser.parse_natural_language(&sanitized_input).await?;
+    // Parse the natural language into a structured command and execute it
+    let args = command_args(parser.parse_input(&sanitized_input).await?)?;
+    debug!("Parsed args: {:?}", args);
+    let processor = CommandProcessor::new();
+    processor.execute(args).await?;
 
-    debug!("Converted to structured command: {}", structured_command);
+    Ok(())
+}
 
-    // Parse the structured command and execute it
-    match CommandArgs::parse(&structured_command) {
-        Ok(args) => {
-            debug!("Parsed args: {:?}", args);
-            let processor = CommandProcessor::new();
-            processor.execute(args).await?;
-        }
-        Err(e) => {
-            eprintln!("Error parsing generated command: {}", e);
-            return Err(e);
-        }
+/// The command to run for a parse result
+#[allow(deprecated)]
+fn command_args(result: ParseResult) -> Result<CommandArgs> {
+    match result {
+        ParseResult::StructuredCommand(args) => Ok(args),
+        ParseResult::CommandString(command) => CommandArgs::parse(&command),
     }
-
-    Ok(())
 }
 
 /// Start the interactive shell mode for Ducktape
@@ -236,23 +233,16 @@ async fn start_interactive_mode() -> Result<()> {
         }
 
         // Try to parse as natural language first
-        match parser.parse_natural_language(input).await {
-            Ok(structured_command) => {
-                debug!("Interactive mode generated command: {}", structured_command);
+        match parser.parse_input(input).await.and_then(command_args) {
+            Ok(args) => {
+                debug!("Interactive mode generated command: {:?}", args);
 
                 // Show the user what command will be executed
-                println!("Executing: {}", structured_command);
+                println!("Executing: {}", ducktape::preview::command_line(&args));
 
                 // Execute the command
-                match CommandArgs::parse(&structured_command) {
-                    Ok(args) => {
-                        if let Err(e) = processor.execute(args).await {
-                            eprintln!("Error executing command: {}", e);
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Error parsing generated command: {}", e);
-                    }
+                if let Err(e) = processor.execute(args).await {
+                    eprintln!("Error executing command: {}", e);
                 }
             }
             Err(e) => {
@@ -0,0 +1,274 @@
+//! Agenda view of calendar events grouped by day.
+//
+// The agenda is built from `calendar::list_events` and the recurring series
+// that started earlier, expanded to one entry per occurrence, and rendered
+// either as text for the terminal or serialized as JSON for `--json` and the API.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use log::warn;
+use serde::Serialize;
+
+use crate::calendar::EventConfig;
+
+/// Events in a date range, grouped by the day they start on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Agenda {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub days: Vec<AgendaDay>,
+}
+
+/// The events starting on one day, in start order
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AgendaDay {
+    pub date: NaiveDate,
+    pub events: Vec<AgendaEvent>,
+}
+
+/// A calendar event as shown in the agenda
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AgendaEvent {
+    pub uid: Option<String>,
+    pub title: String,
+    pub calendars: Vec<String>,
+    pub all_day: bool,
+    /// Start time (HH:MM); None for all-day events
+    pub start_time: Option<String>,
+    pub end_date: Option<String>,
+    pub end_time: Option<String>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub attendees: Vec<String>,
+    /// RFC 5545 RRULE value for recurring events
+    pub recurrence: Option<String>,
+}
+
+impl From<&EventConfig> for AgendaEvent {
+    fn from(event: &EventConfig) -> Self {
+        Self {
+            uid: event.uid.clone(),
+            title: event.title.clone(),
+            calendars: event.calendars.clone(),
+            all_day: event.all_day,
+            start_time: (!event.all_day).then(|| event.start_time.clone()),
+            end_date: event.end_date.clone(),
+            end_time: if event.all_day { None } else { event.end_time.clone() },
+            location: event.location.clone(),
+            description: event.description.clone(),
+            attendees: event.emails.clone(),
+            recurrence: event.recurrence.as_ref().and_then(|r| r.to_rrule().ok()),
+        }
+    }
+}
+
+impl Agenda {
+    /// Group events by start date; events with an unreadable date are skipped
+    pub fn new(from: NaiveDate, to: NaiveDate, events: &[EventConfig]) -> Self {
+        let mut days: Vec<AgendaDay> = Vec::new();
+        let mut sorted: Vec<&EventConfig> = events.iter().collect();
+        // All-day events come first within a day
+        sorted.sort_by(|a, b| {
+            (&a.start_date, !a.all_day, &a.start_time, &a.title).cmp(&(
+                &b.start_date,
+                !b.all_day,
+                &b.start_time,
+                &b.title,
+            ))
+        });
+        for event in sorted {
+            let Ok(date) = NaiveDate::parse_from_str(&event.start_date, "%Y-%m-%d") else {
+                warn!("Skipping event '{}' with date '{}'", event.title, event.start_date);
+                continue;
+            };
+            match days.last_mut() {
+                Some(day) if day.date == date => day.events.push(event.into()),
+                _ => days.push(AgendaDay { date, events: vec![event.into()] }),
+            }
+        }
+        Self { from, to, days }
+    }
+
+    /// Total number of events across all days
+    pub fn event_count(&self) -> usize {
+        self.days.iter().map(|day| day.events.len()).sum()
+    }
+
+    /// Render the agenda as plain text for the terminal
+    pub fn to_text(&self) -> String {
+        let mut out = if self.from == self.to {
+            format!("Agenda for {}\n", self.from.format("%a %-d %b %Y"))
+        } else {
+            format!(
+                "Agenda for {} to {}\n",
+                self.from.format("%a %-d %b %Y"),
+                self.to.format("%a %-d %b %Y")
+            )
+        };
+        if self.days.is_empty() {
+            out.push_str("\nNo events found.\n");
+            return out;
+        }
+        for day in &self.days {
+            out.push_str(&format!("\n{}\n", day.date.format("%A, %-d %B %Y")));
+            for event in &day.events {
+                let when = match (&event.start_time, &event.end_time) {
+                    (None, _) => "all day".to_string(),
+                    (Some(start), Some(end)) => format!("{}-{}", start, end),
+                    (Some(start), None) => start.clone(),
+                };
+                out.push_str(&format!("  {:<12} {}", when, event.title));
+                if !event.calendars.is_empty() {
+                    out.push_str(&format!(" [{}]", event.calendars.join(", ")));
+                }
+                if let Some(location) = event.location.as_deref().filter(|l| !l.is_empty()) {
+                    out.push_str(&format!(" @ {}", location));
+                }
+                if event.recurrence.is_some() {
+                    out.push_str(" (recurring)");
+                }
+                out.push('\n');
+            }
+        }
+        let count = self.event_count();
+        out.push_str(&format!("\n{} event{}\n", count, if count == 1 { "" } else { "s" }));
+        out
+    }
+}
+
+/// One event per occurrence between `from` and `to` (inclusive) of each
+/// recurring event; other events are kept as they are
+///
+/// Each occurrence keeps the series' times and duration. Events with an
+/// unreadable start date are skipped.
+pub fn expand_occurrences(
+    events: &[EventConfig],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<EventConfig> {
+    let mut expanded = Vec::new();
+    for event in events {
+        let Some(recurrence) = &event.recurrence else {
+            expanded.push(event.clone());
+            continue;
+        };
+        let Ok(first) = NaiveDate::parse_from_str(&event.start_date, "%Y-%m-%d") else {
+            warn!("Skipping event '{}' with date '{}'", event.title, event.start_date);
+            continue;
+        };
+        let end = event
+            .end_date
+            .as_deref()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+        for date in recurrence.occurrences(first, to).into_iter().filter(|date| *date >= from) {
+            let mut occurrence = event.clone();
+            occurrence.start_date = date.format("%Y-%m-%d").to_string();
+            occurrence.end_date =
+                end.map(|end| (date + (end - first)).format("%Y-%m-%d").to_string());
+            expanded.push(occurrence);
+        }
+    }
+    expanded
+}
+
+/// Fetch the events between `from` and `to` (inclusive) as an agenda, with
+/// one entry per occurrence of recurring events
+pub async fn get_agenda(calendars: &[String], from: NaiveDate, to: NaiveDate) -> Result<Agenda> {
+    let mut events = crate::calendar::list_events(calendars, from, to).await?;
+    events.extend(crate::calendar::list_recurring_events(calendars, from).await?);
+    Ok(Agenda::new(from, to, &expand_occurrences(&events, from, to)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::{RecurrenceFrequency, RecurrencePattern};
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn sample_events() -> Vec<EventConfig> {
+        let mut planning = EventConfig::new("Planning", "2024-06-03", "10:00");
+        planning.end_time = Some("11:00".to_string());
+        planning.calendars = vec!["Work".to_string()];
+        planning.location = Some("Room 1".to_string());
+
+        let mut holiday = EventConfig::new("Holiday", "2024-06-03", "00:00");
+        holiday.all_day = true;
+        holiday.calendars = vec!["Home".to_string()];
+
+        let standup = EventConfig::new("Standup", "2024-06-05", "09:00")
+            .with_recurrence(RecurrencePattern::new(RecurrenceFrequency::Daily));
+
+        vec![standup, planning, holiday]
+    }
+
+    #[test]
+    fn test_agenda_groups_events_by_day() {
+        let agenda = Agenda::new(date("2024-06-01"), date("2024-06-07"), &sample_events());
+
+        assert_eq!(agenda.event_count(), 3);
+        assert_eq!(agenda.days.len(), 2);
+        assert_eq!(agenda.days[0].date, date("2024-06-03"));
+        let titles: Vec<&str> = agenda.days[0].events.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["Holiday", "Planning"]);
+        assert_eq!(agenda.days[0].events[0].start_time, None);
+        assert_eq!(agenda.days[1].events[0].recurrence.as_deref(), Some("FREQ=DAILY;INTERVAL=1"));
+    }
+
+    #[test]
+    fn test_agenda_text_output() {
+        let agenda = Agenda::new(date("2024-06-01"), date("2024-06-07"), &sample_events());
+        let text = agenda.to_text();
+
+        assert!(text.starts_with("Agenda for Sat 1 Jun 2024 to Fri 7 Jun 2024\n"));
+        assert!(text.contains("\nMonday, 3 June 2024\n"));
+        assert!(text.contains("  all day      Holiday [Home]\n"));
+        assert!(text.contains("  10:00-11:00  Planning [Work] @ Room 1\n"));
+        assert!(text.contains("  09:00        Standup (recurring)\n"));
+        assert!(text.ends_with("\n3 events\n"));
+    }
+
+    #[test]
+    fn test_expand_occurrences() {
+        let mut weekly = EventConfig::new("Retro", "2024-05-27", "23:00")
+            .with_recurrence(RecurrencePattern::new(RecurrenceFrequency::Weekly));
+        weekly.end_date = Some("2024-05-28".to_string());
+        let mut events = sample_events();
+        events.push(weekly);
+
+        let expanded = expand_occurrences(&events, date("2024-06-01"), date("2024-06-07"));
+        let dates: Vec<(&str, &str)> =
+            expanded.iter().map(|e| (e.title.as_str(), e.start_date.as_str())).collect();
+        assert_eq!(
+            dates,
+            [
+                ("Standup", "2024-06-05"),
+                ("Standup", "2024-06-06"),
+                ("Standup", "2024-06-07"),
+                ("Planning", "2024-06-03"),
+                ("Holiday", "2024-06-03"),
+                ("Retro", "2024-06-03"),
+            ]
+        );
+        assert_eq!(expanded[5].end_date.as_deref(), Some("2024-06-04"));
+    }
+
+    #[test]
+    fn test_empty_agenda() {
+        let agenda = Agenda::new(date("2024-06-01"), date("2024-06-01"), &[]);
+        assert_eq!(agenda.to_text(), "Agenda for Sat 1 Jun 2024\n\nNo events found.\n");
+    }
+
+    #[test]
+    fn test_agenda_serializes_to_json() {
+        let agenda = Agenda::new(date("2024-06-01"), date("2024-06-07"), &sample_events());
+        let json = serde_json::to_value(&agenda).unwrap();
+
+        assert_eq!(json["from"], "2024-06-01");
+        assert_eq!(json["days"][0]["date"], "2024-06-03");
+        assert_eq!(json["days"][0]["events"][1]["title"], "Planning");
+        assert_eq!(json["days"][0]["events"][1]["end_time"], "11:00");
+    }
+}
@@ -2,18 +2,66 @@
 //
 // This module provides async functions for interacting with macOS Calendar.app via AppleScript.
 
-use crate::calendar::{EventConfig, RecurrenceFrequency};
-use crate::zoom::{ZoomClient, ZoomMeetingOptions, calculate_meeting_duration, format_zoom_time};
+use crate::calendar::{CalendarBackend, EventConfig, parse_ical_recurrence};
+use crate::zoom::{ZoomClient, ZoomMeetingOptions, format_zoom_time};
 use anyhow::{Result, anyhow};
+use async_trait::async_trait;
 use chrono::Datelike;
 use chrono::TimeZone; // Keep for Local.from_local_datetime
-use chrono::{Local, NaiveDateTime, NaiveTime, Timelike}; // Added Timelike
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike}; // Added Timelike
 use log::{debug, error, info};
 use std::process::Command;
-use std::str::FromStr;
+
+/// Calendar backend that drives macOS Calendar.app through `osascript`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AppleScriptBackend;
+
+#[async_trait]
+impl CalendarBackend for AppleScriptBackend {
+    async fn ensure_running(&self) -> Result<()> {
+        ensure_calendar_running().await
+    }
+
+    async fn available_calendars(&self) -> Result<Vec<String>> {
+        get_available_calendars().await
+    }
+
+    async fn create_single_event(&self, config: EventConfig) -> Result<()> {
+        create_single_event(config).await
+    }
+
+    async fn list_events(
+        &self,
+        calendars: &[String],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<EventConfig>> {
+        list_events(calendars, from, to).await
+    }
+
+    async fn list_recurring_events(
+        &self,
+        calendars: &[String],
+        before: NaiveDate,
+    ) -> Result<Vec<EventConfig>> {
+        list_recurring_events(calendars, before).await
+    }
+
+    async fn get_event(&self, uid: &str) -> Result<EventConfig> {
+        get_event(uid).await
+    }
+
+    async fn update_event(&self, event: &EventConfig) -> Result<EventConfig> {
+        update_event(event).await
+    }
+
+    async fn delete_event(&self, uid: &str) -> Result<EventConfig> {
+        delete_event(uid).await
+    }
+}
 
 /// Ensure Calendar.app is running
-pub async fn ensure_calendar_running() -> Result<()> {
+async fn ensure_calendar_running() -> Result<()> {
     let check_script = r#"tell application "Calendar"
         if it is not running then
             launch
@@ -32,48 +80,8 @@ pub async fn ensure_calendar_running() -> Result<()> {
     if output.status.success() { Ok(()) } else { Err(anyhow!("Calendar is not running")) }
 }
 
-/// List all calendars
-pub async fn list_calendars() -> Result<()> {
-    ensure_calendar_running().await?;
-    let script = r#"tell application "Calendar"
-        try
-            set output to {}
-            repeat with aCal in calendars
-                set calInfo to name of aCal
-                copy calInfo to end of output
-            end repeat
-            return output
-        on error errMsg
-            error "Failed to list calendars: " & errMsg
-        end try
-    end tell"#;
-
-    let output = tokio::process::Command::new("osascript").arg("-e").arg(script).output().await?;
-    if output.status.success() {
-        println!("Available calendars:");
-        let calendars = String::from_utf8_lossy(&output.stdout);
-        if calendars.trim().is_empty() {
-            println!("  No calendars found. Please ensure Calendar.app is properly configured.");
-        } else {
-            let mut unique_calendars: std::collections::HashSet<String> =
-                std::collections::HashSet::new();
-            for calendar in calendars.trim_matches('{').trim_matches('}').split(", ") {
-                unique_calendars.insert(calendar.trim_matches('"').to_string());
-            }
-            let mut sorted_calendars: Vec<_> = unique_calendars.into_iter().collect();
-            sorted_calendars.sort();
-            for calendar in sorted_calendars {
-                println!("  - {}", calendar);
-            }
-        }
-        Ok(())
-    } else {
-        Err(anyhow!("Failed to list calendars: {}", String::from_utf8_lossy(&output.stderr)))
-    }
-}
-
 /// Get available calendars
-pub async fn get_available_calendars() -> Result<Vec<String>> {
+async fn get_available_calendars() -> Result<Vec<String>> {
     let script = r#"tell application "Calendar"
         try
             set output to {}
@@ -91,10 +99,12 @@ pub async fn get_available_calendars() -> Result<Vec<String>> {
     if output.status.success() {
         let calendars = String::from_utf8_lossy(&output.stdout);
         Ok(calendars
+            .trim()
             .trim_matches('{')
             .trim_matches('}')
             .split(", ")
             .map(|s| s.trim_matches('"').to_string())
+            .filter(|s| !s.is_empty())
             .collect())
     } else {
         Err(anyhow!(
@@ -105,7 +115,7 @@ pub async fn get_available_calendars() -> Result<Vec<String>> {
 }
 
 /// Create a single event in Calendar.app
-pub async fn create_single_event(config: EventConfig) -> Result<()> {
+async fn create_single_event(config: EventConfig) -> Result<()> {
     debug!("Creating event with config (times expected to be local): {:?}", config);
 
     let start_datetime_str = format!(
@@ -325,45 +335,37 @@ pub async fn create_single_event(config: EventConfig) -> Result<()> {
         }
     }
     let recurrence_code = if let Some(recurrence) = &config.recurrence {
-        let mut parts = vec![
-            format!("FREQ={}", recurrence.frequency.to_rfc5545()),
-            format!("INTERVAL={}", recurrence.interval),
-        ];
-        if let Some(count) = recurrence.count {
-            parts.push(format!("COUNT={}", count));
-        }
-        if let Some(end_date) = &recurrence.end_date {
-            let end_naive =
-                NaiveDateTime::parse_from_str(&format!("{} 23:59", end_date), "%Y-%m-%d %H:%M")
-                    .map_err(|e| anyhow!("Invalid recurrence end date: {}", e))?;
-            parts.push(format!("UNTIL={}", end_naive.format("%Y%m%dT%H%M%SZ")));
+        let rule_string = recurrence.to_rrule()?;
+        let mut excluded_dates = String::new();
+        for (i, date) in recurrence.exception_dates.iter().enumerate() {
+            let naive = NaiveDateTime::parse_from_str(
+                &format!("{} {}", date, local_start.format("%H:%M")),
+                "%Y-%m-%d %H:%M",
+            )
+            .map_err(|e| anyhow!("Invalid recurrence exception date '{}': {}", date, e))?;
+            let name = format!("exDate{}", i);
+            excluded_dates.push_str(&format!(
+                "\n{}\nset end of excludedDates to {}",
+                applescript_date(&name, naive),
+                name
+            ));
         }
-        if recurrence.frequency == RecurrenceFrequency::Weekly
-            && !recurrence.days_of_week.is_empty()
-        {
-            let days: Vec<&str> = recurrence
-                .days_of_week
-                .iter()
-                .map(|&d| match d {
-                    0 => "SU",
-                    1 => "MO",
-                    2 => "TU",
-                    3 => "WE",
-                    4 => "TH",
-                    5 => "FR",
-                    6 => "SA",
-                    _ => "MO",
-                })
-                .collect();
-            parts.push(format!("BYDAY={}", days.join(",")));
+        if !excluded_dates.is_empty() {
+            excluded_dates = format!(
+                r#"
+                    set excludedDates to {{}}{}
+                    tell newEvent
+                        set its excluded dates to excludedDates
+                    end tell"#,
+                excluded_dates
+            );
         }
-        let rule_string = parts.join(";");
         format!(
             r#"
                     tell newEvent
                         set its recurrence to "{}"
-                    end tell"#,
-            rule_string
+                    end tell{}"#,
+            rule_string, excluded_dates
         )
     } else {
         String::new()
@@ -456,15 +458,414 @@ pub async fn create_single_event(config: EventConfig) -> Result<()> {
     }
 }
 
+/// Separates the fields of one event in the output of the event scripts
+const FIELD_SEPARATOR: char = '\u{1f}';
+/// Separates events in the output of the event scripts
+const RECORD_SEPARATOR: char = '\u{1e}';
+
+/// AppleScript handlers that print an event in the format read by `parse_event_list_output`
+const EVENT_RECORD_HANDLERS: &str = r#"on pad(n)
+    return text -2 thru -1 of ("0" & (n as integer))
+end pad
+
+on formatDate(d)
+    return ((year of d) as string) & "-" & my pad(month of d as integer) & "-" & my pad(day of d) & " " & my pad(hours of d) & ":" & my pad(minutes of d)
+end formatDate
+
+on textOrEmpty(v)
+    if v is missing value then return ""
+    return v as string
+end textOrEmpty
+
+on eventRecord(e, calName)
+    set fieldSep to character id 31
+    tell application "Calendar"
+        set attendeeEmails to ""
+        try
+            repeat with a in attendees of e
+                set attendeeEmails to attendeeEmails & (email of a) & ","
+            end repeat
+        end try
+        return (uid of e) & fieldSep & calName & fieldSep & my textOrEmpty(summary of e) & fieldSep & my formatDate(start date of e) & fieldSep & my formatDate(end date of e) & fieldSep & ((allday event of e) as string) & fieldSep & my textOrEmpty(location of e) & fieldSep & my textOrEmpty(description of e) & fieldSep & attendeeEmails & fieldSep & my textOrEmpty(recurrence of e) & (character id 30)
+    end tell
+end eventRecord
+"#;
+
+/// Quote a value as an AppleScript string literal
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+async fn run_event_script(script: &str, action: &str) -> Result<Vec<EventConfig>> {
+    debug!("Generated AppleScript to {}:\n{}", action, script);
+    let output = tokio::process::Command::new("osascript").arg("-e").arg(script).output().await?;
+    if output.status.success() {
+        Ok(parse_event_list_output(&String::from_utf8_lossy(&output.stdout)))
+    } else {
+        Err(anyhow!("Failed to {}: {}", action, String::from_utf8_lossy(&output.stderr)))
+    }
+}
+
+/// Read events from Calendar.app whose start date falls within `from..=to`
+///
+/// Calendar.app only matches a recurring event on the date of its first
+/// occurrence, so series that started before `from` are not returned; see
+/// `list_recurring_events`.
+async fn list_events(
+    calendars: &[String],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<EventConfig>> {
+    let end = to.succ_opt().ok_or_else(|| anyhow!("Invalid end date: {}", to))?;
+    let script = events_script(
+        calendars,
+        &[("rangeStart", from), ("rangeEnd", end)],
+        "start date is greater than or equal to rangeStart and start date is less than rangeEnd",
+    );
+    run_event_script(&script, "list events").await
+}
+
+/// Read recurring events from Calendar.app whose series starts before `before`
+async fn list_recurring_events(
+    calendars: &[String],
+    before: NaiveDate,
+) -> Result<Vec<EventConfig>> {
+    let script = events_script(
+        calendars,
+        &[("rangeStart", before)],
+        "start date is less than rangeStart and recurrence is not missing value and recurrence is not \"\"",
+    );
+    run_event_script(&script, "list recurring events").await
+}
+
+/// Script printing the events of the wanted calendars that match `condition`,
+/// which can use the given dates (at midnight) by name
+fn events_script(calendars: &[String], dates: &[(&str, NaiveDate)], condition: &str) -> String {
+    let wanted_calendars =
+        calendars.iter().map(|c| applescript_string(c)).collect::<Vec<_>>().join(", ");
+    let dates: String = dates
+        .iter()
+        .map(|(name, date)| {
+            format!(
+                r#"
+set {name} to current date
+set day of {name} to 1
+set year of {name} to {year}
+set month of {name} to {month}
+set day of {name} to {day}
+set time of {name} to 0
+"#,
+                name = name,
+                year = date.format("%Y"),
+                month = date.format("%-m"),
+                day = date.format("%-d"),
+            )
+        })
+        .collect();
+    format!(
+        r#"{handlers}
+set wantedCalendars to {{{wanted_calendars}}}
+{dates}
+set output to ""
+tell application "Calendar"
+    repeat with cal in calendars
+        set calName to name of cal
+        if (count of wantedCalendars) is 0 or wantedCalendars contains calName then
+            set matches to (every event of cal whose {condition})
+            repeat with e in matches
+                set output to output & my eventRecord(e, calName)
+            end repeat
+        end if
+    end repeat
+end tell
+return output"#,
+        handlers = EVENT_RECORD_HANDLERS,
+        wanted_calendars = wanted_calendars,
+        dates = dates,
+        condition = condition,
+    )
+}
+
+/// Read the event with the given UID from Calendar.app
+async fn get_event(uid: &str) -> Result<EventConfig> {
+    let script = format!(
+        r#"{handlers}
+set output to ""
+tell application "Calendar"
+    repeat with cal in calendars
+        set matches to (every event of cal whose uid is {uid})
+        repeat with e in matches
+            set output to output & my eventRecord(e, name of cal)
+        end repeat
+    end repeat
+end tell
+return output"#,
+        handlers = EVENT_RECORD_HANDLERS,
+        uid = applescript_string(uid),
+    );
+    run_event_script(&script, "get event")
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("No event with UID '{}'", uid))
+}
+
+/// Save the dates, location and attendees of an event in Calendar.app
+///
+/// Attendees already on the event are left alone; new ones are invited.
+async fn update_event(event: &EventConfig) -> Result<EventConfig> {
+    let uid = event
+        .uid
+        .as_deref()
+        .ok_or_else(|| anyhow!("Event '{}' has no UID", event.title))?;
+    let (start, end) = event.time_span()?;
+    let attendees: String = event
+        .emails
+        .iter()
+        .map(|email| {
+            format!(
+                r#"
+            if existingEmails does not contain {email} then
+                try
+                    tell e to make new attendee at end of attendees with properties {{email:{email}}}
+                on error errMsg
+                    log "Failed to add attendee " & {email} & ": " & errMsg
+                end try
+            end if"#,
+                email = applescript_string(email)
+            )
+        })
+        .collect();
+    let script = format!(
+        r#"{handlers}
+{new_start}
+{new_end}
+set output to ""
+tell application "Calendar"
+    repeat with cal in calendars
+        set matches to (every event of cal whose uid is {uid})
+        repeat with e in matches
+            -- Set the end on both sides of the start so the event is never inverted
+            set end date of e to newEnd
+            set start date of e to newStart
+            set end date of e to newEnd
+            set location of e to {location}
+            set existingEmails to {{}}
+            try
+                set existingEmails to email of attendees of e
+            end try{attendees}
+            set output to output & my eventRecord(e, name of cal)
+        end repeat
+    end repeat
+end tell
+return output"#,
+        handlers = EVENT_RECORD_HANDLERS,
+        new_start = applescript_date("newStart", start),
+        new_end = applescript_date("newEnd", end),
+        uid = applescript_string(uid),
+        location = applescript_string(event.location.as_deref().unwrap_or("")),
+        attendees = attendees,
+    );
+    let updated = run_event_script(&script, "update event")
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("No event with UID '{}'", uid))?;
+    info!("Updated calendar event '{}' ({})", updated.title, uid);
+    Ok(updated)
+}
+
+/// AppleScript statements that set the variable `name` to a local date-time
+fn applescript_date(name: &str, value: NaiveDateTime) -> String {
+    // The day is reset first so that changing the month never overflows it
+    format!(
+        "set {name} to current date\nset day of {name} to 1\nset year of {name} to {year}\n\
+         set month of {name} to {month}\nset day of {name} to {day}\n\
+         set time of {name} to {seconds}",
+        name = name,
+        year = value.format("%Y"),
+        month = value.format("%-m"),
+        day = value.format("%-d"),
+        seconds = value.time().num_seconds_from_midnight(),
+    )
+}
+
+/// Delete the event with the given UID from Calendar.app and return it
+async fn delete_event(uid: &str) -> Result<EventConfig> {
+    let script = format!(
+        r#"{handlers}
+set output to ""
+tell application "Calendar"
+    repeat with cal in calendars
+        set matches to (every event of cal whose uid is {uid})
+        repeat with e in matches
+            set output to output & my eventRecord(e, name of cal)
+            delete e
+        end repeat
+    end repeat
+end tell
+return output"#,
+        handlers = EVENT_RECORD_HANDLERS,
+        uid = applescript_string(uid),
+    );
+    let deleted = run_event_script(&script, "delete event").await?;
+    let event = deleted
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("No event with UID '{}'", uid))?;
+    info!("Deleted calendar event '{}' ({})", event.title, uid);
+    Ok(event)
+}
+
+/// Parse the records printed by the event scripts into event configs
+///
+/// Each record holds the uid, calendar, title, start, end, all-day flag,
+/// location, description, comma-separated attendee emails and recurrence rule.
+fn parse_event_list_output(output: &str) -> Vec<EventConfig> {
+    output
+        .split(RECORD_SEPARATOR)
+        .filter(|record| !record.trim().is_empty())
+        .filter_map(|record| {
+            let fields: Vec<&str> =
+                record.trim_start_matches('\n').split(FIELD_SEPARATOR).collect();
+            let event = parse_event_record(&fields);
+            if event.is_none() {
+                debug!("Skipping unreadable event record: {:?}", record);
+            }
+            event
+        })
+        .collect()
+}
+
+fn parse_event_record(fields: &[&str]) -> Option<EventConfig> {
+    let [uid, calendar, title, start, end, all_day, location, description, emails, recurrence] =
+        fields
+    else {
+        return None;
+    };
+    let start = NaiveDateTime::parse_from_str(start, "%Y-%m-%d %H:%M").ok()?;
+    let end = NaiveDateTime::parse_from_str(end, "%Y-%m-%d %H:%M").ok()?;
+    let non_empty = |value: &str| (!value.is_empty()).then(|| value.to_string());
+
+    let mut config = EventConfig::new(
+        title,
+        &start.format("%Y-%m-%d").to_string(),
+        &start.format("%H:%M").to_string(),
+    );
+    config.uid = Some(uid.to_string());
+    config.calendars = vec![calendar.to_string()];
+    config.all_day = all_day.trim() == "true";
+    if config.all_day {
+        config.start_time = "00:00".to_string();
+        // Calendar.app ends all-day events at midnight after the last day
+        let last_day = (end - chrono::Duration::minutes(1)).date();
+        if last_day > start.date() {
+            config.end_date = Some(last_day.format("%Y-%m-%d").to_string());
+        }
+    } else if end > start {
+        config.end_time = Some(end.format("%H:%M").to_string());
+        if end.date() != start.date() {
+            config.end_date = Some(end.format("%Y-%m-%d").to_string());
+        }
+    }
+    config.location = non_empty(location);
+    config.description = non_empty(description);
+    config.emails = emails
+        .split(',')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(String::from)
+        .collect();
+    config.recurrence = parse_ical_recurrence(recurrence.trim());
+    Some(config)
+}
+
 /// List event properties
 pub async fn list_event_properties() -> Result<()> {
     // ...implementation moved from calendar.rs...
     Ok(())
 }
 
-/// Delete an event by title and date (placeholder implementation)
-pub async fn delete_event(_title: &str, _date: &str) -> Result<()> {
-    // TODO: Implement event deletion
-    println!("Event deletion not yet implemented");
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::RecurrenceFrequency;
+
+    fn record(fields: &[&str]) -> String {
+        let mut record = fields.join(&FIELD_SEPARATOR.to_string());
+        record.push(RECORD_SEPARATOR);
+        record
+    }
+
+    #[test]
+    fn test_parse_event_list_output() {
+        let output = format!(
+            "{}{}\n",
+            record(&[
+                "ABC-1",
+                "Work",
+                "Planning",
+                "2024-06-03 10:00",
+                "2024-06-03 11:30",
+                "false",
+                "Room 1",
+                "Line one\nLine two",
+                "joe@example.com,ann@example.com,",
+                "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO",
+            ]),
+            record(&[
+                "ABC-2",
+                "Home",
+                "Holiday",
+                "2024-06-10 00:00",
+                "2024-06-13 00:00",
+                "true",
+                "",
+                "",
+                "",
+                "",
+            ]),
+        );
+
+        let events = parse_event_list_output(&output);
+        assert_eq!(events.len(), 2);
+
+        let planning = &events[0];
+        assert_eq!(planning.uid.as_deref(), Some("ABC-1"));
+        assert_eq!(planning.calendars, vec!["Work".to_string()]);
+        assert_eq!(planning.start_date, "2024-06-03");
+        assert_eq!(planning.start_time, "10:00");
+        assert_eq!(planning.end_time.as_deref(), Some("11:30"));
+        assert_eq!(planning.end_date, None);
+        assert_eq!(planning.description.as_deref(), Some("Line one\nLine two"));
+        assert_eq!(planning.emails, vec!["joe@example.com", "ann@example.com"]);
+        let recurrence = planning.recurrence.as_ref().unwrap();
+        assert_eq!(recurrence.frequency, RecurrenceFrequency::Weekly);
+        assert_eq!(recurrence.days_of_week, vec![1]);
+
+        let holiday = &events[1];
+        assert!(holiday.all_day);
+        assert_eq!(holiday.end_date.as_deref(), Some("2024-06-12"));
+        assert_eq!(holiday.end_time, None);
+        assert_eq!(holiday.location, None);
+        assert!(holiday.emails.is_empty());
+        assert!(holiday.recurrence.is_none());
+    }
+
+    #[test]
+    fn test_parse_event_list_output_skips_malformed_records() {
+        let output = format!("{}garbage{}", record(&["only", "three", "fields"]), RECORD_SEPARATOR);
+        assert!(parse_event_list_output(&output).is_empty());
+        assert!(parse_event_list_output("\n").is_empty());
+    }
+
+    #[test]
+    fn test_applescript_date() {
+        let value = NaiveDateTime::parse_from_str("2024-02-29 13:05", "%Y-%m-%d %H:%M").unwrap();
+        assert_eq!(
+            applescript_date("newStart", value),
+            "set newStart to current date\nset day of newStart to 1\nset year of newStart to 2024\n\
+             set month of newStart to 2\nset day of newStart to 29\nset time of newStart to 47100"
+        );
+    }
 }
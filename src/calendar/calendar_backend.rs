@@ -0,0 +1,311 @@
+//! Pluggable calendar backends for DuckTape.
+//
+// `calendar::create_event` and the other public calendar functions never talk to
+// Calendar.app directly; they go through the process-wide `CalendarBackend`
+// returned by `backend()`. The default is the AppleScript backend, and tests (or
+// non-macOS hosts) can swap in the `InMemoryBackend` with `set_backend`.
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use once_cell::sync::Lazy;
+
+use crate::calendar::{AppleScriptBackend, EventConfig};
+
+/// Operations a calendar store must provide for DuckTape to manage events
+#[async_trait]
+pub trait CalendarBackend: Send + Sync {
+    /// Make sure the calendar store is reachable, launching it if needed
+    async fn ensure_running(&self) -> Result<()>;
+
+    /// Names of all calendars known to the store
+    async fn available_calendars(&self) -> Result<Vec<String>>;
+
+    /// Create an event in the single calendar named by `config.calendars[0]`
+    async fn create_single_event(&self, config: EventConfig) -> Result<()>;
+
+    /// Events starting between `from` and `to` (inclusive) in the named calendars,
+    /// or in every calendar when `calendars` is empty
+    async fn list_events(
+        &self,
+        calendars: &[String],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<EventConfig>>;
+
+    /// Recurring events whose series starts before `before`, which
+    /// `list_events` does not return for later dates
+    async fn list_recurring_events(
+        &self,
+        calendars: &[String],
+        before: NaiveDate,
+    ) -> Result<Vec<EventConfig>>;
+
+    /// The event with the given UID
+    async fn get_event(&self, uid: &str) -> Result<EventConfig>;
+
+    /// Save the dates, times, location and attendees of the event with
+    /// `event.uid`, returning the event as it is now stored
+    async fn update_event(&self, event: &EventConfig) -> Result<EventConfig>;
+
+    /// Delete the event with the given UID and return it as it was stored
+    async fn delete_event(&self, uid: &str) -> Result<EventConfig>;
+}
+
+static BACKEND: Lazy<RwLock<Arc<dyn CalendarBackend>>> =
+    Lazy::new(|| RwLock::new(Arc::new(AppleScriptBackend)));
+
+/// Get the calendar backend currently in use
+pub fn backend() -> Arc<dyn CalendarBackend> {
+    match BACKEND.read() {
+        Ok(guard) => Arc::clone(&guard),
+        Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+    }
+}
+
+/// Replace the calendar backend used by all calendar operations
+pub fn set_backend(new_backend: Arc<dyn CalendarBackend>) {
+    match BACKEND.write() {
+        Ok(mut guard) => *guard = new_backend,
+        Err(poisoned) => *poisoned.into_inner() = new_backend,
+    }
+}
+
+/// Calendar backend that keeps calendars and events in process memory
+///
+/// Useful for tests and for exercising the command flow on hosts without Calendar.app.
+#[derive(Debug, Default)]
+pub struct InMemoryBackend {
+    calendars: Mutex<Vec<String>>,
+    events: Mutex<Vec<EventConfig>>,
+}
+
+impl InMemoryBackend {
+    /// Create an empty backend with a single calendar named "Calendar"
+    pub fn new() -> Self {
+        Self::with_calendars(["Calendar"])
+    }
+
+    /// Create an empty backend that exposes the given calendars
+    pub fn with_calendars<I, S>(calendars: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            calendars: Mutex::new(calendars.into_iter().map(Into::into).collect()),
+            events: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Add a calendar if one with the same name does not already exist
+    pub fn add_calendar(&self, name: &str) {
+        let mut calendars = self.calendars.lock().unwrap_or_else(|e| e.into_inner());
+        if !calendars.iter().any(|c| c == name) {
+            calendars.push(name.to_string());
+        }
+    }
+
+    /// Snapshot of all stored events, in creation order
+    pub fn events(&self) -> Vec<EventConfig> {
+        self.events.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    // Stored events in the named calendars, or in all of them when `calendars` is empty
+    fn list_all(&self, calendars: &[String]) -> Vec<EventConfig> {
+        let in_calendars = |event: &&EventConfig| {
+            calendars.is_empty()
+                || event
+                    .calendars
+                    .iter()
+                    .any(|c| calendars.iter().any(|w| w.eq_ignore_ascii_case(c)))
+        };
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter(in_calendars)
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl CalendarBackend for InMemoryBackend {
+    async fn ensure_running(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn available_calendars(&self) -> Result<Vec<String>> {
+        Ok(self.calendars.lock().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    async fn create_single_event(&self, config: EventConfig) -> Result<()> {
+        let calendar = config
+            .calendars
+            .first()
+            .ok_or_else(|| anyhow!("No calendar specified for event '{}'", config.title))?;
+        let exists = self
+            .calendars
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(calendar));
+        if !exists {
+            return Err(anyhow!("Calendar '{}' not found in available calendars", calendar));
+        }
+        let mut config = config;
+        config.uid.get_or_insert_with(|| uuid::Uuid::new_v4().to_string());
+        self.events.lock().unwrap_or_else(|e| e.into_inner()).push(config);
+        Ok(())
+    }
+
+    async fn list_events(
+        &self,
+        calendars: &[String],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<EventConfig>> {
+        let in_range = |event: &EventConfig| {
+            NaiveDate::parse_from_str(&event.start_date, "%Y-%m-%d")
+                .is_ok_and(|date| date >= from && date <= to)
+        };
+        Ok(self.list_all(calendars).into_iter().filter(in_range).collect())
+    }
+
+    async fn list_recurring_events(
+        &self,
+        calendars: &[String],
+        before: NaiveDate,
+    ) -> Result<Vec<EventConfig>> {
+        let started_before = |event: &EventConfig| {
+            NaiveDate::parse_from_str(&event.start_date, "%Y-%m-%d").is_ok_and(|date| date < before)
+        };
+        Ok(self
+            .list_all(calendars)
+            .into_iter()
+            .filter(|event| event.recurrence.is_some() && started_before(event))
+            .collect())
+    }
+
+    async fn get_event(&self, uid: &str) -> Result<EventConfig> {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|event| event.uid.as_deref() == Some(uid))
+            .cloned()
+            .ok_or_else(|| anyhow!("No event with UID '{}'", uid))
+    }
+
+    async fn update_event(&self, event: &EventConfig) -> Result<EventConfig> {
+        let uid = event
+            .uid
+            .as_deref()
+            .ok_or_else(|| anyhow!("Event '{}' has no UID", event.title))?;
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        let stored = events
+            .iter_mut()
+            .find(|stored| stored.uid.as_deref() == Some(uid))
+            .ok_or_else(|| anyhow!("No event with UID '{}'", uid))?;
+        *stored = event.clone();
+        Ok(stored.clone())
+    }
+
+    async fn delete_event(&self, uid: &str) -> Result<EventConfig> {
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        let position = events
+            .iter()
+            .position(|event| event.uid.as_deref() == Some(uid))
+            .ok_or_else(|| anyhow!("No event with UID '{}'", uid))?;
+        Ok(events.remove(position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_memory_backend_stores_events() {
+        let backend = InMemoryBackend::with_calendars(["Work", "Home"]);
+        let mut config = EventConfig::new("Standup", "2024-05-01", "09:00");
+        config.calendars = vec!["work".to_string()];
+
+        backend.create_single_event(config).await.unwrap();
+
+        let events = backend.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].title, "Standup");
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_backend_rejects_unknown_calendar() {
+        let backend = InMemoryBackend::new();
+        let mut config = EventConfig::new("Standup", "2024-05-01", "09:00");
+        config.calendars = vec!["Missing".to_string()];
+
+        assert!(backend.create_single_event(config).await.is_err());
+        assert!(backend.events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_backend_lists_events_in_range() {
+        let backend = InMemoryBackend::with_calendars(["Work", "Home"]);
+        for (title, date, calendar) in [
+            ("Standup", "2024-05-01", "Work"),
+            ("Dinner", "2024-05-02", "Home"),
+            ("Review", "2024-05-09", "Work"),
+        ] {
+            let mut config = EventConfig::new(title, date, "09:00");
+            config.calendars = vec![calendar.to_string()];
+            backend.create_single_event(config).await.unwrap();
+        }
+        let from = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 5, 7).unwrap();
+
+        let all = backend.list_events(&[], from, to).await.unwrap();
+        assert_eq!(all.iter().map(|e| e.title.as_str()).collect::<Vec<_>>(), ["Standup", "Dinner"]);
+        assert!(all.iter().all(|e| e.uid.is_some()));
+
+        let work = backend.list_events(&["work".to_string()], from, to).await.unwrap();
+        assert_eq!(work.len(), 1);
+        assert_eq!(work[0].title, "Standup");
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_backend_deletes_by_uid() {
+        let backend = InMemoryBackend::new();
+        let mut config = EventConfig::new("Standup", "2024-05-01", "09:00");
+        config.calendars = vec!["Calendar".to_string()];
+        config.uid = Some("uid-1".to_string());
+        backend.create_single_event(config).await.unwrap();
+
+        assert!(backend.delete_event("uid-2").await.is_err());
+        let deleted = backend.delete_event("uid-1").await.unwrap();
+        assert_eq!(deleted.title, "Standup");
+        assert!(backend.events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_backend_gets_and_updates_by_uid() {
+        let backend = InMemoryBackend::new();
+        let mut config = EventConfig::new("Standup", "2024-05-01", "09:00");
+        config.calendars = vec!["Calendar".to_string()];
+        config.uid = Some("uid-1".to_string());
+        backend.create_single_event(config).await.unwrap();
+
+        let mut event = backend.get_event("uid-1").await.unwrap();
+        assert!(backend.get_event("uid-2").await.is_err());
+
+        event.start_time = "10:00".to_string();
+        let updated = backend.update_event(&event).await.unwrap();
+        assert_eq!(updated.start_time, "10:00");
+        assert_eq!(backend.events()[0].start_time, "10:00");
+
+        event.uid = Some("uid-2".to_string());
+        assert!(backend.update_event(&event).await.is_err());
+    }
+}
@@ -219,6 +219,25 @@ async fn lookup_by_name_part(name_part: &str, part_type: &str) -> Result<Vec<Str
     }
 }
 
+/// Look up the email addresses of several contacts; names without an email
+/// address are reported and skipped
+pub async fn lookup_contact_emails(names: &[&str]) -> Vec<String> {
+    let mut emails = Vec::new();
+    for name in names {
+        match lookup_contact(name).await {
+            Ok(found) if found.is_empty() => {
+                eprintln!("{} No email address found for contact '{}'", "WARNING:".yellow(), name);
+            }
+            Ok(found) => emails.extend(found.into_iter().map(|e| e.trim().to_string())),
+            Err(e) => {
+                eprintln!("{} Error looking up contact '{}': {}", "ERROR:".red(), name, e);
+                error!("Failed to lookup contact '{}': {}", name, e);
+            }
+        }
+    }
+    emails
+}
+
 /// Enhanced event creation with contact lookup
 pub async fn create_event_with_contacts(
     mut config: EventConfig,
@@ -0,0 +1,276 @@
+//! Export calendar events as RFC 5545 iCalendar (.ics) data.
+//
+// Events are read through the active calendar backend (see `export_events`)
+// and serialized here. Times in `EventConfig` are local, so timed events are
+// written in UTC and all-day events as plain dates.
+
+use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::calendar::EventConfig;
+
+/// Maximum length of a content line in octets, excluding the CRLF (RFC 5545 §3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Events to export for `from` to `to` (inclusive): those starting in the range,
+/// plus recurring series that started earlier and still occur in it
+///
+/// Recurring series are exported whole, with their RRULE and EXDATEs, so the
+/// file matches what the calendar shows for the range. Used by both the CLI
+/// and the HTTP export.
+pub async fn export_events(
+    calendars: &[String],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<EventConfig>> {
+    let mut events = crate::calendar::list_events(calendars, from, to).await?;
+    let earlier = crate::calendar::list_recurring_events(calendars, from).await?;
+    events.extend(earlier.into_iter().filter(|event| occurs_between(event, from, to)));
+    Ok(events)
+}
+
+// Whether a recurring event has an occurrence between `from` and `to` (inclusive)
+fn occurs_between(event: &EventConfig, from: NaiveDate, to: NaiveDate) -> bool {
+    let (Some(recurrence), Ok(first)) =
+        (&event.recurrence, NaiveDate::parse_from_str(&event.start_date, "%Y-%m-%d"))
+    else {
+        return false;
+    };
+    recurrence.occurrences(first, to).iter().any(|date| *date >= from)
+}
+
+/// Serialize events into a complete VCALENDAR document
+pub fn events_to_ics(events: &[EventConfig]) -> Result<String> {
+    let dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:-//DuckTape//DuckTape {}//EN", env!("CARGO_PKG_VERSION")),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+    for event in events {
+        lines.extend(
+            event_to_vevent(event, &dtstamp)
+                .map_err(|e| anyhow!("Failed to export event '{}': {}", event.title, e))?,
+        );
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = String::new();
+    for line in &lines {
+        ics.push_str(&fold_line(line));
+        ics.push_str("\r\n");
+    }
+    Ok(ics)
+}
+
+/// Build the unfolded content lines of a single VEVENT
+fn event_to_vevent(event: &EventConfig, dtstamp: &str) -> Result<Vec<String>> {
+    let (start, end) = event.time_span()?;
+    let uid = event
+        .uid
+        .clone()
+        .unwrap_or_else(|| format!("{}@ducktape", uuid::Uuid::new_v4()));
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", escape_text(&uid)),
+        format!("DTSTAMP:{}", dtstamp),
+    ];
+
+    let start_time = if event.all_day {
+        // DTEND of an all-day event is exclusive, as is the end of the span
+        lines.push(format!("DTSTART;VALUE=DATE:{}", start.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+        None
+    } else {
+        lines.push(format!("DTSTART:{}", format_utc(start)?));
+        lines.push(format!("DTEND:{}", format_utc(end)?));
+        Some(start.time())
+    };
+
+    lines.push(format!("SUMMARY:{}", escape_text(&event.title)));
+    if let Some(location) = event.location.as_deref().filter(|l| !l.is_empty()) {
+        lines.push(format!("LOCATION:{}", escape_text(location)));
+    }
+    if let Some(description) = event.description.as_deref().filter(|d| !d.is_empty()) {
+        lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+    }
+    for email in &event.emails {
+        lines.push(format!("ATTENDEE;RSVP=TRUE:mailto:{}", email));
+    }
+
+    if let Some(recurrence) = &event.recurrence {
+        lines.push(format!("RRULE:{}", recurrence.to_rrule()?));
+        for date in &recurrence.exception_dates {
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|e| anyhow!("Invalid recurrence exception date '{}': {}", date, e))?;
+            match start_time {
+                Some(time) => lines.push(format!("EXDATE:{}", format_utc(date.and_time(time))?)),
+                None => lines.push(format!("EXDATE;VALUE=DATE:{}", date.format("%Y%m%d"))),
+            }
+        }
+    }
+
+    if let Some(minutes) = event.reminder {
+        lines.push("BEGIN:VALARM".to_string());
+        lines.push("ACTION:DISPLAY".to_string());
+        lines.push(format!("DESCRIPTION:{}", escape_text(&event.title)));
+        lines.push(format!("TRIGGER:-PT{}M", minutes));
+        lines.push("END:VALARM".to_string());
+    }
+
+    lines.push("END:VEVENT".to_string());
+    Ok(lines)
+}
+
+/// Convert a local date-time to an RFC 5545 UTC timestamp
+fn format_utc(local: NaiveDateTime) -> Result<String> {
+    let local = Local
+        .from_local_datetime(&local)
+        .earliest()
+        .ok_or_else(|| anyhow!("{} does not exist in the local timezone", local))?;
+    Ok(local.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+/// Escape a TEXT property value (RFC 5545 §3.3.11)
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Fold a content line at 75 octets without splitting UTF-8 characters
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut line_octets = 0;
+    for c in line.chars() {
+        if line_octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            line_octets = 1;
+        }
+        folded.push(c);
+        line_octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::{
+        OrdinalWeekday, RecurrenceFrequency, RecurrencePattern, ical_event_to_config,
+    };
+    use ical::IcalParser;
+
+    fn parse_back(ics: &str) -> Vec<EventConfig> {
+        IcalParser::new(ics.as_bytes())
+            .flat_map(|calendar| calendar.unwrap().events)
+            .map(|event| ical_event_to_config(&event, None).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_events_to_ics_writes_timed_event() {
+        let mut event = EventConfig::new("Planning; Q3, draft", "2024-06-03", "10:00");
+        event.end_time = Some("11:30".to_string());
+        event.uid = Some("ABC-1".to_string());
+        event.location = Some("Room 1".to_string());
+        event.description = Some("Agenda\nNotes".to_string());
+        event.emails = vec!["joe@example.com".to_string()];
+        event.reminder = Some(15);
+
+        let ics = events_to_ics(&[event]).unwrap();
+        let start = format_utc(
+            NaiveDateTime::parse_from_str("2024-06-03 10:00", "%Y-%m-%d %H:%M").unwrap(),
+        )
+        .unwrap();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:ABC-1\r\n"));
+        assert!(ics.contains(&format!("DTSTART:{}\r\n", start)));
+        assert!(ics.contains("SUMMARY:Planning\\; Q3\\, draft\r\n"));
+        assert!(ics.contains("DESCRIPTION:Agenda\\nNotes\r\n"));
+        assert!(ics.contains("ATTENDEE;RSVP=TRUE:mailto:joe@example.com\r\n"));
+        assert!(ics.contains("TRIGGER:-PT15M\r\n"));
+    }
+
+    #[test]
+    fn test_events_to_ics_writes_all_day_event_with_exclusive_end() {
+        let mut event = EventConfig::new("Holiday", "2024-06-10", "00:00");
+        event.all_day = true;
+        event.end_date = Some("2024-06-12".to_string());
+
+        let ics = events_to_ics(&[event]).unwrap();
+        assert!(ics.contains("DTSTART;VALUE=DATE:20240610\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20240613\r\n"));
+    }
+
+    #[test]
+    fn test_events_to_ics_writes_recurrence() {
+        let recurrence = RecurrencePattern::new(RecurrenceFrequency::Monthly)
+            .with_ordinal_weekdays(&[OrdinalWeekday::new(2, 2)])
+            .with_count(6)
+            .with_exception_dates(&["2024-08-13"]);
+        let mut event =
+            EventConfig::new("Review", "2024-07-09", "09:00").with_recurrence(recurrence);
+        event.all_day = true;
+
+        let ics = events_to_ics(&[event]).unwrap();
+        assert!(ics.contains("RRULE:FREQ=MONTHLY;INTERVAL=1;COUNT=6;BYDAY=2TU\r\n"));
+        assert!(ics.contains("EXDATE;VALUE=DATE:20240813\r\n"));
+    }
+
+    #[test]
+    fn test_events_to_ics_round_trips_through_import() {
+        let mut event = EventConfig::new("Offsite", "2024-06-04", "09:00");
+        event.end_time = Some("17:00".to_string());
+        event.location = Some("Main office, floor 2".to_string());
+        event.emails = vec!["joe@example.com".to_string(), "ann@example.com".to_string()];
+        event.reminder = Some(30);
+        event.recurrence = Some(
+            RecurrencePattern::new(RecurrenceFrequency::Weekly)
+                .with_days_of_week(&[2])
+                .with_end_date("2024-12-31"),
+        );
+
+        let imported = parse_back(&events_to_ics(std::slice::from_ref(&event)).unwrap());
+        assert_eq!(imported.len(), 1);
+        let imported = &imported[0];
+        assert_eq!(imported.title, event.title);
+        assert_eq!(imported.start_date, event.start_date);
+        assert_eq!(imported.start_time, event.start_time);
+        assert_eq!(imported.end_time, event.end_time);
+        assert_eq!(imported.location, event.location);
+        assert_eq!(imported.emails, event.emails);
+        assert_eq!(imported.reminder, event.reminder);
+        assert_eq!(imported.recurrence, event.recurrence);
+    }
+
+    #[test]
+    fn test_events_to_ics_rejects_invalid_dates() {
+        let event = EventConfig::new("Broken", "June 3rd", "10:00");
+        assert!(events_to_ics(&[event]).is_err());
+    }
+
+    #[test]
+    fn test_fold_line_respects_octet_limit() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(100));
+        let folded = fold_line(&line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -0,0 +1,328 @@
+//! Free/busy computation for calendar events.
+//
+// Busy time comes from the events returned by `calendar::list_events`, and from
+// every occurrence of recurring series that started earlier. All-day events are
+// treated as free, matching Calendar.app's default availability for them, so
+// holidays and birthdays don't block a whole day.
+
+use std::fmt;
+
+use anyhow::{Result, anyhow};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use lazy_static::lazy_static;
+use log::debug;
+use regex::Regex;
+
+use crate::calendar::EventConfig;
+use crate::config::CalendarConfig;
+
+/// A span of local time from `start` up to, but not including, `end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeSlot {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl TimeSlot {
+    pub fn new(start: NaiveDateTime, end: NaiveDateTime) -> Self {
+        Self { start, end }
+    }
+
+    /// Whether the two slots share any time
+    pub fn overlaps(&self, other: &TimeSlot) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// Length of the slot in minutes
+    pub fn minutes(&self) -> i64 {
+        (self.end - self.start).num_minutes()
+    }
+}
+
+impl fmt::Display for TimeSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start.format("%H:%M"), self.end.format("%H:%M"))
+    }
+}
+
+/// The part of each day in which free slots are suggested
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkingHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl Default for WorkingHours {
+    fn default() -> Self {
+        Self {
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or(NaiveTime::MIN),
+            end: NaiveTime::from_hms_opt(17, 0, 0).unwrap_or(NaiveTime::MIN),
+        }
+    }
+}
+
+impl WorkingHours {
+    /// Read `working_hours_start`/`working_hours_end`, defaulting to 09:00-17:00
+    pub fn from_config(config: &CalendarConfig) -> Result<Self> {
+        let defaults = Self::default();
+        let parse = |value: &Option<String>, default: NaiveTime| match value {
+            Some(time) => NaiveTime::parse_from_str(time.trim(), "%H:%M")
+                .map_err(|_| anyhow!("Invalid working hours time '{}'. Use HH:MM", time)),
+            None => Ok(default),
+        };
+        let hours = Self {
+            start: parse(&config.working_hours_start, defaults.start)?,
+            end: parse(&config.working_hours_end, defaults.end)?,
+        };
+        if hours.start >= hours.end {
+            return Err(anyhow!(
+                "Working hours must start before they end ({} - {})",
+                hours.start.format("%H:%M"),
+                hours.end.format("%H:%M")
+            ));
+        }
+        Ok(hours)
+    }
+
+    /// The working hours on a given day
+    pub fn on(&self, date: NaiveDate) -> TimeSlot {
+        TimeSlot::new(date.and_time(self.start), date.and_time(self.end))
+    }
+}
+
+/// The time an event blocks, or `None` for all-day events
+pub fn busy_slot(event: &EventConfig) -> Result<Option<TimeSlot>> {
+    if event.all_day {
+        return Ok(None);
+    }
+    let (start, end) = event.time_span()?;
+    Ok(Some(TimeSlot::new(start, end)))
+}
+
+/// The times an event blocks within `window`, one per occurrence of a
+/// recurring event
+pub fn busy_slots(event: &EventConfig, window: &TimeSlot) -> Result<Vec<TimeSlot>> {
+    let Some(first) = busy_slot(event)? else {
+        return Ok(Vec::new());
+    };
+    let Some(recurrence) = &event.recurrence else {
+        return Ok(if first.overlaps(window) { vec![first] } else { Vec::new() });
+    };
+    let length = first.end - first.start;
+    // Occurrences that start before the window can run into it
+    let from = (window.start - length).date();
+    Ok(recurrence
+        .occurrences(first.start.date(), window.end.date())
+        .into_iter()
+        .filter(|date| *date >= from)
+        .map(|date| {
+            let start = date.and_time(first.start.time());
+            TimeSlot::new(start, start + length)
+        })
+        .filter(|slot| slot.overlaps(window))
+        .collect())
+}
+
+/// Events from `existing` whose busy time overlaps `slot`
+pub fn conflicting_events(slot: &TimeSlot, existing: &[EventConfig]) -> Vec<EventConfig> {
+    existing
+        .iter()
+        .filter(|event| match busy_slots(event, slot) {
+            Ok(busy) => !busy.is_empty(),
+            Err(e) => {
+                debug!("Ignoring event '{}' for conflicts: {}", event.title, e);
+                false
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+/// Gaps of at least `min_minutes` in `window` that no busy slot covers
+pub fn free_slots(window: &TimeSlot, busy: &[TimeSlot], min_minutes: i64) -> Vec<TimeSlot> {
+    let mut busy: Vec<&TimeSlot> = busy.iter().filter(|b| b.overlaps(window)).collect();
+    busy.sort_by_key(|b| b.start);
+
+    let mut free = Vec::new();
+    let mut cursor = window.start;
+    for slot in busy {
+        if slot.start > cursor {
+            free.push(TimeSlot::new(cursor, slot.start));
+        }
+        cursor = cursor.max(slot.end);
+        if cursor >= window.end {
+            break;
+        }
+    }
+    if cursor < window.end {
+        free.push(TimeSlot::new(cursor, window.end));
+    }
+    free.retain(|slot| slot.minutes() >= min_minutes);
+    free
+}
+
+/// Existing events in the candidate's calendars that overlap it
+///
+/// All-day candidates never conflict. The day before the event is searched
+/// too, so that events running past midnight are found.
+pub async fn find_conflicts(candidate: &EventConfig) -> Result<Vec<EventConfig>> {
+    let Some(slot) = busy_slot(candidate)? else {
+        return Ok(Vec::new());
+    };
+    let from = slot.start.date() - Duration::days(1);
+    let to = (slot.end - Duration::minutes(1)).date();
+    let existing = events_between(&candidate.calendars, from, to).await?;
+    Ok(conflicting_events(&slot, &existing))
+}
+
+/// Open slots of at least `min_minutes` within working hours on `date`
+pub async fn find_free_slots(
+    date: NaiveDate,
+    min_minutes: i64,
+    calendars: &[String],
+    hours: &WorkingHours,
+) -> Result<Vec<TimeSlot>> {
+    if min_minutes <= 0 {
+        return Err(anyhow!("Duration must be at least one minute"));
+    }
+    let window = hours.on(date);
+    let events = events_between(calendars, date - Duration::days(1), date).await?;
+    let busy: Vec<TimeSlot> =
+        events.iter().filter_map(|e| busy_slots(e, &window).ok()).flatten().collect();
+    Ok(free_slots(&window, &busy, min_minutes))
+}
+
+// Events starting between `from` and `to`, and recurring series that started
+// before `from`
+async fn events_between(
+    calendars: &[String],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<EventConfig>> {
+    let mut events = crate::calendar::list_events(calendars, from, to).await?;
+    events.extend(crate::calendar::list_recurring_events(calendars, from).await?);
+    Ok(events)
+}
+
+lazy_static! {
+    static ref DURATION_RE: Regex =
+        Regex::new(r"^(?:(\d+)\s*h(?:ours?|rs?)?)?\s*(?:(\d+)\s*m(?:in(?:utes?|s)?)?)?$").unwrap();
+}
+
+/// Parse a duration such as `45`, `30m`, `1h` or `1h30m` into minutes
+pub fn parse_duration_minutes(value: &str) -> Result<i64> {
+    let value = value.trim().to_lowercase();
+    if let Ok(minutes) = value.parse::<i64>() {
+        return Ok(minutes);
+    }
+    match DURATION_RE.captures(&value) {
+        Some(caps) if caps.get(1).is_some() || caps.get(2).is_some() => {
+            let part = |i: usize| caps.get(i).map_or(Ok(0), |m| m.as_str().parse::<i64>());
+            Ok(part(1)? * 60 + part(2)?)
+        }
+        _ => Err(anyhow!("Invalid duration '{}'. Use minutes or a form like 1h30m", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn slot(start: &str, end: &str) -> TimeSlot {
+        TimeSlot::new(at(&format!("2024-06-03 {}", start)), at(&format!("2024-06-03 {}", end)))
+    }
+
+    fn event(title: &str, start: &str, end: &str) -> EventConfig {
+        let mut event = EventConfig::new(title, "2024-06-03", start);
+        event.end_time = Some(end.to_string());
+        event
+    }
+
+    #[test]
+    fn test_conflicting_events() {
+        let mut existing = vec![
+            event("Standup", "09:00", "09:15"),
+            event("Planning", "10:00", "11:00"),
+            event("Lunch", "11:00", "12:00"),
+        ];
+        let mut holiday = EventConfig::new("Holiday", "2024-06-03", "00:00");
+        holiday.all_day = true;
+        existing.push(holiday);
+
+        let conflicts = conflicting_events(&slot("10:30", "11:00"), &existing);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].title, "Planning");
+
+        // Back-to-back events do not conflict
+        assert!(conflicting_events(&slot("09:15", "10:00"), &existing).is_empty());
+    }
+
+    #[test]
+    fn test_overnight_event_blocks_next_morning() {
+        let mut late = EventConfig::new("Deploy", "2024-06-02", "23:00");
+        late.end_time = Some("01:00".to_string());
+        let conflicts = conflicting_events(&slot("00:30", "02:00"), &[late]);
+        assert_eq!(conflicts.len(), 1);
+    }
+
+    #[test]
+    fn test_recurring_event_blocks_later_occurrences() {
+        let mut standup = EventConfig::new("Standup", "2024-05-06", "09:00");
+        standup.end_time = Some("09:30".to_string());
+        standup.recurrence = Some(
+            crate::calendar::RecurrencePattern::new(crate::calendar::RecurrenceFrequency::Weekly)
+                .with_days_of_week(&[1, 3]),
+        );
+
+        // 2024-06-03 is a Monday
+        assert_eq!(
+            busy_slots(&standup, &slot("09:00", "17:00")).unwrap(),
+            vec![slot("09:00", "09:30")]
+        );
+        assert_eq!(conflicting_events(&slot("09:15", "10:00"), &[standup.clone()]).len(), 1);
+
+        let tuesday = TimeSlot::new(at("2024-06-04 09:00"), at("2024-06-04 17:00"));
+        assert!(busy_slots(&standup, &tuesday).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_free_slots_within_working_hours() {
+        let busy = vec![slot("08:00", "09:30"), slot("10:00", "11:00"), slot("10:30", "12:00")];
+        let free = free_slots(&slot("09:00", "17:00"), &busy, 30);
+        assert_eq!(free, vec![slot("09:30", "10:00"), slot("12:00", "17:00")]);
+
+        let long = free_slots(&slot("09:00", "17:00"), &busy, 60);
+        assert_eq!(long, vec![slot("12:00", "17:00")]);
+
+        assert!(free_slots(&slot("09:00", "17:00"), &[slot("08:00", "18:00")], 15).is_empty());
+    }
+
+    #[test]
+    fn test_working_hours_from_config() {
+        let mut config = CalendarConfig::default();
+        assert_eq!(WorkingHours::from_config(&config).unwrap(), WorkingHours::default());
+
+        config.working_hours_start = Some("08:30".to_string());
+        config.working_hours_end = Some("16:00".to_string());
+        let hours = WorkingHours::from_config(&config).unwrap();
+        assert_eq!(hours.on(NaiveDate::from_ymd_opt(2024, 6, 3).unwrap()), slot("08:30", "16:00"));
+
+        config.working_hours_end = Some("08:00".to_string());
+        assert!(WorkingHours::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_minutes() {
+        assert_eq!(parse_duration_minutes("45").unwrap(), 45);
+        assert_eq!(parse_duration_minutes("30m").unwrap(), 30);
+        assert_eq!(parse_duration_minutes("1h").unwrap(), 60);
+        assert_eq!(parse_duration_minutes("1h30m").unwrap(), 90);
+        assert_eq!(parse_duration_minutes("2 hours").unwrap(), 120);
+        assert!(parse_duration_minutes("soon").is_err());
+        assert!(parse_duration_minutes("").is_err());
+    }
+}
@@ -2,32 +2,1163 @@
 //
 // This module provides functions to import events from CSV and ICS files.
 
-use crate::calendar::calendar_types::RecurrencePattern;
-use anyhow::Result;
+use crate::calendar::calendar_types::{
+    EventConfig, OrdinalWeekday, RecurrenceFrequency, RecurrencePattern, weekday_from_rfc5545,
+};
+use crate::calendar::{create_event, validate_email, validate_event_config};
+use anyhow::{Result, anyhow};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use ical::parser::ical::component::{IcalAlarm, IcalEvent};
+use ical::property::Property;
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
 use std::path::Path;
 
+/// File formats supported by calendar import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportFormat {
+    #[default]
+    Ics,
+    Csv,
+}
+
+/// A single event that could not be imported
+#[derive(Debug, Clone)]
+pub struct ImportFailure {
+    /// 1-based event number for ICS files, or data row number for CSV files
+    pub position: usize,
+    /// Event title, if one could be read
+    pub title: Option<String>,
+    /// Why the event was skipped
+    pub reason: String,
+}
+
+/// Per-event outcome of a calendar import
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    /// Format of the imported file
+    pub format: ImportFormat,
+    /// Whether events were only validated, not created
+    pub dry_run: bool,
+    /// Titles of events that were created (or would be, in a dry run)
+    pub imported: Vec<String>,
+    /// Events that failed to parse, validate or be created
+    pub failed: Vec<ImportFailure>,
+}
+
+impl ImportReport {
+    /// Total number of events seen in the source file
+    pub fn total(&self) -> usize {
+        self.imported.len() + self.failed.len()
+    }
+
+    /// What `ImportFailure::position` counts in this report
+    pub fn position_unit(&self) -> &'static str {
+        match self.format {
+            ImportFormat::Ics => "event",
+            ImportFormat::Csv => "row",
+        }
+    }
+
+    /// Print a human readable summary of the import
+    pub fn print_summary(&self) {
+        let verb = if self.dry_run { "Would import" } else { "Imported" };
+        println!("{} {} of {} events", verb, self.imported.len(), self.total());
+        for failure in &self.failed {
+            println!(
+                "  Failed {} {} ({}): {}",
+                self.position_unit(),
+                failure.position,
+                failure.title.as_deref().unwrap_or("untitled"),
+                failure.reason
+            );
+        }
+    }
+}
+
+/// Event fields that a CSV column can be mapped to
+const CSV_FIELDS: &[(&str, &[&str])] = &[
+    ("title", &["title", "subject", "summary", "name"]),
+    ("date", &["date", "start_date", "start date"]),
+    ("start_time", &["start_time", "start time", "time", "start"]),
+    ("end_date", &["end_date", "end date"]),
+    ("end_time", &["end_time", "end time", "end"]),
+    ("calendar", &["calendar", "calendars"]),
+    ("location", &["location", "where"]),
+    ("description", &["description", "notes", "details"]),
+    ("emails", &["emails", "email", "attendees", "invitees"]),
+    ("all_day", &["all_day", "all day", "all day event", "allday"]),
+    ("reminder", &["reminder", "reminder_minutes", "alarm"]),
+    ("timezone", &["timezone", "time zone", "tz"]),
+    ("recurrence", &["recurrence", "rrule", "repeat"]),
+];
+
+/// Options controlling how a CSV file is read
+#[derive(Debug, Clone, Default)]
+pub struct CsvImportOptions {
+    /// Explicit column names keyed by event field (e.g. `title` -> `Subject`)
+    pub column_map: HashMap<String, String>,
+    /// Validate and print the events without creating them
+    pub dry_run: bool,
+}
+
+impl CsvImportOptions {
+    /// Start from the `[calendar.csv_mapping]` section of the application config
+    pub fn from_config(config: &crate::config::Config) -> Result<Self> {
+        let mut options = Self::default();
+        for (field, column) in &config.calendar.csv_mapping {
+            options = options.with_mapping(field, column)?;
+        }
+        Ok(options)
+    }
+
+    /// Add mappings from a `field=column` list such as `title=Subject,date=Start Date`
+    ///
+    /// Later mappings override earlier ones, so a `--map` argument can be layered
+    /// on top of the mapping section in config.toml.
+    pub fn with_mapping_spec(mut self, spec: &str) -> Result<Self> {
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (field, column) = pair.split_once('=').ok_or_else(|| {
+                anyhow!("Invalid column mapping '{}', expected field=column", pair)
+            })?;
+            self = self.with_mapping(field.trim(), column.trim())?;
+        }
+        Ok(self)
+    }
+
+    /// Map an event field to a CSV column name
+    pub fn with_mapping(mut self, field: &str, column: &str) -> Result<Self> {
+        let field = canonical_csv_field(field).ok_or_else(|| {
+            anyhow!(
+                "Unknown event field '{}'. Valid fields: {}",
+                field,
+                CSV_FIELDS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+            )
+        })?;
+        if column.is_empty() {
+            return Err(anyhow!("Column name for field '{}' is empty", field));
+        }
+        self.column_map.insert(field.to_string(), column.to_string());
+        Ok(self)
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+fn canonical_csv_field(name: &str) -> Option<&'static str> {
+    let name = name.trim();
+    CSV_FIELDS
+        .iter()
+        .find(|(field, aliases)| {
+            field.eq_ignore_ascii_case(name) || aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+        })
+        .map(|(field, _)| *field)
+}
+
+/// Resolve each event field to a column index in `headers`
+fn resolve_csv_columns(
+    headers: &csv::StringRecord,
+    column_map: &HashMap<String, String>,
+) -> Result<HashMap<&'static str, usize>> {
+    let find = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name.trim()));
+
+    let mut columns = HashMap::new();
+    for (field, aliases) in CSV_FIELDS {
+        let index = match column_map.get(*field) {
+            Some(column) => Some(find(column).ok_or_else(|| {
+                anyhow!("Column '{}' mapped to '{}' is not in the CSV header", column, field)
+            })?),
+            None => aliases.iter().find_map(|alias| find(alias)),
+        };
+        if let Some(index) = index {
+            columns.insert(*field, index);
+        }
+    }
+
+    for required in ["title", "date", "start_time"] {
+        if !columns.contains_key(required) {
+            return Err(anyhow!(
+                "CSV header has no column for '{}'. Use --map {}=<column> to choose one",
+                required,
+                required
+            ));
+        }
+    }
+    Ok(columns)
+}
+
+/// Normalize common date spellings to YYYY-MM-DD
+fn normalize_csv_date(value: &str) -> Result<String> {
+    ["%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y", "%d.%m.%Y", "%Y%m%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .ok_or_else(|| anyhow!("Invalid date '{}'", value))
+}
+
+/// Normalize common time spellings (e.g. `9:30`, `09:30:00`, `2:00 PM`) to HH:MM
+fn normalize_csv_time(value: &str) -> Result<String> {
+    let mut upper = value.to_uppercase();
+    // chrono needs minutes, so "10AM" becomes "10:00AM"
+    if !upper.contains(':') && (upper.ends_with("AM") || upper.ends_with("PM")) {
+        let hour_end = upper.find(|c: char| !c.is_ascii_digit()).unwrap_or(upper.len());
+        upper.insert_str(hour_end, ":00");
+    }
+    ["%H:%M", "%H:%M:%S", "%I:%M %p", "%I:%M%p", "%I:%M:%S %p"]
+        .iter()
+        .find_map(|format| chrono::NaiveTime::parse_from_str(&upper, format).ok())
+        .map(|time| time.format("%H:%M").to_string())
+        .ok_or_else(|| anyhow!("Invalid time '{}'", value))
+}
+
+fn parse_csv_bool(value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "y" | "1" => Ok(true),
+        "false" | "no" | "n" | "0" => Ok(false),
+        _ => Err(anyhow!("Invalid all_day value '{}'", value)),
+    }
+}
+
+/// Convert the start and end of `config` from `timezone` to the local timezone
+fn convert_csv_times_to_local(config: &mut EventConfig, timezone: &str) -> Result<()> {
+    let tz: Tz = timezone.parse().map_err(|_| anyhow!("Unknown time zone '{}'", timezone))?;
+    let to_local = |date: &str, time: &str| -> Result<NaiveDateTime> {
+        let naive = NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M")?;
+        let zoned = tz
+            .from_local_datetime(&naive)
+            .earliest()
+            .ok_or_else(|| anyhow!("Time {} does not exist in time zone {}", naive, timezone))?;
+        Ok(zoned.with_timezone(&Local).naive_local())
+    };
+
+    let start = to_local(&config.start_date, &config.start_time)?;
+    if let Some(end_time) = &config.end_time {
+        let end_date = config.end_date.as_deref().unwrap_or(&config.start_date);
+        let end = to_local(end_date, end_time)?;
+        config.end_time = Some(end.format("%H:%M").to_string());
+        // Keep an explicit end date, or add one when the conversion moved the
+        // end to a different day than the start
+        if config.end_date.is_some() || end.date() != start.date() {
+            config.end_date = Some(end.format("%Y-%m-%d").to_string());
+        }
+    } else if let Some(end_date) = &config.end_date {
+        config.end_date =
+            Some(to_local(end_date, &config.start_time)?.format("%Y-%m-%d").to_string());
+    }
+    config.start_date = start.format("%Y-%m-%d").to_string();
+    config.start_time = start.format("%H:%M").to_string();
+    Ok(())
+}
+
+/// Build an `EventConfig` from one CSV record
+fn csv_record_to_config(
+    record: &csv::StringRecord,
+    columns: &HashMap<&'static str, usize>,
+    target_calendar: Option<&str>,
+) -> Result<EventConfig> {
+    let get = |field: &str| {
+        columns
+            .get(field)
+            .and_then(|&i| record.get(i))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    let title = get("title").ok_or_else(|| anyhow!("Missing title"))?;
+    let date = normalize_csv_date(get("date").ok_or_else(|| anyhow!("Missing date"))?)?;
+    let all_day = get("all_day").map(parse_csv_bool).transpose()?.unwrap_or(false);
+    let start_time = match get("start_time") {
+        Some(time) => normalize_csv_time(time)?,
+        None if all_day => "00:00".to_string(),
+        None => return Err(anyhow!("Missing start time")),
+    };
+
+    let mut config = EventConfig::new(title, &date, &start_time);
+    config.all_day = all_day;
+    config.end_time = get("end_time").map(normalize_csv_time).transpose()?;
+    config.end_date = get("end_date").map(normalize_csv_date).transpose()?;
+    config.location = get("location").map(str::to_string);
+    config.description = get("description").map(str::to_string);
+    if let Some(timezone) = get("timezone") {
+        if !all_day {
+            convert_csv_times_to_local(&mut config, timezone)?;
+        }
+        config.timezone = Some(timezone.to_string());
+    }
+    if let Some(calendar) = get("calendar").or(target_calendar) {
+        config.calendars = vec![calendar.to_string()];
+    }
+    if let Some(emails) = get("emails") {
+        config.emails = emails
+            .split([',', ';', ' '])
+            .map(strip_mailto)
+            .filter(|e| !e.is_empty())
+            .map(str::to_string)
+            .collect();
+    }
+    if let Some(reminder) = get("reminder") {
+        config.reminder = Some(
+            reminder
+                .parse()
+                .map_err(|_| anyhow!("Invalid reminder '{}', expected minutes", reminder))?,
+        );
+    }
+    if let Some(rule) = get("recurrence") {
+        config.recurrence = Some(
+            parse_ical_recurrence(rule)
+                .ok_or_else(|| anyhow!("Invalid recurrence rule '{}'", rule))?,
+        );
+    }
+    Ok(config)
+}
+
 /// Import calendar events from a CSV file
-pub async fn import_csv_events(_file_path: &Path, _target_calendar: Option<String>) -> Result<i32> {
-    // TODO: Implement CSV import
-    println!("CSV import not yet implemented");
-    Ok(0)
+///
+/// The first row must be a header. Columns are matched to event fields by the
+/// names in `options.column_map`, falling back to common header names such as
+/// `title`, `date`, `start_time` and `end_time`. Times in a row with a
+/// `timezone` column are converted from that zone to local time. Each row is
+/// validated with `validate_event_config`; failures are reported by line
+/// number. In a dry run the valid events are printed instead of created.
+pub async fn import_csv_events(
+    file_path: &Path,
+    target_calendar: Option<String>,
+    options: &CsvImportOptions,
+) -> Result<ImportReport> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_path(file_path)
+        .map_err(|e| anyhow!("Failed to open {}: {}", file_path.display(), e))?;
+    let headers = reader
+        .headers()
+        .map_err(|e| anyhow!("Failed to read CSV header: {}", e))?
+        .clone();
+    let columns = resolve_csv_columns(&headers, &options.column_map)?;
+
+    let mut report =
+        ImportReport { format: ImportFormat::Csv, dry_run: options.dry_run, ..Default::default() };
+    // Count data rows ourselves: the reader's line numbers include the header
+    // and every line of a multi-line quoted field
+    let mut position = 0;
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                position += 1;
+                report
+                    .failed
+                    .push(ImportFailure { position, title: None, reason: e.to_string() });
+                continue;
+            }
+        };
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+        position += 1;
+        let title = columns.get("title").and_then(|&i| record.get(i)).map(str::to_string);
+
+        let result = match csv_record_to_config(&record, &columns, target_calendar.as_deref()) {
+            Ok(config) => match validate_event_config(&config) {
+                Ok(()) if options.dry_run => {
+                    print_dry_run_event(position, &config);
+                    Ok(config)
+                }
+                Ok(()) => create_event(config.clone()).await.map(|_| config),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(config) => report.imported.push(config.title),
+            Err(e) => {
+                warn!("Failed to import row {} from {}: {}", position, file_path.display(), e);
+                report.failed.push(ImportFailure { position, title, reason: e.to_string() });
+            }
+        }
+    }
+
+    if report.total() == 0 {
+        return Err(anyhow!("No events found in {}", file_path.display()));
+    }
+    Ok(report)
+}
+
+fn print_dry_run_event(position: usize, config: &EventConfig) {
+    let time = if config.all_day {
+        "all day".to_string()
+    } else {
+        match &config.end_time {
+            Some(end) => format!("{}-{}", config.start_time, end),
+            None => config.start_time.clone(),
+        }
+    };
+    let calendar = config.calendars.first().map(String::as_str).unwrap_or("default calendar");
+    println!(
+        "Row {}: {} on {} {} [{}]",
+        position, config.title, config.start_date, time, calendar
+    );
+    if let Some(location) = &config.location {
+        println!("    Location: {}", location);
+    }
+    if !config.emails.is_empty() {
+        println!("    Attendees: {}", config.emails.join(", "));
+    }
+    if let Some(recurrence) = &config.recurrence {
+        if let Ok(rule) = recurrence.to_rrule() {
+            println!("    Repeats: {}", rule);
+        }
+    }
 }
 
 /// Import calendar events from an iCalendar (.ics) file
-pub async fn import_ics_events(_file_path: &Path, _target_calendar: Option<String>) -> Result<i32> {
-    // TODO: Implement ICS import
-    println!("ICS import not yet implemented");
-    Ok(0)
+///
+/// Every VEVENT in the file is converted to an `EventConfig` and created in
+/// `target_calendar` (or the default calendar). A failure in one event does not
+/// stop the others; the returned report lists each success and failure.
+pub async fn import_ics_events(
+    file_path: &Path,
+    target_calendar: Option<String>,
+) -> Result<ImportReport> {
+    let file = File::open(file_path)
+        .map_err(|e| anyhow!("Failed to open {}: {}", file_path.display(), e))?;
+    let parser = ical::IcalParser::new(BufReader::new(file));
+
+    let mut report = ImportReport::default();
+    let mut position = 0;
+    for calendar in parser {
+        let calendar = calendar.map_err(|e| anyhow!("Invalid iCalendar data: {}", e))?;
+        for event in &calendar.events {
+            position += 1;
+            let title = property_text(event, "SUMMARY");
+            match import_ical_event(event, target_calendar.as_deref()).await {
+                Ok(config) => {
+                    info!("Imported event '{}' from {}", config.title, file_path.display());
+                    report.imported.push(config.title);
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to import event {} from {}: {}",
+                        position,
+                        file_path.display(),
+                        e
+                    );
+                    report.failed.push(ImportFailure { position, title, reason: e.to_string() });
+                }
+            }
+        }
+    }
+
+    if position == 0 {
+        return Err(anyhow!("No events found in {}", file_path.display()));
+    }
+    Ok(report)
 }
 
-/// Import a single iCal event
-pub async fn import_ical_event(/* params */) -> Result<()> {
-    // ...implementation moved from calendar.rs...
-    Ok(())
+/// Import a single iCal event into `target_calendar`, returning the created config
+pub async fn import_ical_event(
+    event: &IcalEvent,
+    target_calendar: Option<&str>,
+) -> Result<EventConfig> {
+    let config = ical_event_to_config(event, target_calendar)?;
+    create_event(config.clone()).await?;
+    Ok(config)
+}
+
+/// Convert a parsed VEVENT into an `EventConfig` with times in the local timezone
+pub fn ical_event_to_config(
+    event: &IcalEvent,
+    target_calendar: Option<&str>,
+) -> Result<EventConfig> {
+    let title = property_text(event, "SUMMARY")
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| anyhow!("Event has no SUMMARY"))?;
+    let dtstart = find_property(&event.properties, "DTSTART")
+        .ok_or_else(|| anyhow!("Event '{}' has no DTSTART", title))?;
+    let start = parse_ical_datetime(dtstart)?;
+
+    let end = match find_property(&event.properties, "DTEND") {
+        Some(dtend) => Some(parse_ical_datetime(dtend)?),
+        None => match find_property(&event.properties, "DURATION").and_then(|p| p.value.as_deref())
+        {
+            Some(value) => {
+                let duration = parse_ical_duration(value)
+                    .ok_or_else(|| anyhow!("Invalid DURATION '{}'", value))?;
+                Some(start.offset(duration))
+            }
+            None => None,
+        },
+    };
+
+    let mut config = EventConfig::new(title.trim(), "", "00:00");
+    config.timezone = start.timezone.clone();
+    match start.value {
+        IcalDateTime::Date(date) => {
+            config.all_day = true;
+            config.start_date = date.format("%Y-%m-%d").to_string();
+            // DTEND of an all-day event is exclusive
+            if let Some(IcalDateTime::Date(end_date)) = end.map(|e| e.value) {
+                let last_day = end_date.pred_opt().unwrap_or(end_date);
+                if last_day > date {
+                    config.end_date = Some(last_day.format("%Y-%m-%d").to_string());
+                }
+            }
+        }
+        IcalDateTime::DateTime(start_dt) => {
+            config.start_date = start_dt.format("%Y-%m-%d").to_string();
+            config.start_time = start_dt.format("%H:%M").to_string();
+            if let Some(end) = end {
+                let end_dt = match end.value {
+                    IcalDateTime::DateTime(dt) => dt,
+                    IcalDateTime::Date(d) => d.and_hms_opt(0, 0, 0).unwrap_or(start_dt),
+                };
+                if end_dt > start_dt {
+                    config.end_time = Some(end_dt.format("%H:%M").to_string());
+                    if end_dt.date() != start_dt.date() {
+                        config.end_date = Some(end_dt.format("%Y-%m-%d").to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(calendar) = target_calendar {
+        config.calendars = vec![calendar.to_string()];
+    }
+    config.location = property_text(event, "LOCATION").filter(|s| !s.is_empty());
+    config.description = property_text(event, "DESCRIPTION").filter(|s| !s.is_empty());
+
+    for attendee in event.properties.iter().filter(|p| p.name.eq_ignore_ascii_case("ATTENDEE")) {
+        let Some(value) = attendee.value.as_deref() else { continue };
+        let email = strip_mailto(value);
+        if validate_email(email) {
+            if !config.emails.iter().any(|e| e.eq_ignore_ascii_case(email)) {
+                config.emails.push(email.to_string());
+            }
+        } else {
+            debug!("Skipping attendee with invalid address: {}", value);
+        }
+    }
+
+    config.reminder = event.alarms.iter().find_map(alarm_minutes_before);
+
+    if let Some(rrule) = find_property(&event.properties, "RRULE").and_then(|p| p.value.as_deref())
+    {
+        config.recurrence = parse_ical_recurrence(rrule);
+        match config.recurrence.as_mut() {
+            Some(recurrence) => recurrence.exception_dates = parse_exception_dates(event)?,
+            None => warn!(
+                "Unsupported RRULE '{}' for event '{}'; importing as a single event",
+                rrule, title
+            ),
+        }
+    }
+
+    Ok(config)
+}
+
+/// Parse an RFC 5545 recurrence rule such as `FREQ=MONTHLY;BYDAY=TU;BYSETPOS=2`
+///
+/// Supports FREQ (daily through yearly), INTERVAL, COUNT, UNTIL, BYDAY (with
+/// optional ordinals like `2TU` or `-1FR`), BYMONTHDAY, BYMONTH, BYSETPOS and
+/// WKST. Returns `None` if the rule is malformed or uses an unsupported
+/// frequency. EXDATE is a separate property and is not handled here.
+pub fn parse_ical_recurrence(rrule: &str) -> Option<RecurrencePattern> {
+    let rule = rrule.trim();
+    let rule = match rule.get(..6) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("RRULE:") => &rule[6..],
+        _ => rule,
+    };
+
+    let mut parts = Vec::new();
+    for part in rule.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        let (key, value) = part.split_once('=')?;
+        parts.push((key.trim().to_ascii_uppercase(), value.trim()));
+    }
+
+    let frequency =
+        match parts.iter().find(|(key, _)| key == "FREQ")?.1.to_ascii_uppercase().as_str() {
+            "DAILY" => RecurrenceFrequency::Daily,
+            "WEEKLY" => RecurrenceFrequency::Weekly,
+            "MONTHLY" => RecurrenceFrequency::Monthly,
+            "YEARLY" => RecurrenceFrequency::Yearly,
+            other => {
+                debug!("Unsupported recurrence frequency: {}", other);
+                return None;
+            }
+        };
+    let mut pattern = RecurrencePattern::new(frequency);
+
+    for (key, value) in parts {
+        match key.as_str() {
+            "FREQ" => {}
+            "INTERVAL" => pattern.interval = value.parse().ok().filter(|&i| i > 0)?,
+            "COUNT" => pattern.count = Some(value.parse().ok()?),
+            "UNTIL" => pattern.end_date = Some(parse_until(value)?),
+            "BYDAY" => {
+                for day in value.split(',') {
+                    let day = day.trim();
+                    let split = day.len().checked_sub(2)?;
+                    let weekday = weekday_from_rfc5545(day.get(split..)?)?;
+                    match day.get(..split)? {
+                        "" => pattern.days_of_week.push(weekday),
+                        ordinal => {
+                            let ordinal: i8 = ordinal.parse().ok()?;
+                            if ordinal == 0 || !(-53..=53).contains(&ordinal) {
+                                return None;
+                            }
+                            pattern.ordinal_weekdays.push(OrdinalWeekday::new(ordinal, weekday));
+                        }
+                    }
+                }
+            }
+            "BYMONTHDAY" => {
+                pattern.days_of_month =
+                    parse_number_list(value, |d: &i8| *d != 0 && (-31..=31).contains(d))?
+            }
+            "BYMONTH" => pattern.months = parse_number_list(value, |m: &u8| (1..=12).contains(m))?,
+            "BYSETPOS" => {
+                pattern.set_positions =
+                    parse_number_list(value, |p: &i16| *p != 0 && (-366..=366).contains(p))?
+            }
+            "WKST" => pattern.week_start = Some(weekday_from_rfc5545(value)?),
+            other => debug!("Ignoring unsupported RRULE part: {}={}", other, value),
+        }
+    }
+
+    Some(pattern)
+}
+
+/// Parse a comma-separated list of numbers, rejecting values that fail `valid`
+fn parse_number_list<T: std::str::FromStr>(
+    value: &str,
+    valid: impl Fn(&T) -> bool,
+) -> Option<Vec<T>> {
+    value
+        .split(',')
+        .map(|v| v.trim().parse::<T>().ok().filter(|n| valid(n)))
+        .collect()
+}
+
+/// Convert an RRULE UNTIL value (date or date-time) to a YYYY-MM-DD date
+fn parse_until(value: &str) -> Option<String> {
+    let date = if value.len() == 8 {
+        NaiveDate::parse_from_str(value, "%Y%m%d").ok()?
+    } else if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        Utc.from_utc_datetime(&naive).with_timezone(&Local).date_naive()
+    } else {
+        NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?.date()
+    };
+    Some(date.format("%Y-%m-%d").to_string())
+}
+
+/// Collect all EXDATE values of an event as local YYYY-MM-DD dates
+fn parse_exception_dates(event: &IcalEvent) -> Result<Vec<String>> {
+    let mut dates = Vec::new();
+    for property in event.properties.iter().filter(|p| p.name.eq_ignore_ascii_case("EXDATE")) {
+        let Some(values) = property.value.as_deref() else { continue };
+        for raw in values.split(',').filter(|v| !v.trim().is_empty()) {
+            let date = match parse_ical_datetime_value(property, raw)?.value {
+                IcalDateTime::Date(d) => d,
+                IcalDateTime::DateTime(dt) => dt.date(),
+            };
+            let date = date.format("%Y-%m-%d").to_string();
+            if !dates.contains(&date) {
+                dates.push(date);
+            }
+        }
+    }
+    Ok(dates)
+}
+
+/// A DTSTART/DTEND value, already converted to local time
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IcalDateTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+#[derive(Debug, Clone)]
+struct ParsedDateTime {
+    value: IcalDateTime,
+    /// TZID the value was originally expressed in
+    timezone: Option<String>,
+}
+
+impl ParsedDateTime {
+    fn offset(&self, duration: Duration) -> ParsedDateTime {
+        let value = match self.value {
+            IcalDateTime::Date(d) => IcalDateTime::Date(d + duration),
+            IcalDateTime::DateTime(dt) => IcalDateTime::DateTime(dt + duration),
+        };
+        ParsedDateTime { value, timezone: self.timezone.clone() }
+    }
+}
+
+fn find_property<'a>(properties: &'a [Property], name: &str) -> Option<&'a Property> {
+    properties.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+fn find_param<'a>(property: &'a Property, name: &str) -> Option<&'a str> {
+    property
+        .params
+        .as_ref()?
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, values)| values.first())
+        .map(|v| v.trim_matches('"'))
+}
+
+fn property_text(event: &IcalEvent, name: &str) -> Option<String> {
+    find_property(&event.properties, name)
+        .and_then(|p| p.value.as_deref())
+        .map(unescape_text)
+}
+
+/// Undo RFC 5545 TEXT escaping (`\,` `\;` `\\` `\n`)
+fn unescape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+fn strip_mailto(value: &str) -> &str {
+    let trimmed = value.trim();
+    match trimmed.get(..7) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("mailto:") => &trimmed[7..],
+        _ => trimmed,
+    }
+}
+
+/// Parse a DTSTART/DTEND property, honouring VALUE=DATE, UTC (`Z`) and TZID forms
+fn parse_ical_datetime(property: &Property) -> Result<ParsedDateTime> {
+    let raw = property
+        .value
+        .as_deref()
+        .ok_or_else(|| anyhow!("{} has no value", property.name))?;
+    parse_ical_datetime_value(property, raw)
+}
+
+/// Parse one date or date-time value using the parameters of `property`
+fn parse_ical_datetime_value(property: &Property, raw: &str) -> Result<ParsedDateTime> {
+    let raw = raw.trim();
+    let is_date = find_param(property, "VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE"))
+        || (raw.len() == 8 && !raw.contains('T'));
+    if is_date {
+        let date = NaiveDate::parse_from_str(raw, "%Y%m%d")
+            .map_err(|e| anyhow!("Invalid date '{}' in {}: {}", raw, property.name, e))?;
+        return Ok(ParsedDateTime { value: IcalDateTime::Date(date), timezone: None });
+    }
+
+    let (naive_str, is_utc) = match raw.strip_suffix('Z') {
+        Some(stripped) => (stripped, true),
+        None => (raw, false),
+    };
+    let naive = NaiveDateTime::parse_from_str(naive_str, "%Y%m%dT%H%M%S")
+        .map_err(|e| anyhow!("Invalid date-time '{}' in {}: {}", raw, property.name, e))?;
+
+    if is_utc {
+        let local = Utc.from_utc_datetime(&naive).with_timezone(&Local).naive_local();
+        return Ok(ParsedDateTime {
+            value: IcalDateTime::DateTime(local),
+            timezone: Some("UTC".to_string()),
+        });
+    }
+
+    match find_param(property, "TZID") {
+        Some(tzid) => {
+            let tz: Tz = tzid
+                .parse()
+                .map_err(|_| anyhow!("Unknown time zone '{}' in {}", tzid, property.name))?;
+            let zoned = tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .ok_or_else(|| anyhow!("Time {} does not exist in time zone {}", naive, tzid))?;
+            Ok(ParsedDateTime {
+                value: IcalDateTime::DateTime(zoned.with_timezone(&Local).naive_local()),
+                timezone: Some(tzid.to_string()),
+            })
+        }
+        // Floating time: already local
+        None => Ok(ParsedDateTime { value: IcalDateTime::DateTime(naive), timezone: None }),
+    }
+}
+
+/// Parse an RFC 5545 duration such as `PT1H30M`, `-PT15M` or `P1D`
+fn parse_ical_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (negative, rest) = match value.as_bytes().first()? {
+        b'-' => (true, &value[1..]),
+        b'+' => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let rest = rest.strip_prefix('P')?;
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+    Some(if negative { -total } else { total })
+}
+
+/// Minutes before the event start at which a VALARM fires, if it is a relative trigger
+fn alarm_minutes_before(alarm: &IcalAlarm) -> Option<i32> {
+    let trigger = find_property(&alarm.properties, "TRIGGER")?;
+    if find_param(trigger, "VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE-TIME")) {
+        return None;
+    }
+    if find_param(trigger, "RELATED").is_some_and(|v| v.eq_ignore_ascii_case("END")) {
+        return None;
+    }
+    let duration = parse_ical_duration(trigger.value.as_deref()?)?;
+    if duration > Duration::zero() {
+        return None;
+    }
+    i32::try_from((-duration).num_minutes()).ok()
 }
 
-/// Parse iCal recurrence rule
-pub fn parse_ical_recurrence(_rrule: &str) -> Option<RecurrencePattern> {
-    // ...implementation moved from calendar.rs...
-    None
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn parse_events(ics: &str) -> Vec<IcalEvent> {
+        ical::IcalParser::new(Cursor::new(ics.as_bytes()))
+            .flat_map(|cal| cal.expect("valid calendar").events)
+            .collect()
+    }
+
+    #[test]
+    fn test_floating_event_with_attendees_and_alarm() {
+        let events = parse_events(
+            "BEGIN:VCALENDAR\r\n\
+             VERSION:2.0\r\n\
+             BEGIN:VEVENT\r\n\
+             SUMMARY:Quarterly planning\\, Q3\r\n\
+             DTSTART:20240610T090000\r\n\
+             DTEND:20240610T103000\r\n\
+             LOCATION:Room 4\r\n\
+             DESCRIPTION:Agenda:\\nBudget\r\n\
+             ATTENDEE;CN=Joe:mailto:joe@example.com\r\n\
+             ATTENDEE:MAILTO:jane@example.com\r\n\
+             ATTENDEE:mailto:not-an-email\r\n\
+             BEGIN:VALARM\r\n\
+             ACTION:DISPLAY\r\n\
+             TRIGGER:-PT15M\r\n\
+             END:VALARM\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        );
+        let config = ical_event_to_config(&events[0], Some("Work")).unwrap();
+
+        assert_eq!(config.title, "Quarterly planning, Q3");
+        assert_eq!(config.start_date, "2024-06-10");
+        assert_eq!(config.start_time, "09:00");
+        assert_eq!(config.end_time.as_deref(), Some("10:30"));
+        assert_eq!(config.end_date, None);
+        assert_eq!(config.calendars, vec!["Work".to_string()]);
+        assert_eq!(config.location.as_deref(), Some("Room 4"));
+        assert_eq!(config.description.as_deref(), Some("Agenda:\nBudget"));
+        assert_eq!(config.emails, vec!["joe@example.com", "jane@example.com"]);
+        assert_eq!(config.reminder, Some(15));
+        assert!(!config.all_day);
+    }
+
+    #[test]
+    fn test_all_day_event() {
+        let events = parse_events(
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\n\
+             SUMMARY:Offsite\r\n\
+             DTSTART;VALUE=DATE:20240701\r\n\
+             DTEND;VALUE=DATE:20240703\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        );
+        let config = ical_event_to_config(&events[0], None).unwrap();
+
+        assert!(config.all_day);
+        assert_eq!(config.start_date, "2024-07-01");
+        assert_eq!(config.end_date.as_deref(), Some("2024-07-02"));
+        assert!(config.calendars.is_empty());
+    }
+
+    #[test]
+    fn test_tzid_and_utc_times_are_converted_to_local() {
+        let events = parse_events(
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\n\
+             SUMMARY:Zoned\r\n\
+             DTSTART;TZID=America/New_York:20240115T090000\r\n\
+             DURATION:PT45M\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             SUMMARY:Utc\r\n\
+             DTSTART:20240115T140000Z\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        );
+        // 09:00 in New York in January is 14:00 UTC
+        let expected = Utc.with_ymd_and_hms(2024, 1, 15, 14, 0, 0).unwrap().with_timezone(&Local);
+        let expected_end = expected + Duration::minutes(45);
+
+        let zoned = ical_event_to_config(&events[0], None).unwrap();
+        assert_eq!(zoned.start_date, expected.format("%Y-%m-%d").to_string());
+        assert_eq!(zoned.start_time, expected.format("%H:%M").to_string());
+        assert_eq!(zoned.end_time, Some(expected_end.format("%H:%M").to_string()));
+        assert_eq!(zoned.timezone.as_deref(), Some("America/New_York"));
+
+        let utc = ical_event_to_config(&events[1], None).unwrap();
+        assert_eq!(utc.start_time, expected.format("%H:%M").to_string());
+        assert_eq!(utc.timezone.as_deref(), Some("UTC"));
+    }
+
+    #[test]
+    fn test_event_errors() {
+        let events = parse_events(
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\n\
+             DTSTART:20240115T090000\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             SUMMARY:No start\r\n\
+             END:VEVENT\r\n\
+             BEGIN:VEVENT\r\n\
+             SUMMARY:Bad zone\r\n\
+             DTSTART;TZID=Mars/Olympus:20240115T090000\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        );
+        assert!(ical_event_to_config(&events[0], None).is_err());
+        assert!(ical_event_to_config(&events[1], None).is_err());
+        let err = ical_event_to_config(&events[2], None).unwrap_err();
+        assert!(err.to_string().contains("Mars/Olympus"));
+    }
+
+    #[test]
+    fn test_parse_ical_recurrence_simple_rules() {
+        let weekly =
+            parse_ical_recurrence("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR;COUNT=10").unwrap();
+        assert_eq!(weekly.frequency, RecurrenceFrequency::Weekly);
+        assert_eq!(weekly.interval, 2);
+        assert_eq!(weekly.count, Some(10));
+        assert_eq!(weekly.days_of_week, vec![1, 3, 5]);
+
+        let daily = parse_ical_recurrence("RRULE:FREQ=DAILY;UNTIL=20251231").unwrap();
+        assert_eq!(daily.frequency, RecurrenceFrequency::Daily);
+        assert_eq!(daily.end_date.as_deref(), Some("2025-12-31"));
+
+        let floating = parse_ical_recurrence("FREQ=DAILY;UNTIL=20251231T090000").unwrap();
+        assert_eq!(floating.end_date.as_deref(), Some("2025-12-31"));
+    }
+
+    #[test]
+    fn test_parse_ical_recurrence_positional_rules() {
+        let second_tuesday = parse_ical_recurrence("FREQ=MONTHLY;BYDAY=TU;BYSETPOS=2").unwrap();
+        assert_eq!(second_tuesday.days_of_week, vec![2]);
+        assert_eq!(second_tuesday.set_positions, vec![2]);
+
+        let last_friday = parse_ical_recurrence("FREQ=MONTHLY;BYDAY=-1FR,+1MO").unwrap();
+        assert_eq!(
+            last_friday.ordinal_weekdays,
+            vec![OrdinalWeekday::new(-1, 5), OrdinalWeekday::new(1, 1)]
+        );
+        assert!(last_friday.days_of_week.is_empty());
+
+        let month_days = parse_ical_recurrence("FREQ=MONTHLY;BYMONTHDAY=1,15,-1").unwrap();
+        assert_eq!(month_days.days_of_month, vec![1, 15, -1]);
+
+        let yearly = parse_ical_recurrence("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH;WKST=SU").unwrap();
+        assert_eq!(yearly.months, vec![11]);
+        assert_eq!(yearly.ordinal_weekdays, vec![OrdinalWeekday::new(4, 4)]);
+        assert_eq!(yearly.week_start, Some(0));
+    }
+
+    #[test]
+    fn test_parse_ical_recurrence_rejects_invalid_rules() {
+        assert!(parse_ical_recurrence("").is_none());
+        assert!(parse_ical_recurrence("INTERVAL=2").is_none());
+        assert!(parse_ical_recurrence("FREQ=HOURLY").is_none());
+        assert!(parse_ical_recurrence("FREQ=WEEKLY;BYDAY=XX").is_none());
+        assert!(parse_ical_recurrence("FREQ=WEEKLY;BYDAY=0MO").is_none());
+        assert!(parse_ical_recurrence("FREQ=MONTHLY;BYMONTHDAY=32").is_none());
+        assert!(parse_ical_recurrence("FREQ=MONTHLY;BYSETPOS=0").is_none());
+        assert!(parse_ical_recurrence("FREQ=DAILY;INTERVAL=0").is_none());
+        assert!(parse_ical_recurrence("FREQ=DAILY;COUNT").is_none());
+    }
+
+    #[test]
+    fn test_recurring_event_keeps_rule_and_exception_dates() {
+        let events = parse_events(
+            "BEGIN:VCALENDAR\r\n\
+             BEGIN:VEVENT\r\n\
+             SUMMARY:Team sync\r\n\
+             DTSTART:20240109T100000\r\n\
+             DTEND:20240109T103000\r\n\
+             RRULE:FREQ=MONTHLY;BYDAY=TU;BYSETPOS=2\r\n\
+             EXDATE:20240213T100000,20240312T100000\r\n\
+             EXDATE;VALUE=DATE:20240409\r\n\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n",
+        );
+        let config = ical_event_to_config(&events[0], None).unwrap();
+        let recurrence = config.recurrence.expect("recurrence should be imported");
+
+        assert_eq!(recurrence.frequency, RecurrenceFrequency::Monthly);
+        assert_eq!(recurrence.set_positions, vec![2]);
+        assert_eq!(recurrence.exception_dates, vec!["2024-02-13", "2024-03-12", "2024-04-09"]);
+    }
+
+    fn csv_record(headers: &[&str], values: &[&str]) -> (csv::StringRecord, csv::StringRecord) {
+        (
+            csv::StringRecord::from(headers.to_vec()),
+            csv::StringRecord::from(values.to_vec()),
+        )
+    }
+
+    #[test]
+    fn test_csv_columns_use_header_aliases_and_mapping() {
+        let headers = csv::StringRecord::from(vec!["Subject", "Start Date", "Time", "Where"]);
+        let columns = resolve_csv_columns(&headers, &HashMap::new()).unwrap();
+        assert_eq!(columns["title"], 0);
+        assert_eq!(columns["date"], 1);
+        assert_eq!(columns["start_time"], 2);
+        assert_eq!(columns["location"], 3);
+
+        let options = CsvImportOptions::default().with_mapping_spec("location=subject").unwrap();
+        let columns = resolve_csv_columns(&headers, &options.column_map).unwrap();
+        assert_eq!(columns["location"], 0);
+
+        let options = CsvImportOptions::default().with_mapping_spec("title=Missing").unwrap();
+        assert!(resolve_csv_columns(&headers, &options.column_map).is_err());
+
+        let headers = csv::StringRecord::from(vec!["Subject", "Time"]);
+        let err = resolve_csv_columns(&headers, &HashMap::new()).unwrap_err();
+        assert!(err.to_string().contains("'date'"));
+    }
+
+    #[test]
+    fn test_csv_mapping_spec_rejects_unknown_fields() {
+        assert!(CsvImportOptions::default().with_mapping_spec("colour=Color").is_err());
+        assert!(CsvImportOptions::default().with_mapping_spec("title").is_err());
+        let options = CsvImportOptions::default()
+            .with_mapping_spec("Subject=Name, attendees=Guests")
+            .unwrap();
+        assert_eq!(options.column_map["title"], "Name");
+        assert_eq!(options.column_map["emails"], "Guests");
+    }
+
+    #[test]
+    fn test_csv_record_to_config_normalizes_values() {
+        let (headers, record) = csv_record(
+            &["title", "date", "start_time", "end_time", "attendees", "all_day", "rrule"],
+            &[
+                "Review",
+                "03/20/2025",
+                "2:30 PM",
+                "15:30:00",
+                "mailto:a@example.com; b@example.com",
+                "no",
+                "FREQ=WEEKLY;BYDAY=TH",
+            ],
+        );
+        let columns = resolve_csv_columns(&headers, &HashMap::new()).unwrap();
+        let config = csv_record_to_config(&record, &columns, Some("Work")).unwrap();
+
+        assert_eq!(config.title, "Review");
+        assert_eq!(config.start_date, "2025-03-20");
+        assert_eq!(config.start_time, "14:30");
+        assert_eq!(config.end_time.as_deref(), Some("15:30"));
+        assert_eq!(config.emails, vec!["a@example.com", "b@example.com"]);
+        assert_eq!(config.calendars, vec!["Work"]);
+        assert_eq!(config.recurrence.unwrap().days_of_week, vec![4]);
+    }
+
+    #[test]
+    fn test_csv_timezone_column_converts_times_to_local() {
+        let (headers, record) = csv_record(
+            &["title", "date", "start_time", "end_time", "timezone"],
+            &["Standup", "2024-01-15", "09:00", "09:30", "America/New_York"],
+        );
+        let columns = resolve_csv_columns(&headers, &HashMap::new()).unwrap();
+        let config = csv_record_to_config(&record, &columns, None).unwrap();
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 15, 14, 0, 0).unwrap().with_timezone(&Local);
+        let end = start + Duration::minutes(30);
+        assert_eq!(config.start_date, start.format("%Y-%m-%d").to_string());
+        assert_eq!(config.start_time, start.format("%H:%M").to_string());
+        assert_eq!(config.end_time, Some(end.format("%H:%M").to_string()));
+        assert_eq!(config.timezone.as_deref(), Some("America/New_York"));
+
+        let (_, unknown) = csv_record(
+            &["title", "date", "start_time", "end_time", "timezone"],
+            &["Standup", "2024-01-15", "09:00", "09:30", "Mars/Olympus"],
+        );
+        assert!(csv_record_to_config(&unknown, &columns, None).is_err());
+    }
+
+    #[test]
+    fn test_csv_record_errors() {
+        let (headers, _) = csv_record(&["title", "date", "time", "reminder"], &[]);
+        let columns = resolve_csv_columns(&headers, &HashMap::new()).unwrap();
+        let convert = |values: Vec<&str>| {
+            csv_record_to_config(&csv::StringRecord::from(values), &columns, None)
+        };
+
+        assert!(convert(vec!["", "2025-03-20", "10:00", ""]).is_err());
+        assert!(convert(vec!["Talk", "20th March", "10:00", ""]).is_err());
+        assert!(convert(vec!["Talk", "2025-03-20", "25:00", ""]).is_err());
+        assert!(convert(vec!["Talk", "2025-03-20", "10:00", "soon"]).is_err());
+        assert_eq!(convert(vec!["Talk", "2025-03-20", "10am", "5"]).unwrap().reminder, Some(5));
+    }
+
+    #[test]
+    fn test_parse_ical_duration() {
+        assert_eq!(parse_ical_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_ical_duration("-PT15M"), Some(Duration::minutes(-15)));
+        assert_eq!(parse_ical_duration("P1DT2H"), Some(Duration::hours(26)));
+        assert_eq!(parse_ical_duration("P2W"), Some(Duration::weeks(2)));
+        assert_eq!(parse_ical_duration("PT"), Some(Duration::zero()));
+        assert_eq!(parse_ical_duration("1H"), None);
+        assert_eq!(parse_ical_duration("PT5"), None);
+    }
 }
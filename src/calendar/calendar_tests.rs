@@ -4,5 +4,241 @@
 
 #[cfg(test)]
 mod tests {
-    // ...tests moved from calendar.rs...
+    use crate::calendar::{
+        EventConfig, EventUpdate, OrdinalWeekday, RecurrenceFrequency, RecurrencePattern,
+        parse_ical_recurrence, validate_event_config,
+    };
+
+    #[test]
+    fn test_to_rrule_includes_extended_rule_parts() {
+        let pattern = RecurrencePattern::new(RecurrenceFrequency::Monthly)
+            .with_interval(2)
+            .with_count(6)
+            .with_ordinal_weekdays(&[OrdinalWeekday::new(-1, 5)])
+            .with_days_of_week(&[2])
+            .with_days_of_month(&[1, -1])
+            .with_months(&[3, 9])
+            .with_set_positions(&[2])
+            .with_week_start(1);
+
+        assert_eq!(
+            pattern.to_rrule().unwrap(),
+            "FREQ=MONTHLY;INTERVAL=2;COUNT=6;BYMONTH=3,9;BYMONTHDAY=1,-1;BYDAY=-1FR,TU;\
+             BYSETPOS=2;WKST=MO"
+        );
+    }
+
+    #[test]
+    fn test_to_rrule_round_trips_through_parser() {
+        let rules = [
+            "FREQ=DAILY;INTERVAL=1;COUNT=5",
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,WE,FR",
+            "FREQ=MONTHLY;INTERVAL=1;BYDAY=TU;BYSETPOS=2",
+            "FREQ=YEARLY;INTERVAL=1;BYMONTH=11;BYDAY=4TH;WKST=SU",
+        ];
+        for rule in rules {
+            let pattern = parse_ical_recurrence(rule).unwrap();
+            assert_eq!(pattern.to_rrule().unwrap(), rule);
+        }
+
+        let until = RecurrencePattern::new(RecurrenceFrequency::Daily).with_end_date("2025-12-31");
+        let end_utc = chrono::NaiveDate::from_ymd_opt(2025, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 0)
+            .unwrap()
+            .and_local_timezone(chrono::Local)
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            until.to_rrule().unwrap(),
+            format!("FREQ=DAILY;INTERVAL=1;UNTIL={}", end_utc.format("%Y%m%dT%H%M%SZ"))
+        );
+        assert_eq!(parse_ical_recurrence(&until.to_rrule().unwrap()), Some(until));
+
+        let both = RecurrencePattern::new(RecurrenceFrequency::Daily)
+            .with_count(3)
+            .with_end_date("2025-12-31");
+        assert_eq!(both.to_rrule().unwrap(), "FREQ=DAILY;INTERVAL=1;COUNT=3");
+    }
+
+    fn date(value: &str) -> chrono::NaiveDate {
+        chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn occurrences(pattern: &RecurrencePattern, first: &str, until: &str) -> Vec<String> {
+        pattern
+            .occurrences(date(first), date(until))
+            .iter()
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_occurrences_of_simple_rules() {
+        let weekly = RecurrencePattern::new(RecurrenceFrequency::Weekly)
+            .with_days_of_week(&[1, 3])
+            .with_exception_dates(&["2024-06-12"]);
+        assert_eq!(
+            occurrences(&weekly, "2024-06-05", "2024-06-18"),
+            vec!["2024-06-05", "2024-06-10", "2024-06-17"]
+        );
+
+        let fortnightly = RecurrenceFrequency::Weekly;
+        let fortnightly = RecurrencePattern::new(fortnightly).with_interval(2).with_count(3);
+        assert_eq!(
+            occurrences(&fortnightly, "2024-06-03", "2024-12-31"),
+            vec!["2024-06-03", "2024-06-17", "2024-07-01"]
+        );
+
+        let daily = RecurrencePattern::new(RecurrenceFrequency::Daily).with_end_date("2024-06-04");
+        assert_eq!(occurrences(&daily, "2024-06-02", "2024-06-10").len(), 3);
+
+        // Months without a 31st are skipped
+        let monthly = RecurrencePattern::new(RecurrenceFrequency::Monthly);
+        assert_eq!(
+            occurrences(&monthly, "2024-01-31", "2024-05-31"),
+            vec!["2024-01-31", "2024-03-31", "2024-05-31"]
+        );
+    }
+
+    #[test]
+    fn test_occurrences_with_huge_interval() {
+        for frequency in ["DAILY", "WEEKLY", "MONTHLY", "YEARLY"] {
+            for (interval, until) in
+                [(20_000_000, date("9999-12-31")), (u32::MAX, chrono::NaiveDate::MAX)]
+            {
+                let rule = format!("FREQ={};INTERVAL={}", frequency, interval);
+                let pattern = parse_ical_recurrence(&rule).unwrap();
+                assert_eq!(
+                    pattern.occurrences(date("2024-06-03"), until),
+                    vec![date("2024-06-03")],
+                    "{}",
+                    rule
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_occurrences_of_positional_rules() {
+        let second_tuesday = RecurrencePattern::new(RecurrenceFrequency::Monthly)
+            .with_ordinal_weekdays(&[OrdinalWeekday::new(2, 2)]);
+        assert_eq!(
+            occurrences(&second_tuesday, "2024-05-14", "2024-07-31"),
+            vec!["2024-05-14", "2024-06-11", "2024-07-09"]
+        );
+
+        let last_weekday = RecurrencePattern::new(RecurrenceFrequency::Monthly)
+            .with_days_of_week(&[1, 2, 3, 4, 5])
+            .with_set_positions(&[-1]);
+        assert_eq!(
+            occurrences(&last_weekday, "2024-05-31", "2024-07-31"),
+            vec!["2024-05-31", "2024-06-28", "2024-07-31"]
+        );
+
+        let thanksgiving = parse_ical_recurrence("FREQ=YEARLY;BYMONTH=11;BYDAY=4TH").unwrap();
+        assert_eq!(
+            occurrences(&thanksgiving, "2023-11-23", "2025-01-01"),
+            vec!["2023-11-23", "2024-11-28"]
+        );
+
+        let last_day =
+            RecurrencePattern::new(RecurrenceFrequency::Monthly).with_days_of_month(&[-1]);
+        assert_eq!(
+            occurrences(&last_day, "2024-01-31", "2024-03-31"),
+            vec!["2024-01-31", "2024-02-29", "2024-03-31"]
+        );
+    }
+
+    #[test]
+    fn test_validate_event_config_checks_extended_recurrence() {
+        let mut config = EventConfig::new("Review", "2024-05-14", "10:00");
+        config.recurrence = Some(
+            RecurrencePattern::new(RecurrenceFrequency::Monthly)
+                .with_days_of_week(&[2])
+                .with_set_positions(&[2])
+                .with_exception_dates(&["2024-06-11"]),
+        );
+        assert!(validate_event_config(&config).is_ok());
+
+        config.recurrence = Some(
+            RecurrencePattern::new(RecurrenceFrequency::Monthly).with_exception_dates(&["June 11"]),
+        );
+        assert!(validate_event_config(&config).is_err());
+
+        config.recurrence =
+            Some(RecurrencePattern::new(RecurrenceFrequency::Monthly).with_days_of_month(&[0]));
+        assert!(validate_event_config(&config).is_err());
+
+        config.recurrence =
+            Some(RecurrencePattern::new(RecurrenceFrequency::Yearly).with_months(&[13]));
+        assert!(validate_event_config(&config).is_err());
+    }
+
+    fn planning() -> EventConfig {
+        let mut event = EventConfig::new("Planning", "2024-06-03", "10:00");
+        event.end_time = Some("11:30".to_string());
+        event.emails = vec!["joe@example.com".to_string()];
+        event.description = Some("Join URL: https://zoom.us/j/81234567890".to_string());
+        event
+    }
+
+    #[test]
+    fn test_event_update_moves_start_and_keeps_duration() {
+        let update = EventUpdate {
+            date: Some("2024-06-04".to_string()),
+            start_time: Some("23:00".to_string()),
+            ..Default::default()
+        };
+        let updated = update.apply(&planning()).unwrap();
+        assert_eq!(updated.start_date, "2024-06-04");
+        assert_eq!(updated.start_time, "23:00");
+        assert_eq!(updated.end_date.as_deref(), Some("2024-06-05"));
+        assert_eq!(updated.end_time.as_deref(), Some("00:30"));
+        assert_eq!(updated.description, planning().description);
+
+        let update = EventUpdate { end_time: Some("12:00".to_string()), ..Default::default() };
+        let updated = update.apply(&planning()).unwrap();
+        assert_eq!(
+            (updated.start_time.as_str(), updated.end_time.as_deref()),
+            ("10:00", Some("12:00"))
+        );
+        assert_eq!(updated.end_date, None);
+    }
+
+    #[test]
+    fn test_event_update_location_and_attendees() {
+        let update = EventUpdate {
+            location: Some("Room 2".to_string()),
+            add_emails: vec!["JOE@example.com".to_string(), "ann@example.com".to_string()],
+            ..Default::default()
+        };
+        let updated = update.apply(&planning()).unwrap();
+        assert_eq!(updated.location.as_deref(), Some("Room 2"));
+        assert_eq!(updated.emails, ["joe@example.com", "ann@example.com"]);
+
+        let clear = EventUpdate { location: Some(String::new()), ..Default::default() };
+        assert_eq!(clear.apply(&updated).unwrap().location, None);
+
+        let bad_email = EventUpdate { add_emails: vec!["joe".to_string()], ..Default::default() };
+        assert!(bad_email.apply(&planning()).is_err());
+        let bad_location = EventUpdate { location: Some("a; b".to_string()), ..Default::default() };
+        assert!(bad_location.apply(&planning()).is_err());
+    }
+
+    #[test]
+    fn test_event_update_all_day_event() {
+        let mut holiday = EventConfig::new("Holiday", "2024-06-10", "00:00");
+        holiday.all_day = true;
+        holiday.end_date = Some("2024-06-12".to_string());
+
+        let update = EventUpdate { date: Some("2024-06-17".to_string()), ..Default::default() };
+        let moved = update.apply(&holiday).unwrap();
+        assert_eq!(moved.start_date, "2024-06-17");
+        assert_eq!(moved.end_date.as_deref(), Some("2024-06-19"));
+
+        let timed = EventUpdate { start_time: Some("09:00".to_string()), ..Default::default() };
+        assert!(timed.apply(&holiday).is_err());
+        assert!(EventUpdate::default().is_empty());
+    }
 }
@@ -2,6 +2,7 @@
 //
 // This module contains all core types, enums, and error types used by the calendar system.
 
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime};
 use thiserror::Error;
 
 /// Custom error type for calendar operations
@@ -62,8 +63,37 @@ impl RecurrenceFrequency {
     }
 }
 
+/// RFC 5545 two-letter weekday codes, indexed by day number (0=Sunday)
+const WEEKDAY_CODES: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+
+/// Convert a day number (0=Sunday, 1=Monday, etc.) to its RFC 5545 code
+pub fn weekday_to_rfc5545(day: u8) -> &'static str {
+    WEEKDAY_CODES.get(day as usize).copied().unwrap_or("MO")
+}
+
+/// Parse an RFC 5545 weekday code (e.g. "TU") into a day number (0=Sunday)
+pub fn weekday_from_rfc5545(code: &str) -> Option<u8> {
+    WEEKDAY_CODES.iter().position(|c| c.eq_ignore_ascii_case(code)).map(|i| i as u8)
+}
+
+/// A weekday at a given position within the month or year, e.g. the second
+/// Tuesday (`2TU`) or the last Friday (`-1FR`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrdinalWeekday {
+    /// Position of the weekday; negative values count from the end
+    pub ordinal: i8,
+    /// Day of the week (0=Sunday, 1=Monday, etc.)
+    pub day: u8,
+}
+
+impl OrdinalWeekday {
+    pub fn new(ordinal: i8, day: u8) -> Self {
+        Self { ordinal, day }
+    }
+}
+
 /// Recurrence pattern for calendar events
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct RecurrencePattern {
     /// Frequency of recurrence
     pub frequency: RecurrenceFrequency,
@@ -75,12 +105,36 @@ pub struct RecurrencePattern {
     pub count: Option<u32>,
     /// Days of the week for weekly recurrence (0=Sunday, 1=Monday, etc.)
     pub days_of_week: Vec<u8>,
+    /// Weekdays with a position, such as "second Tuesday" (BYDAY=2TU)
+    pub ordinal_weekdays: Vec<OrdinalWeekday>,
+    /// Days of the month (BYMONTHDAY); negative values count from the end of the month
+    pub days_of_month: Vec<i8>,
+    /// Months of the year, 1-12 (BYMONTH)
+    pub months: Vec<u8>,
+    /// Positions within the set of occurrences in each period (BYSETPOS)
+    pub set_positions: Vec<i16>,
+    /// First day of the week (WKST), 0=Sunday
+    pub week_start: Option<u8>,
+    /// Dates in YYYY-MM-DD format on which the event does not occur (EXDATE)
+    pub exception_dates: Vec<String>,
 }
 
 impl RecurrencePattern {
     /// Create a new simple recurrence pattern with the given frequency
     pub fn new(frequency: RecurrenceFrequency) -> Self {
-        Self { frequency, interval: 1, end_date: None, count: None, days_of_week: Vec::new() }
+        Self {
+            frequency,
+            interval: 1,
+            end_date: None,
+            count: None,
+            days_of_week: Vec::new(),
+            ordinal_weekdays: Vec::new(),
+            days_of_month: Vec::new(),
+            months: Vec::new(),
+            set_positions: Vec::new(),
+            week_start: None,
+            exception_dates: Vec::new(),
+        }
     }
     /// Set the interval for recurrence
     pub fn with_interval(mut self, interval: u32) -> Self {
@@ -102,6 +156,255 @@ impl RecurrencePattern {
         self.days_of_week = days.to_vec();
         self
     }
+    /// Set positioned weekdays, e.g. `OrdinalWeekday::new(2, 2)` for the second Tuesday
+    pub fn with_ordinal_weekdays(mut self, days: &[OrdinalWeekday]) -> Self {
+        self.ordinal_weekdays = days.to_vec();
+        self
+    }
+    /// Set the days of the month the event repeats on
+    pub fn with_days_of_month(mut self, days: &[i8]) -> Self {
+        self.days_of_month = days.to_vec();
+        self
+    }
+    /// Set the months of the year the event repeats in
+    pub fn with_months(mut self, months: &[u8]) -> Self {
+        self.months = months.to_vec();
+        self
+    }
+    /// Set the BYSETPOS positions
+    pub fn with_set_positions(mut self, positions: &[i16]) -> Self {
+        self.set_positions = positions.to_vec();
+        self
+    }
+    /// Set the first day of the week
+    pub fn with_week_start(mut self, day: u8) -> Self {
+        self.week_start = Some(day);
+        self
+    }
+    /// Set the dates to skip
+    pub fn with_exception_dates(mut self, dates: &[&str]) -> Self {
+        self.exception_dates = dates.iter().map(|d| d.to_string()).collect();
+        self
+    }
+
+    /// Build the RFC 5545 RRULE value (without the `RRULE:` prefix)
+    ///
+    /// Exception dates are not part of the rule; they are written as EXDATE.
+    /// When both a count and an end date are set, only the count is written.
+    pub fn to_rrule(&self) -> anyhow::Result<String> {
+        let mut parts = vec![
+            format!("FREQ={}", self.frequency.to_rfc5545()),
+            format!("INTERVAL={}", self.interval),
+        ];
+        // RFC 5545 allows only one of COUNT and UNTIL; the count wins
+        if let Some(count) = self.count {
+            parts.push(format!("COUNT={}", count));
+        } else if let Some(end_date) = &self.end_date {
+            // UNTIL is the end of the local end date, written in UTC
+            let end_naive = chrono::NaiveDateTime::parse_from_str(
+                &format!("{} 23:59", end_date),
+                "%Y-%m-%d %H:%M",
+            )
+            .map_err(|e| anyhow::anyhow!("Invalid recurrence end date: {}", e))?;
+            let end_utc = end_naive
+                .and_local_timezone(chrono::Local)
+                .earliest()
+                .ok_or_else(|| anyhow::anyhow!("Invalid recurrence end date: {}", end_date))?
+                .with_timezone(&chrono::Utc);
+            parts.push(format!("UNTIL={}", end_utc.format("%Y%m%dT%H%M%SZ")));
+        }
+        if !self.months.is_empty() {
+            parts.push(format!("BYMONTH={}", join_numbers(&self.months)));
+        }
+        if !self.days_of_month.is_empty() {
+            parts.push(format!("BYMONTHDAY={}", join_numbers(&self.days_of_month)));
+        }
+        if !self.days_of_week.is_empty() || !self.ordinal_weekdays.is_empty() {
+            let days: Vec<String> = self
+                .ordinal_weekdays
+                .iter()
+                .map(|d| format!("{}{}", d.ordinal, weekday_to_rfc5545(d.day)))
+                .chain(self.days_of_week.iter().map(|&d| weekday_to_rfc5545(d).to_string()))
+                .collect();
+            parts.push(format!("BYDAY={}", days.join(",")));
+        }
+        if !self.set_positions.is_empty() {
+            parts.push(format!("BYSETPOS={}", join_numbers(&self.set_positions)));
+        }
+        if let Some(day) = self.week_start {
+            parts.push(format!("WKST={}", weekday_to_rfc5545(day)));
+        }
+        Ok(parts.join(";"))
+    }
+}
+
+impl RecurrencePattern {
+    /// Dates of the series that starts on `first`, up to and including `until`
+    ///
+    /// COUNT, the end date and the BY* parts are applied at the level of whole
+    /// days. Exception dates are left out but still count towards COUNT.
+    pub fn occurrences(&self, first: NaiveDate, until: NaiveDate) -> Vec<NaiveDate> {
+        let end_date = self
+            .end_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        let last = end_date.map_or(until, |end| end.min(until));
+        let interval = self.interval.max(1);
+
+        let mut dates = Vec::new();
+        let mut generated = 0;
+        for period in 0u32.. {
+            // Huge intervals (from imported files) end the series instead of overflowing
+            let Some(start) = period
+                .checked_mul(interval)
+                .and_then(|periods| self.period_start(first, periods))
+            else {
+                break;
+            };
+            if start > last {
+                break;
+            }
+            for date in self.period_dates(first, start).into_iter().filter(|d| *d >= first) {
+                if date > last || self.count.is_some_and(|count| generated >= count) {
+                    return dates;
+                }
+                generated += 1;
+                if !self.exception_dates.contains(&date.format("%Y-%m-%d").to_string()) {
+                    dates.push(date);
+                }
+            }
+        }
+        dates
+    }
+
+    // First day of the period `periods` periods after the one containing `first`
+    fn period_start(&self, first: NaiveDate, periods: u32) -> Option<NaiveDate> {
+        match self.frequency {
+            RecurrenceFrequency::Daily => first.checked_add_days(chrono::Days::new(periods.into())),
+            RecurrenceFrequency::Weekly => {
+                let week_start = self.week_start.unwrap_or(1) as u32;
+                let offset = (first.weekday().num_days_from_sunday() + 7 - week_start) % 7;
+                first
+                    .checked_sub_signed(Duration::days(offset.into()))?
+                    .checked_add_signed(Duration::weeks(periods.into()))
+            }
+            RecurrenceFrequency::Monthly => {
+                first.with_day(1)?.checked_add_months(Months::new(periods))
+            }
+            RecurrenceFrequency::Yearly => {
+                first.with_ordinal(1)?.checked_add_months(Months::new(periods.checked_mul(12)?))
+            }
+        }
+    }
+
+    // Dates in the period starting on `start` that match the rule
+    fn period_dates(&self, first: NaiveDate, start: NaiveDate) -> Vec<NaiveDate> {
+        let end = match self.frequency {
+            RecurrenceFrequency::Daily => start.succ_opt(),
+            RecurrenceFrequency::Weekly => start.checked_add_signed(Duration::weeks(1)),
+            RecurrenceFrequency::Monthly => start.checked_add_months(Months::new(1)),
+            RecurrenceFrequency::Yearly => start.checked_add_months(Months::new(12)),
+        };
+        let Some(end) = end else { return Vec::new() };
+
+        let by_weekday = !self.days_of_week.is_empty() || !self.ordinal_weekdays.is_empty();
+        let by_day = by_weekday || !self.days_of_month.is_empty();
+        let dates: Vec<NaiveDate> = start
+            .iter_days()
+            .take_while(|date| *date < end)
+            .filter(|date| self.months.is_empty() || self.months.contains(&(date.month() as u8)))
+            .filter(|date| {
+                self.days_of_month.is_empty()
+                    || self.days_of_month.iter().any(|&day| day_of_month_matches(*date, day))
+            })
+            .filter(|date| !by_weekday || self.weekday_matches(*date))
+            // Without day rules the series repeats on the day it started
+            .filter(|date| {
+                by_day
+                    || match self.frequency {
+                        RecurrenceFrequency::Daily => true,
+                        RecurrenceFrequency::Weekly => date.weekday() == first.weekday(),
+                        RecurrenceFrequency::Monthly => date.day() == first.day(),
+                        RecurrenceFrequency::Yearly => {
+                            date.day() == first.day()
+                                && (!self.months.is_empty() || date.month() == first.month())
+                        }
+                    }
+            })
+            .collect();
+
+        if self.set_positions.is_empty() {
+            return dates;
+        }
+        let mut selected: Vec<NaiveDate> = self
+            .set_positions
+            .iter()
+            .filter_map(|&position| {
+                let index = if position > 0 {
+                    position as usize - 1
+                } else {
+                    dates.len().checked_sub(position.unsigned_abs() as usize)?
+                };
+                dates.get(index).copied()
+            })
+            .collect();
+        selected.sort();
+        selected.dedup();
+        selected
+    }
+
+    // Whether the date falls on one of the rule's weekdays. Positions count
+    // within the month for monthly rules and yearly rules with months, within
+    // the year for other yearly rules, and are ignored otherwise.
+    fn weekday_matches(&self, date: NaiveDate) -> bool {
+        let day = date.weekday().num_days_from_sunday() as u8;
+        if self.days_of_week.contains(&day) {
+            return true;
+        }
+        let (position, from_end) = match self.frequency {
+            RecurrenceFrequency::Monthly => {
+                (date.day0() / 7 + 1, (days_in_month(date) - date.day()) / 7 + 1)
+            }
+            RecurrenceFrequency::Yearly if !self.months.is_empty() => {
+                (date.day0() / 7 + 1, (days_in_month(date) - date.day()) / 7 + 1)
+            }
+            RecurrenceFrequency::Yearly => {
+                let days_in_year = if date.leap_year() { 366 } else { 365 };
+                (date.ordinal0() / 7 + 1, (days_in_year - date.ordinal()) / 7 + 1)
+            }
+            _ => {
+                return self.ordinal_weekdays.iter().any(|weekday| weekday.day == day);
+            }
+        };
+        self.ordinal_weekdays.iter().any(|weekday| {
+            weekday.day == day
+                && if weekday.ordinal > 0 {
+                    weekday.ordinal as u32 == position
+                } else {
+                    weekday.ordinal.unsigned_abs() as u32 == from_end
+                }
+        })
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    date.with_day(1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .and_then(|next| next.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+// BYMONTHDAY value `day` matches the date; negative values count from the end
+fn day_of_month_matches(date: NaiveDate, day: i8) -> bool {
+    if day > 0 {
+        date.day() == day as u32
+    } else {
+        days_in_month(date) + 1 - date.day() == day.unsigned_abs() as u32
+    }
+}
+
+fn join_numbers<T: ToString>(values: &[T]) -> String {
+    values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",")
 }
 
 /// Configuration for a calendar event
@@ -125,6 +428,8 @@ pub struct EventConfig {
     pub zoom_meeting_id: Option<u64>,
     pub zoom_join_url: Option<String>,
     pub zoom_password: Option<String>,
+    /// Identifier assigned by the calendar store; None for events not yet created
+    pub uid: Option<String>,
 }
 
 impl EventConfig {
@@ -147,6 +452,7 @@ impl EventConfig {
             zoom_meeting_id: None,
             zoom_join_url: None,
             zoom_password: None,
+            uid: None,
         }
     }
     pub fn with_recurrence(mut self, recurrence: RecurrencePattern) -> Self {
@@ -157,4 +463,138 @@ impl EventConfig {
         self.create_zoom_meeting = enable;
         self
     }
+
+    /// Local start and (exclusive) end of the event
+    ///
+    /// All-day events span midnight to midnight after their last day. A timed
+    /// event without an end lasts one hour, and an end time before the start
+    /// on the same day is taken to be after midnight.
+    pub fn time_span(&self) -> anyhow::Result<(NaiveDateTime, NaiveDateTime)> {
+        let start_date = parse_config_date(&self.start_date)?;
+        let end_date = match &self.end_date {
+            Some(date) => parse_config_date(date)?,
+            None => start_date,
+        };
+        if self.all_day {
+            let last_day = end_date.max(start_date);
+            return Ok((
+                start_date.and_time(NaiveTime::MIN),
+                (last_day + Duration::days(1)).and_time(NaiveTime::MIN),
+            ));
+        }
+
+        let start = start_date.and_time(parse_config_time(&self.start_time)?);
+        let end = match &self.end_time {
+            Some(end_time) => {
+                let end = end_date.and_time(parse_config_time(end_time)?);
+                if end <= start && self.end_date.is_none() { end + Duration::days(1) } else { end }
+            }
+            None => start + Duration::hours(1),
+        };
+        if end <= start {
+            return Err(anyhow::anyhow!("End time is not after start time"));
+        }
+        Ok((start, end))
+    }
+}
+
+fn parse_config_date(value: &str) -> anyhow::Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|e| anyhow::anyhow!("Invalid date '{}': {}", value, e))
+}
+
+fn parse_config_time(value: &str) -> anyhow::Result<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .map_err(|e| anyhow::anyhow!("Invalid time '{}': {}", value, e))
+}
+
+/// Changes to apply to an existing event; `None` fields are left as they are
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventUpdate {
+    /// New start date (YYYY-MM-DD)
+    pub date: Option<String>,
+    /// New start time (HH:MM)
+    pub start_time: Option<String>,
+    /// New end time (HH:MM)
+    pub end_time: Option<String>,
+    /// New location; an empty string clears it
+    pub location: Option<String>,
+    /// Attendees to invite in addition to the existing ones
+    pub add_emails: Vec<String>,
+}
+
+impl EventUpdate {
+    /// Whether the update changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.date.is_none()
+            && self.start_time.is_none()
+            && self.end_time.is_none()
+            && self.location.is_none()
+            && self.add_emails.is_empty()
+    }
+
+    /// Return `event` with the changes applied
+    ///
+    /// Moving the start keeps the event's duration unless a new end time is
+    /// given too, and moving the date shifts a multi-day event's end date with
+    /// it. Title, description, attendees and Zoom details are kept.
+    pub fn apply(&self, event: &EventConfig) -> anyhow::Result<EventConfig> {
+        use crate::calendar::calendar_validation::{contains_dangerous_characters, validate_email};
+
+        if event.all_day && (self.start_time.is_some() || self.end_time.is_some()) {
+            return Err(anyhow::anyhow!("'{}' is an all-day event and has no times", event.title));
+        }
+        let mut updated = event.clone();
+        let (old_start, old_end) = event.time_span()?;
+        let new_date = match &self.date {
+            Some(date) => parse_config_date(date)?,
+            None => old_start.date(),
+        };
+
+        if event.all_day {
+            let shift = new_date - old_start.date();
+            updated.start_date = new_date.format("%Y-%m-%d").to_string();
+            if let Some(end_date) = &event.end_date {
+                let end_date = parse_config_date(end_date)? + shift;
+                updated.end_date = Some(end_date.format("%Y-%m-%d").to_string());
+            }
+        } else {
+            let start_time = match &self.start_time {
+                Some(time) => parse_config_time(time)?,
+                None => old_start.time(),
+            };
+            let start = new_date.and_time(start_time);
+            let end = match &self.end_time {
+                Some(time) => {
+                    let end = new_date.and_time(parse_config_time(time)?);
+                    if end <= start { end + Duration::days(1) } else { end }
+                }
+                None => start + (old_end - old_start),
+            };
+            updated.start_date = start.format("%Y-%m-%d").to_string();
+            updated.start_time = start.format("%H:%M").to_string();
+            updated.end_time = Some(end.format("%H:%M").to_string());
+            updated.end_date =
+                (end.date() != start.date()).then(|| end.format("%Y-%m-%d").to_string());
+        }
+
+        if let Some(location) = &self.location {
+            let location = location.trim();
+            if contains_dangerous_characters(location) {
+                return Err(anyhow::anyhow!("Location contains potentially dangerous characters"));
+            }
+            updated.location = (!location.is_empty()).then(|| location.to_string());
+        }
+
+        for email in &self.add_emails {
+            let email = email.trim();
+            if !validate_email(email) {
+                return Err(anyhow::anyhow!("Invalid email format: {}", email));
+            }
+            if !updated.emails.iter().any(|e| e.eq_ignore_ascii_case(email)) {
+                updated.emails.push(email.to_string());
+            }
+        }
+        Ok(updated)
+    }
 }
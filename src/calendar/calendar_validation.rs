@@ -163,6 +163,30 @@ pub fn validate_event_config(
                 return Err(anyhow!("Invalid recurrence end date format: {}", end_date));
             }
         }
+        for date in &recurrence.exception_dates {
+            if !validate_date_format(date) {
+                return Err(anyhow!("Invalid recurrence exception date format: {}", date));
+            }
+        }
+        if recurrence.interval == 0 {
+            return Err(anyhow!("Recurrence interval must be at least 1"));
+        }
+        let valid_day = |d: u8| d <= 6;
+        if !recurrence.days_of_week.iter().all(|&d| valid_day(d))
+            || !recurrence.ordinal_weekdays.iter().all(|d| valid_day(d.day) && d.ordinal != 0)
+            || !recurrence.week_start.is_none_or(valid_day)
+        {
+            return Err(anyhow!("Invalid recurrence weekday"));
+        }
+        if recurrence.days_of_month.iter().any(|&d| d == 0 || !(-31..=31).contains(&d)) {
+            return Err(anyhow!("Invalid recurrence day of month"));
+        }
+        if recurrence.months.iter().any(|m| !(1..=12).contains(m)) {
+            return Err(anyhow!("Invalid recurrence month"));
+        }
+        if recurrence.set_positions.iter().any(|&p| p == 0 || !(-366..=366).contains(&p)) {
+            return Err(anyhow!("Invalid recurrence set position"));
+        }
     }
 
     // If creating a Zoom meeting, validate needed fields
@@ -1,18 +1,27 @@
 use crate::config::Config;
 use crate::state::{CalendarItem, StateManager};
 use anyhow::{Result, anyhow};
-use log::{debug, error, info};
+use chrono::{Local, NaiveDate};
+use log::{debug, error, info, warn};
 
+mod calendar_agenda;
 mod calendar_applescript;
+mod calendar_backend;
 mod calendar_contacts;
+mod calendar_export;
+mod calendar_freebusy;
 mod calendar_import;
 #[cfg(test)]
 mod calendar_tests;
 mod calendar_types;
 mod calendar_validation;
 
+pub use calendar_agenda::*;
 pub use calendar_applescript::*;
+pub use calendar_backend::*;
 pub use calendar_contacts::*;
+pub use calendar_export::*;
+pub use calendar_freebusy::*;
 pub use calendar_import::*;
 pub use calendar_types::*;
 pub use calendar_validation::*;
@@ -31,6 +40,218 @@ pub enum CalendarError {
     ScriptError(String),
 }
 
+/// Ensure the active calendar backend is ready to accept requests
+pub async fn ensure_calendar_running() -> Result<()> {
+    backend().ensure_running().await
+}
+
+/// Get the names of all calendars in the active backend
+pub async fn get_available_calendars() -> Result<Vec<String>> {
+    backend().available_calendars().await
+}
+
+/// Print a sorted, de-duplicated list of all calendars
+pub async fn list_calendars() -> Result<()> {
+    ensure_calendar_running().await?;
+    let calendars = get_available_calendars().await?;
+    println!("Available calendars:");
+    if calendars.is_empty() {
+        println!("  No calendars found. Please ensure Calendar.app is properly configured.");
+    } else {
+        let unique_calendars: std::collections::BTreeSet<String> = calendars.into_iter().collect();
+        for calendar in unique_calendars {
+            println!("  - {}", calendar);
+        }
+    }
+    Ok(())
+}
+
+/// Create an event in the single calendar named by `config.calendars[0]`
+pub async fn create_single_event(config: EventConfig) -> Result<()> {
+    backend().create_single_event(config).await
+}
+
+/// Get events starting between `from` and `to` (inclusive), ordered by start
+///
+/// An empty `calendars` slice means every calendar in the active backend.
+pub async fn list_events(
+    calendars: &[String],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<EventConfig>> {
+    if from > to {
+        return Err(anyhow!("Start date {} is after end date {}", from, to));
+    }
+    ensure_calendar_running().await?;
+    let mut events = backend().list_events(calendars, from, to).await?;
+    events.sort_by(|a, b| {
+        (&a.start_date, &a.start_time, &a.title).cmp(&(&b.start_date, &b.start_time, &b.title))
+    });
+    Ok(events)
+}
+
+/// Get recurring events whose series started before `before`
+///
+/// `list_events` only returns a series on the date it starts, so callers that
+/// need every occurrence in a range also expand these.
+pub async fn list_recurring_events(
+    calendars: &[String],
+    before: NaiveDate,
+) -> Result<Vec<EventConfig>> {
+    ensure_calendar_running().await?;
+    backend().list_recurring_events(calendars, before).await
+}
+
+/// Find events on `date` whose title matches `title`, ignoring case
+pub async fn find_events_by_title(
+    title: &str,
+    date: NaiveDate,
+    calendars: &[String],
+) -> Result<Vec<EventConfig>> {
+    let title = title.trim();
+    Ok(list_events(calendars, date, date)
+        .await?
+        .into_iter()
+        .filter(|event| event.title.trim().eq_ignore_ascii_case(title))
+        .collect())
+}
+
+/// Delete the event with the given UID and return it
+///
+/// With `delete_zoom`, the Zoom meeting referenced by the event's notes is
+/// deleted too. The event stays deleted if the Zoom call fails.
+pub async fn delete_event_by_uid(uid: &str, delete_zoom: bool) -> Result<EventConfig> {
+    ensure_calendar_running().await?;
+    let event = backend().delete_event(uid).await?;
+    forget_event(&event);
+
+    if delete_zoom {
+        match linked_zoom_meeting(&event) {
+            Some(id) => crate::zoom::ZoomClient::new()?.delete_meeting(id).await.map_err(|e| {
+                anyhow!("Deleted event '{}' but not its Zoom meeting {}: {}", event.title, id, e)
+            })?,
+            None => warn!("Event '{}' has no Zoom meeting ID in its notes", event.title),
+        }
+    }
+    Ok(event)
+}
+
+/// Apply `update` to the event with the given UID and return the saved event
+///
+/// When the start or end moves, the Zoom meeting referenced by the event's
+/// notes is rescheduled to match. The event stays updated if the Zoom call fails.
+pub async fn update_event_by_uid(uid: &str, update: &EventUpdate) -> Result<EventConfig> {
+    if update.is_empty() {
+        return Err(anyhow!(
+            "Nothing to update. Pass --date, --start, --end, --location, --add-email or --add-contacts"
+        ));
+    }
+    ensure_calendar_running().await?;
+    let event = backend().get_event(uid).await?;
+    let changed = update.apply(&event)?;
+    let updated = backend().update_event(&changed).await?;
+    remember_update(&event, &updated);
+
+    if updated.time_span()? != event.time_span()? {
+        if let Some(id) = linked_zoom_meeting(&updated) {
+            let (start, end) = updated.time_span()?;
+            let meeting_update = crate::zoom::ZoomMeetingUpdate::reschedule(start, end)?;
+            crate::zoom::ZoomClient::new()?
+                .update_meeting(id, meeting_update)
+                .await
+                .map_err(|e| {
+                    anyhow!(
+                        "Updated event '{}' but not its Zoom meeting {}: {}",
+                        updated.title,
+                        id,
+                        e
+                    )
+                })?;
+        }
+    }
+    Ok(updated)
+}
+
+/// The ID of the Zoom meeting an event links to, if any
+fn linked_zoom_meeting(event: &EventConfig) -> Option<u64> {
+    event.zoom_meeting_id.or_else(|| {
+        [event.description.as_deref(), event.zoom_join_url.as_deref()]
+            .into_iter()
+            .flatten()
+            .find_map(crate::zoom::extract_meeting_id)
+    })
+}
+
+/// Record an updated event's new date, time and location in the local event history
+fn remember_update(before: &EventConfig, after: &EventConfig) {
+    let result = StateManager::new().and_then(|state| {
+        state.update(|items: &mut Vec<CalendarItem>| {
+            for item in items.iter_mut().filter(|item| is_history_of(item, before)) {
+                item.date = after.start_date.clone();
+                item.time = after.start_time.clone();
+                item.location = after.location.clone();
+                item.email = (!after.emails.is_empty()).then(|| after.emails.join(", "));
+            }
+        })
+    });
+    if let Err(e) = result {
+        warn!("Failed to update event history for '{}': {}", after.title, e);
+    }
+}
+
+/// Whether a history item was recorded for `event`
+fn is_history_of(item: &CalendarItem, event: &EventConfig) -> bool {
+    item.title == event.title
+        && item.date == event.start_date
+        && (event.all_day || item.time == event.start_time)
+}
+
+/// Drop a deleted event from the local event history
+fn forget_event(event: &EventConfig) {
+    let result = StateManager::new().and_then(|state| {
+        state.update(|items: &mut Vec<CalendarItem>| {
+            items.retain(|item| !is_history_of(item, event))
+        })
+    });
+    if let Err(e) = result {
+        warn!("Failed to update event history after deleting '{}': {}", event.title, e);
+    }
+}
+
+/// Parse a date argument given as `today`, `tomorrow`, `yesterday` or YYYY-MM-DD
+pub fn parse_date_argument(value: &str) -> Result<NaiveDate> {
+    let today = Local::now().date_naive();
+    match value.trim().to_lowercase().as_str() {
+        "today" => Ok(today),
+        "tomorrow" => Ok(today + chrono::Duration::days(1)),
+        "yesterday" => Ok(today - chrono::Duration::days(1)),
+        other => NaiveDate::parse_from_str(other, "%Y-%m-%d")
+            .map_err(|_| anyhow!("Invalid date '{}'. Use YYYY-MM-DD, today or tomorrow", value)),
+    }
+}
+
+/// Resolve optional `from`/`to` date arguments into an inclusive range
+///
+/// `from` defaults to today and `to` to `default_days` after `from`.
+pub fn resolve_date_range(
+    from: Option<&str>,
+    to: Option<&str>,
+    default_days: i64,
+) -> Result<(NaiveDate, NaiveDate)> {
+    let from = match from {
+        Some(value) => parse_date_argument(value)?,
+        None => Local::now().date_naive(),
+    };
+    let to = match to {
+        Some(value) => parse_date_argument(value)?,
+        None => from + chrono::Duration::days(default_days),
+    };
+    if from > to {
+        return Err(anyhow!("Start date {} is after end date {}", from, to));
+    }
+    Ok((from, to))
+}
+
 pub async fn create_event(config: EventConfig) -> Result<()> {
     debug!("Creating event with config: {:?}", config);
     use crate::calendar::calendar_validation::validate_event_config;
@@ -24,6 +24,10 @@ pub struct Cli {
     /// Start both terminal and API server
     #[arg(long = "full", conflicts_with = "api_server")]
     pub full: bool,
+
+    /// Run commands generated from natural language without asking for confirmation
+    #[arg(long = "yes", short = 'y', global = true)]
+    pub yes: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -68,6 +72,27 @@ pub enum Commands {
         action: UtilityActions,
     },
 
+    /// Manage the files DuckTape keeps in ~/.ducktape
+    State {
+        #[command(subcommand)]
+        action: StateActions,
+    },
+
+    /// Search local notes, events and reminders
+    Search {
+        /// Words, "quoted phrases", title:word and folder:name filters
+        #[arg(required = true, num_args = 1..)]
+        query: Vec<String>,
+
+        /// Only show notes, events or reminders
+        #[arg(long = "type", value_parser = ["note", "event", "reminder"])]
+        kind: Option<String>,
+
+        /// Maximum number of results
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+
     /// Run a natural language command using AI
     Ai {
         /// The natural language command
@@ -136,18 +161,92 @@ pub enum CalendarActions {
         /// Check for calendar conflicts before creating the event
         #[arg(long)]
         check_conflicts: bool,
+        /// Create the event even if --check-conflicts finds overlapping events
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Suggest open slots within working hours on a day
+    Free {
+        /// Day to search (YYYY-MM-DD, today or tomorrow)
+        #[arg(long, default_value = "today")]
+        date: String,
+        /// Minimum slot length, e.g. 30, 45m or 1h30m (defaults to the configured event duration)
+        #[arg(long)]
+        duration: Option<String>,
+        /// Calendars whose events count as busy (all calendars when omitted)
+        #[arg(long, value_delimiter = ',')]
+        calendar: Option<Vec<String>>,
     },
 
-    /// Delete a calendar event
+    /// Delete a calendar event by title and date, or by UID
     #[command(alias = "remove")]
     Delete {
-        /// Event ID or title to delete
+        /// Event title, or the event UID when no date is given
         #[arg(required = true)]
-        event_id: String,
+        event: String,
 
-        /// Calendar name
-        #[arg(default_value = "Work")]
-        calendar: String,
+        /// Date of the event (YYYY-MM-DD, today or tomorrow)
+        date: Option<String>,
+
+        /// Only match events in these calendars
+        #[arg(long, value_delimiter = ',')]
+        calendar: Option<Vec<String>>,
+
+        /// Delete every event that matches
+        #[arg(long, conflicts_with = "index")]
+        all: bool,
+
+        /// Delete only the numbered match from the list shown for ambiguous titles
+        #[arg(long)]
+        index: Option<usize>,
+
+        /// Also delete the Zoom meeting linked in the event notes
+        #[arg(long)]
+        zoom: bool,
+    },
+
+    /// Change the date, time, location or attendees of an existing event
+    #[command(alias = "reschedule")]
+    Update {
+        /// Event title, or the event UID when no date is given
+        #[arg(required = true)]
+        event: String,
+
+        /// Current date of the event (YYYY-MM-DD, today or tomorrow)
+        current_date: Option<String>,
+
+        /// Only match events in these calendars
+        #[arg(long, value_delimiter = ',')]
+        calendar: Option<Vec<String>>,
+
+        /// Update only the numbered match from the list shown for ambiguous titles
+        #[arg(long)]
+        index: Option<usize>,
+
+        /// New date (YYYY-MM-DD, today or tomorrow)
+        #[arg(long)]
+        date: Option<String>,
+
+        /// New start time (HH:MM); the duration is kept unless --end is given
+        #[arg(long)]
+        start: Option<String>,
+
+        /// New end time (HH:MM)
+        #[arg(long)]
+        end: Option<String>,
+
+        /// New location (an empty string clears it)
+        #[arg(long)]
+        location: Option<String>,
+
+        /// Attendees to invite, comma separated
+        #[arg(long, value_delimiter = ',')]
+        add_email: Option<Vec<String>>,
+
+        /// Contact names to look up and invite, comma separated
+        #[arg(long, value_delimiter = ',')]
+        add_contacts: Option<Vec<String>>,
     },
 
     /// Import events from a file
@@ -160,9 +259,56 @@ pub enum CalendarActions {
         #[arg(default_value = "Work")]
         calendar: String,
 
-        /// File format (ics, csv)
-        #[arg(long, default_value = "ics")]
-        format: String,
+        /// File format (ics, csv); detected from the file extension when omitted
+        #[arg(long)]
+        format: Option<String>,
+
+        /// CSV column mapping as field=column pairs (e.g. "title=Subject,date=Start Date")
+        #[arg(long)]
+        map: Option<String>,
+
+        /// Print the events that would be imported without creating them (CSV only)
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show events in a date range grouped by day
+    #[command(alias = "events")]
+    Agenda {
+        /// First day to show (YYYY-MM-DD, today or tomorrow)
+        #[arg(long, default_value = "today")]
+        from: String,
+
+        /// Last day to show, inclusive (defaults to 6 days after --from)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Calendars to show (all calendars when omitted)
+        #[arg(long, value_delimiter = ',')]
+        calendar: Option<Vec<String>>,
+
+        /// Print the agenda as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Export events in a date range as an iCalendar (.ics) file
+    Export {
+        /// First day to export (YYYY-MM-DD, today or tomorrow)
+        #[arg(long, default_value = "today")]
+        from: String,
+
+        /// Last day to export, inclusive (defaults to 30 days after --from)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Calendars to export (all calendars when omitted)
+        #[arg(long, value_delimiter = ',')]
+        calendar: Option<Vec<String>>,
+
+        /// File to write (prints to stdout when omitted)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
     },
 
     /// Set the default calendar
@@ -190,6 +336,14 @@ pub enum ReminderActions {
     List {
         /// List name
         list: Option<String>,
+
+        /// Only show completed reminders
+        #[arg(long, conflicts_with = "pending")]
+        completed: bool,
+
+        /// Only show reminders that are not completed
+        #[arg(long)]
+        pending: bool,
     },
 
     /// Create a new reminder
@@ -210,6 +364,22 @@ pub enum ReminderActions {
         /// Notes for the reminder
         #[arg(long)]
         notes: Option<String>,
+
+        /// Due date (YYYY-MM-DD, or "YYYY-MM-DD HH:MM" for a due time)
+        #[arg(long)]
+        due: Option<String>,
+
+        /// Priority (low, medium, high)
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Flag the reminder
+        #[arg(long, alias = "flagged")]
+        flag: bool,
+
+        /// Repeat rule, e.g. daily, "every 2 weeks" or "every Monday"
+        #[arg(long)]
+        repeat: Option<String>,
     },
 
     /// Mark a reminder as completed
@@ -223,6 +393,17 @@ pub enum ReminderActions {
         list: Option<String>,
     },
 
+    /// Mark a completed reminder as not completed
+    #[command(aliases = ["undone", "reopen"])]
+    Uncomplete {
+        /// Reminder ID or title
+        #[arg(required = true)]
+        reminder_id: String,
+
+        /// List name
+        list: Option<String>,
+    },
+
     /// Delete a reminder
     #[command(alias = "remove")]
     Delete {
@@ -279,6 +460,54 @@ pub enum NoteActions {
         folder: Option<String>,
     },
 
+    /// Append text to the end of a note
+    Append {
+        /// Note title
+        #[arg(required = true, num_args = 1.., value_delimiter = ' ')]
+        title: Vec<String>,
+
+        /// Text to append
+        #[arg(long, required = true)]
+        content: String,
+
+        /// Folder name
+        #[arg(long)]
+        folder: Option<String>,
+    },
+
+    /// Edit a note in $VISUAL or $EDITOR
+    Edit {
+        /// Note title
+        #[arg(required = true, num_args = 1.., value_delimiter = ' ')]
+        title: Vec<String>,
+
+        /// Folder name
+        #[arg(long)]
+        folder: Option<String>,
+    },
+
+    /// Export notes as Markdown files with YAML front matter
+    Export {
+        /// Directory to write to, with one subdirectory per folder
+        #[arg(long, required = true)]
+        dir: PathBuf,
+
+        /// Only export notes in this folder
+        #[arg(long)]
+        folder: Option<String>,
+    },
+
+    /// Import Markdown files as notes, replacing notes with the same title
+    Import {
+        /// Directory to read .md files from, including subdirectories
+        #[arg(long, required = true)]
+        dir: PathBuf,
+
+        /// Folder for every imported note (defaults to each file's front matter)
+        #[arg(long)]
+        folder: Option<String>,
+    },
+
     /// Delete a note
     #[command(alias = "remove")]
     Delete {
@@ -337,6 +566,19 @@ pub enum ContactActions {
     },
 }
 
+#[derive(Debug, Subcommand)]
+pub enum StateActions {
+    /// Upgrade state files written by older versions
+    Migrate {
+        /// Show what would change without writing any files
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Copy the JSON state files into the SQLite database, replacing its contents
+    Import,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum UtilityActions {
     /// Display current date
@@ -353,6 +595,7 @@ pub enum UtilityActions {
 ///
 /// This function extracts relevant information from the Cli struct and
 /// converts it to a CommandArgs struct that can be used by the command processor.
+#[allow(deprecated)]
 pub fn convert_to_command_args(cli: &Cli) -> Option<CommandArgs> {
     match &cli.command {
         Some(cmd) => match cmd {
@@ -384,6 +627,7 @@ pub fn convert_to_command_args(cli: &Cli) -> Option<CommandArgs> {
                         count,
                         days,
                         check_conflicts,
+                        force,
                     } => {
                         args.push("create".to_string());
                         args.push(title.clone());
@@ -432,17 +676,123 @@ pub fn convert_to_command_args(cli: &Cli) -> Option<CommandArgs> {
                         if *check_conflicts {
                             flags.insert("check_conflicts".to_string(), Some("true".to_string()));
                         }
+                        if *force {
+                            flags.insert("force".to_string(), Some("true".to_string()));
+                        }
                     }
-                    CalendarActions::Delete { event_id, calendar } => {
+                    CalendarActions::Free { date, duration, calendar } => {
+                        args.push("free".to_string());
+                        flags.insert("date".to_string(), Some(date.clone()));
+                        if let Some(d) = duration {
+                            flags.insert("duration".to_string(), Some(d.clone()));
+                        }
+                        if let Some(cals) = calendar {
+                            flags.insert("calendar".to_string(), Some(cals.join(",")));
+                        }
+                    }
+                    CalendarActions::Delete { event, date, calendar, all, index, zoom } => {
                         args.push("delete".to_string());
-                        args.push(event_id.clone());
-                        args.push(calendar.clone());
+                        args.push(event.clone());
+                        if let Some(d) = date {
+                            args.push(d.clone());
+                        }
+                        if let Some(cals) = calendar {
+                            flags.insert("calendar".to_string(), Some(cals.join(",")));
+                        }
+                        if *all {
+                            flags.insert("all".to_string(), Some("true".to_string()));
+                        }
+                        if let Some(i) = index {
+                            flags.insert("index".to_string(), Some(i.to_string()));
+                        }
+                        if *zoom {
+                            flags.insert("zoom".to_string(), Some("true".to_string()));
+                        }
+                    }
+                    CalendarActions::Update {
+                        event,
+                        current_date,
+                        calendar,
+                        index,
+                        date,
+                        start,
+                        end,
+                        location,
+                        add_email,
+                        add_contacts,
+                    } => {
+                        args.push("update".to_string());
+                        args.push(event.clone());
+                        if let Some(d) = current_date {
+                            args.push(d.clone());
+                        }
+                        if let Some(cals) = calendar {
+                            flags.insert("calendar".to_string(), Some(cals.join(",")));
+                        }
+                        if let Some(i) = index {
+                            flags.insert("index".to_string(), Some(i.to_string()));
+                        }
+                        if let Some(d) = date {
+                            flags.insert("date".to_string(), Some(d.clone()));
+                        }
+                        if let Some(s) = start {
+                            flags.insert("start".to_string(), Some(s.clone()));
+                        }
+                        if let Some(e) = end {
+                            flags.insert("end".to_string(), Some(e.clone()));
+                        }
+                        if let Some(l) = location {
+                            flags.insert("location".to_string(), Some(l.clone()));
+                        }
+                        if let Some(emails) = add_email {
+                            flags.insert("add_email".to_string(), Some(emails.join(",")));
+                        }
+                        if let Some(names) = add_contacts {
+                            flags.insert("add_contacts".to_string(), Some(names.join(",")));
+                        }
                     }
-                    CalendarActions::Import { file, calendar, format } => {
+                    CalendarActions::Import { file, calendar, format, map, dry_run } => {
                         args.push("import".to_string());
                         args.push(file.to_string_lossy().to_string());
                         args.push(calendar.clone());
-                        flags.insert("format".to_string(), Some(format.clone()));
+                        if let Some(f) = format {
+                            flags.insert("format".to_string(), Some(f.clone()));
+                        }
+                        if let Some(m) = map {
+                            flags.insert("map".to_string(), Some(m.clone()));
+                        }
+                        if *dry_run {
+                            flags.insert("dry_run".to_string(), Some("true".to_string()));
+                        }
+                    }
+                    CalendarActions::Agenda { from, to, calendar, json } => {
+                        args.push("agenda".to_string());
+                        flags.insert("from".to_string(), Some(from.clone()));
+                        if let Some(t) = to {
+                            flags.insert("to".to_string(), Some(t.clone()));
+                        }
+                        if let Some(cals) = calendar {
+                            flags.insert("calendar".to_string(), Some(cals.join(",")));
+                        }
+                        if *json {
+                            flags.insert("json".to_string(), Some("true".to_string()));
+                        }
+                    }
+                    CalendarActions::Export { from, to, calendar, output } => {
+                        args.push("export".to_string());
+                        flags.insert("from".to_string(), Some(from.clone()));
+                        if let Some(t) = to {
+                            flags.insert("to".to_string(), Some(t.clone()));
+                        }
+                        if let Some(cals) = calendar {
+                            flags.insert("calendar".to_string(), Some(cals.join(",")));
+                        }
+                        if let Some(path) = output {
+                            flags.insert(
+                                "output".to_string(),
+                                Some(path.to_string_lossy().to_string()),
+                            );
+                        }
                     }
                     CalendarActions::SetDefault { calendar } => {
                         args.push("set-default".to_string());
@@ -460,13 +810,28 @@ pub fn convert_to_command_args(cli: &Cli) -> Option<CommandArgs> {
                     ReminderActions::Lists => {
                         args.push("lists".to_string());
                     }
-                    ReminderActions::List { list } => {
+                    ReminderActions::List { list, completed, pending } => {
                         args.push("list".to_string());
                         if let Some(l) = list {
                             args.push(l.clone());
                         }
+                        if *completed {
+                            flags.insert("completed".to_string(), Some("true".to_string()));
+                        }
+                        if *pending {
+                            flags.insert("pending".to_string(), Some("true".to_string()));
+                        }
                     }
-                    ReminderActions::Create { title, lists, remind, notes } => {
+                    ReminderActions::Create {
+                        title,
+                        lists,
+                        remind,
+                        notes,
+                        due,
+                        priority,
+                        flag,
+                        repeat,
+                    } => {
                         args.push("create".to_string());
                         args.push(title.clone());
                         for list in lists {
@@ -478,6 +843,18 @@ pub fn convert_to_command_args(cli: &Cli) -> Option<CommandArgs> {
                         if let Some(n) = notes {
                             flags.insert("notes".to_string(), Some(n.clone()));
                         }
+                        if let Some(d) = due {
+                            flags.insert("due".to_string(), Some(d.clone()));
+                        }
+                        if let Some(p) = priority {
+                            flags.insert("priority".to_string(), Some(p.clone()));
+                        }
+                        if *flag {
+                            flags.insert("flag".to_string(), Some("true".to_string()));
+                        }
+                        if let Some(r) = repeat {
+                            flags.insert("repeat".to_string(), Some(r.clone()));
+                        }
                     }
                     ReminderActions::Complete { reminder_id, list } => {
                         args.push("complete".to_string());
@@ -486,6 +863,13 @@ pub fn convert_to_command_args(cli: &Cli) -> Option<CommandArgs> {
                             args.push(l.clone());
                         }
                     }
+                    ReminderActions::Uncomplete { reminder_id, list } => {
+                        args.push("uncomplete".to_string());
+                        args.push(reminder_id.clone());
+                        if let Some(l) = list {
+                            args.push(l.clone());
+                        }
+                    }
                     ReminderActions::Delete { reminder_id, list } => {
                         args.push("delete".to_string());
                         args.push(reminder_id.clone());
@@ -533,6 +917,35 @@ pub fn convert_to_command_args(cli: &Cli) -> Option<CommandArgs> {
                             flags.insert("folder".to_string(), Some(f.clone()));
                         }
                     }
+                    NoteActions::Append { title, content, folder } => {
+                        args.push("append".to_string());
+                        args.push(title.join(" "));
+                        flags.insert("content".to_string(), Some(content.clone()));
+                        if let Some(f) = folder {
+                            flags.insert("folder".to_string(), Some(f.clone()));
+                        }
+                    }
+                    NoteActions::Edit { title, folder } => {
+                        args.push("edit".to_string());
+                        args.push(title.join(" "));
+                        if let Some(f) = folder {
+                            flags.insert("folder".to_string(), Some(f.clone()));
+                        }
+                    }
+                    NoteActions::Export { dir, folder } => {
+                        args.push("export".to_string());
+                        flags.insert("dir".to_string(), Some(dir.to_string_lossy().to_string()));
+                        if let Some(f) = folder {
+                            flags.insert("folder".to_string(), Some(f.clone()));
+                        }
+                    }
+                    NoteActions::Import { dir, folder } => {
+                        args.push("import".to_string());
+                        flags.insert("dir".to_string(), Some(dir.to_string_lossy().to_string()));
+                        if let Some(f) = folder {
+                            flags.insert("folder".to_string(), Some(f.clone()));
+                        }
+                    }
                     NoteActions::Delete { note_id, folder } => {
                         args.push("delete".to_string());
                         let id_str = note_id.join(" ");
@@ -607,6 +1020,33 @@ pub fn convert_to_command_args(cli: &Cli) -> Option<CommandArgs> {
 
                 Some(CommandArgs { command: "utility".to_string(), args, flags })
             }
+            Commands::State { action } => {
+                let mut args = Vec::new();
+                let mut flags = HashMap::new();
+
+                match action {
+                    StateActions::Migrate { dry_run } => {
+                        args.push("migrate".to_string());
+                        if *dry_run {
+                            flags.insert("dry-run".to_string(), Some("true".to_string()));
+                        }
+                    }
+                    StateActions::Import => {
+                        args.push("import".to_string());
+                    }
+                }
+
+                Some(CommandArgs { command: "state".to_string(), args, flags })
+            }
+            Commands::Search { query, kind, limit } => {
+                let mut flags = HashMap::new();
+                if let Some(k) = kind {
+                    flags.insert("type".to_string(), Some(k.clone()));
+                }
+                flags.insert("limit".to_string(), Some(limit.to_string()));
+
+                Some(CommandArgs { command: "search".to_string(), args: query.clone(), flags })
+            }
             Commands::Ai { nl_command } => {
                 let mut args = Vec::new();
                 let flags = HashMap::new();
@@ -281,6 +281,72 @@ fn process_calendar_create_args(args: &mut Vec<String>) {
     }
 }
 
+/// Get the value of a flag with surrounding quotes removed
+fn flag_value(args: &CommandArgs, name: &str) -> Option<String> {
+    args.flags.get(name).cloned().flatten().map(|v| v.trim_matches('"').to_string())
+}
+
+/// Split a comma-separated `--calendar` flag into calendar names
+fn calendar_list_flag(args: &CommandArgs) -> Vec<String> {
+    flag_value(args, "calendar")
+        .map(|list| {
+            list.split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Find the events titled `title` on `date` that a command should act on
+///
+/// With several matches, `--index` picks one and `all` takes them all;
+/// otherwise the matches are listed followed by `hint`, and nothing is
+/// returned. Nothing is returned either when no event matches.
+async fn select_events_by_title(
+    args: &CommandArgs,
+    title: &str,
+    date: chrono::NaiveDate,
+    all: bool,
+    hint: &str,
+) -> Result<Vec<crate::calendar::EventConfig>> {
+    let matches =
+        crate::calendar::find_events_by_title(title, date, &calendar_list_flag(args)).await?;
+    if matches.is_empty() {
+        println!("No event titled '{}' found on {}", title, date);
+        return Ok(Vec::new());
+    }
+
+    let index = flag_value(args, "index")
+        .map(|i| i.parse::<usize>().map_err(|_| anyhow!("Invalid index '{}'", i)))
+        .transpose()?;
+    match index {
+        Some(i) => {
+            let event = i
+                .checked_sub(1)
+                .and_then(|i| matches.get(i))
+                .ok_or_else(|| anyhow!("Index {} is out of range (1-{})", i, matches.len()))?;
+            Ok(vec![event.clone()])
+        }
+        None if matches.len() == 1 || all => Ok(matches),
+        None => {
+            println!("{} events titled '{}' on {}:", matches.len(), title, date);
+            for (i, m) in matches.iter().enumerate() {
+                println!(
+                    "  {}. {} {} [{}] uid: {}",
+                    i + 1,
+                    m.start_time,
+                    m.title,
+                    m.calendars.join(", "),
+                    m.uid.as_deref().unwrap_or("-")
+                );
+            }
+            println!("{}", hint);
+            Ok(Vec::new())
+        }
+    }
+}
+
 // Command handler trait for handling commands
 pub trait CommandHandler: Debug + Send + Sync {
     fn execute(&self, args: CommandArgs) -> Pin<Box<dyn Future<Output = Result<()>> + '_>>;
@@ -511,6 +577,34 @@ impl CommandHandler for CalendarHandler {
                         debug!("Added {} email attendees", config.emails.len());
                     }
 
+                    if args.flags.contains_key("check_conflicts")
+                        || args.flags.contains_key("check-conflicts")
+                    {
+                        let conflicts = crate::calendar::find_conflicts(&config).await?;
+                        if !conflicts.is_empty() {
+                            println!(
+                                "'{}' on {} overlaps {} existing event(s):",
+                                config.title,
+                                config.start_date,
+                                conflicts.len()
+                            );
+                            for event in &conflicts {
+                                println!(
+                                    "  {}-{} {} [{}]",
+                                    event.start_time,
+                                    event.end_time.as_deref().unwrap_or("?"),
+                                    event.title,
+                                    event.calendars.join(", ")
+                                );
+                            }
+                            if !args.flags.contains_key("force") {
+                                println!("Event not created. Use --force to create it anyway.");
+                                return Ok(());
+                            }
+                            println!("Creating the event anyway (--force).");
+                        }
+                    }
+
                     // If contacts are specified, use create_event_with_contacts
                     if let Some(contacts_str) = contacts {
                         info!("Processing contacts string: '{}'", contacts_str);
@@ -566,6 +660,280 @@ impl CommandHandler for CalendarHandler {
                     crate::calendar::create_event(config).await
                 }
                 Some("list") => crate::calendar::list_calendars().await,
+                Some("import") => {
+                    let Some(file) = args.args.get(1) else {
+                        println!(
+                            "Usage: ducktape calendar import <file> [calendar] [--format ics|csv] [--map field=column,...] [--dry-run]"
+                        );
+                        return Ok(());
+                    };
+                    let path = std::path::Path::new(file.trim_matches('"'));
+                    let calendar = args.args.get(2).map(|c| c.trim_matches('"').to_string());
+                    let format = args
+                        .flags
+                        .get("format")
+                        .cloned()
+                        .flatten()
+                        .or_else(|| path.extension().map(|ext| ext.to_string_lossy().to_string()))
+                        .unwrap_or_else(|| "ics".to_string())
+                        .to_lowercase();
+                    let dry_run =
+                        args.flags.contains_key("dry_run") || args.flags.contains_key("dry-run");
+
+                    match format.as_str() {
+                        "ics" | "ical" => {
+                            if dry_run {
+                                println!("Dry run is only supported for CSV imports.");
+                                return Ok(());
+                            }
+                            let report = crate::calendar::import_ics_events(path, calendar).await?;
+                            report.print_summary();
+                            Ok(())
+                        }
+                        "csv" => {
+                            let app_config = crate::config::Config::load()?;
+                            let mut options =
+                                crate::calendar::CsvImportOptions::from_config(&app_config)?
+                                    .with_dry_run(dry_run);
+                            if let Some(Some(spec)) = args.flags.get("map") {
+                                options = options.with_mapping_spec(spec.trim_matches('"'))?;
+                            }
+                            let report =
+                                crate::calendar::import_csv_events(path, calendar, &options)
+                                    .await?;
+                            report.print_summary();
+                            Ok(())
+                        }
+                        other => {
+                            println!("Unsupported import format '{}'. Use ics or csv.", other);
+                            Ok(())
+                        }
+                    }
+                }
+                Some("delete") => {
+                    let Some(event) = args.args.get(1).map(|e| e.trim_matches('"')) else {
+                        println!(
+                            "Usage: ducktape calendar delete <title> <date> [--calendar name] [--all | --index N] [--zoom]"
+                        );
+                        println!("       ducktape calendar delete <uid> [--zoom]");
+                        return Ok(());
+                    };
+                    let delete_zoom = args.flags.contains_key("zoom");
+
+                    let Some(date) = args.args.get(2) else {
+                        let deleted = crate::calendar::delete_event_by_uid(event, delete_zoom)
+                            .await
+                            .map_err(|e| {
+                                anyhow!("{}. To delete by title, also give the event date", e)
+                            })?;
+                        println!("Deleted '{}' on {}", deleted.title, deleted.start_date);
+                        return Ok(());
+                    };
+                    let date = crate::calendar::parse_date_argument(date.trim_matches('"'))?;
+                    let selected = select_events_by_title(
+                        &args,
+                        event,
+                        date,
+                        args.flags.contains_key("all"),
+                        "Nothing deleted. Re-run with --all or --index <number>.",
+                    )
+                    .await?;
+
+                    for event in selected {
+                        let uid = event.uid.as_deref().ok_or_else(|| {
+                            anyhow!("Event '{}' has no UID and cannot be deleted", event.title)
+                        })?;
+                        crate::calendar::delete_event_by_uid(uid, delete_zoom).await?;
+                        println!(
+                            "Deleted '{}' on {} at {} from {}",
+                            event.title,
+                            event.start_date,
+                            event.start_time,
+                            event.calendars.join(", ")
+                        );
+                    }
+                    Ok(())
+                }
+                Some("update") => {
+                    let Some(event) = args.args.get(1).map(|e| e.trim_matches('"')) else {
+                        println!(
+                            "Usage: ducktape calendar update <title> <date> [--calendar name] [--index N] [--date D] [--start HH:MM] [--end HH:MM] [--location L] [--add-email a,b] [--add-contacts names]"
+                        );
+                        println!(
+                            "       ducktape calendar update <uid> [--date D] [--start HH:MM] ..."
+                        );
+                        return Ok(());
+                    };
+                    let date = match flag_value(&args, "date") {
+                        Some(date) => Some(
+                            crate::calendar::parse_date_argument(&date)?
+                                .format("%Y-%m-%d")
+                                .to_string(),
+                        ),
+                        None => None,
+                    };
+                    let split = |list: String| -> Vec<String> {
+                        list.split(',')
+                            .map(|e| e.trim().to_string())
+                            .filter(|e| !e.is_empty())
+                            .collect()
+                    };
+                    let mut add_emails = flag_value(&args, "add_email")
+                        .or_else(|| flag_value(&args, "add-email"))
+                        .map(split)
+                        .unwrap_or_default();
+                    let add_contacts = flag_value(&args, "add_contacts")
+                        .or_else(|| flag_value(&args, "add-contacts"))
+                        .map(split)
+                        .unwrap_or_default();
+                    if !add_contacts.is_empty() {
+                        let names: Vec<&str> = add_contacts.iter().map(String::as_str).collect();
+                        let found = crate::calendar::lookup_contact_emails(&names).await;
+                        if found.is_empty() && add_emails.is_empty() {
+                            return Err(anyhow!(
+                                "No email addresses found for {}",
+                                add_contacts.join(", ")
+                            ));
+                        }
+                        add_emails.extend(found);
+                    }
+                    let update = crate::calendar::EventUpdate {
+                        date,
+                        start_time: flag_value(&args, "start"),
+                        end_time: flag_value(&args, "end"),
+                        location: flag_value(&args, "location"),
+                        add_emails,
+                    };
+
+                    let uids = match args.args.get(2) {
+                        Some(current_date) => {
+                            let current_date = crate::calendar::parse_date_argument(
+                                current_date.trim_matches('"'),
+                            )?;
+                            let selected = select_events_by_title(
+                                &args,
+                                event,
+                                current_date,
+                                false,
+                                "Nothing updated. Re-run with --index <number>.",
+                            )
+                            .await?;
+                            selected
+                                .into_iter()
+                                .map(|e| {
+                                    e.uid.ok_or_else(|| {
+                                        anyhow!(
+                                            "Event '{}' has no UID and cannot be updated",
+                                            e.title
+                                        )
+                                    })
+                                })
+                                .collect::<Result<Vec<_>>>()?
+                        }
+                        None => vec![event.to_string()],
+                    };
+
+                    for uid in uids {
+                        let updated = crate::calendar::update_event_by_uid(&uid, &update)
+                            .await
+                            .map_err(|e| {
+                                if args.args.len() > 2 {
+                                    e
+                                } else {
+                                    anyhow!("{}. To update by title, also give the event date", e)
+                                }
+                            })?;
+                        let when = if updated.all_day {
+                            "all day".to_string()
+                        } else {
+                            format!(
+                                "{}-{}",
+                                updated.start_time,
+                                updated.end_time.as_deref().unwrap_or("?")
+                            )
+                        };
+                        println!("Updated '{}': {} {}", updated.title, updated.start_date, when);
+                        if let Some(location) = &updated.location {
+                            println!("  Location: {}", location);
+                        }
+                        if !updated.emails.is_empty() {
+                            println!("  Attendees: {}", updated.emails.join(", "));
+                        }
+                    }
+                    Ok(())
+                }
+                Some("free") => {
+                    let date = crate::calendar::parse_date_argument(
+                        flag_value(&args, "date").as_deref().unwrap_or("today"),
+                    )?;
+                    let app_config = crate::config::Config::load()?;
+                    let minutes = match flag_value(&args, "duration") {
+                        Some(duration) => crate::calendar::parse_duration_minutes(&duration)?,
+                        None => app_config.calendar.default_duration_minutes.unwrap_or(60) as i64,
+                    };
+                    let hours = crate::calendar::WorkingHours::from_config(&app_config.calendar)?;
+                    let slots = crate::calendar::find_free_slots(
+                        date,
+                        minutes,
+                        &calendar_list_flag(&args),
+                        &hours,
+                    )
+                    .await?;
+
+                    println!(
+                        "Free slots of at least {} min on {} ({}):",
+                        minutes,
+                        date.format("%A, %-d %B %Y"),
+                        hours.on(date)
+                    );
+                    if slots.is_empty() {
+                        println!("  None");
+                    }
+                    for slot in slots {
+                        println!("  {} ({} min)", slot, slot.minutes());
+                    }
+                    Ok(())
+                }
+                Some("agenda") => {
+                    let (from, to) = crate::calendar::resolve_date_range(
+                        flag_value(&args, "from").as_deref(),
+                        flag_value(&args, "to").as_deref(),
+                        6,
+                    )?;
+                    let agenda =
+                        crate::calendar::get_agenda(&calendar_list_flag(&args), from, to).await?;
+                    if args.flags.contains_key("json") {
+                        println!("{}", serde_json::to_string_pretty(&agenda)?);
+                    } else {
+                        print!("{}", agenda.to_text());
+                    }
+                    Ok(())
+                }
+                Some("export") => {
+                    let (from, to) = crate::calendar::resolve_date_range(
+                        flag_value(&args, "from").as_deref(),
+                        flag_value(&args, "to").as_deref(),
+                        30,
+                    )?;
+                    let calendars = calendar_list_flag(&args);
+
+                    let events = crate::calendar::export_events(&calendars, from, to).await?;
+                    let ics = crate::calendar::events_to_ics(&events)?;
+                    match flag_value(&args, "output") {
+                        Some(path) => {
+                            std::fs::write(&path, ics)?;
+                            println!(
+                                "Exported {} event(s) from {} to {} into {}",
+                                events.len(),
+                                from,
+                                to,
+                                path
+                            );
+                        }
+                        None => print!("{}", ics),
+                    }
+                    Ok(())
+                }
                 Some("props") | None if args.command == "calendar-props" => {
                     crate::calendar::list_event_properties().await
                 }
@@ -576,7 +944,7 @@ impl CommandHandler for CalendarHandler {
                 }
                 _ => {
                     println!(
-                        "Unknown calendar command. Available commands: create, list, show, props"
+                        "Unknown calendar command. Available commands: create, list, agenda, free, update, delete, import, export, show, props"
                     );
                     Ok(())
                 }
@@ -744,6 +1112,27 @@ impl CommandHandler for ReminderHandler {
                             Some(note_text.trim_matches('"').trim_matches('\'').to_string());
                     }
 
+                    let due_date = flag_value(&args, "due").map(|due| {
+                        // Accept "today"/"tomorrow" in place of the date part
+                        let (day, time) =
+                            due.split_once(' ').map_or((due.as_str(), None), |(d, t)| (d, Some(t)));
+                        match (crate::reminder::resolve_relative_date(day), time) {
+                            (Ok(date), Some(time)) => format!("{} {}", date, time),
+                            (Ok(date), None) => date,
+                            (Err(_), _) => due.clone(),
+                        }
+                    });
+                    config.due_date = due_date.as_deref();
+                    if let Some(priority) = flag_value(&args, "priority") {
+                        config.priority = Some(priority.parse()?);
+                    }
+                    config.flagged =
+                        args.flags.contains_key("flag") || args.flags.contains_key("flagged");
+                    if let Some(repeat) = flag_value(&args, "repeat") {
+                        config.recurrence =
+                            Some(crate::reminder::parse_reminder_recurrence(&repeat)?);
+                    }
+
                     debug!("Final reminder config: {:?}", config);
 
                     // Use await with the async create_reminder function
@@ -759,8 +1148,62 @@ impl CommandHandler for ReminderHandler {
                     }
                 }
                 Some("list") => {
-                    // Implementation for listing reminders would go here using async/await
-                    println!("Listing reminders... (not implemented yet)");
+                    let list_name = args.args.get(1).map(|l| l.trim_matches('"'));
+                    let filter = if args.flags.contains_key("completed") {
+                        crate::reminder::ReminderFilter::Completed
+                    } else if args.flags.contains_key("pending") {
+                        crate::reminder::ReminderFilter::Pending
+                    } else {
+                        crate::reminder::ReminderFilter::All
+                    };
+                    let reminders = crate::reminder::get_reminders(list_name, filter).await?;
+                    if reminders.is_empty() {
+                        println!("No reminders found");
+                    }
+                    for reminder in reminders {
+                        let mut line = format!(
+                            "  [{}] {} ({})",
+                            if reminder.completed { "x" } else { " " },
+                            reminder.title,
+                            reminder.lists.join(", ")
+                        );
+                        if let Some(due) = &reminder.due_date {
+                            line.push_str(&format!(" due {}", due));
+                        }
+                        if let Some(priority) = reminder.priority {
+                            line.push_str(&format!(" [{} priority]", priority));
+                        }
+                        if reminder.flagged {
+                            line.push_str(" [flagged]");
+                        }
+                        if reminder.recurrence.is_some() {
+                            line.push_str(" (repeats)");
+                        }
+                        println!("{}", line);
+                    }
+                    Ok(())
+                }
+                Some(action @ ("complete" | "done" | "uncomplete" | "undone" | "reopen")) => {
+                    let completed = matches!(action, "complete" | "done");
+                    let Some(target) = args.args.get(1).map(|t| t.trim_matches('"')) else {
+                        println!("Usage: ducktape reminder {} <title|id> [list]", action);
+                        return Ok(());
+                    };
+                    let list_name = args.args.get(2).map(|l| l.trim_matches('"'));
+                    let (reminder, next_due) = if completed {
+                        crate::reminder::complete_reminder(target, list_name).await?
+                    } else {
+                        (crate::reminder::uncomplete_reminder(target, list_name).await?, None)
+                    };
+                    println!(
+                        "Reminder '{}' in {} marked as {}",
+                        reminder.title,
+                        reminder.lists.join(", "),
+                        if completed { "completed" } else { "not completed" }
+                    );
+                    if let Some(due) = next_due {
+                        println!("Next occurrence due {}", due);
+                    }
                     Ok(())
                 }
                 Some("delete") => {
@@ -770,7 +1213,7 @@ impl CommandHandler for ReminderHandler {
                 }
                 _ => {
                     println!(
-                        "Unknown reminder command. Available commands: create/add, list, delete"
+                        "Unknown reminder command. Available commands: create/add, list, complete, uncomplete, delete"
                     );
                     Ok(())
                 }
@@ -922,6 +1365,80 @@ impl CommandHandler for NotesHandler {
                         }
                     }
                 }
+                Some("append") => {
+                    let content = flag_value(&args, "content").filter(|c| !c.trim().is_empty());
+                    let (Some(title), Some(content)) = (note_title(&args), content) else {
+                        println!("Not enough arguments for note append command");
+                        println!(
+                            "Usage: ducktape note append <title> --content <text> [--folder <folder_name>]"
+                        );
+                        return Ok(());
+                    };
+                    let folder = flag_value(&args, "folder");
+
+                    match crate::notes::append_to_note(&title, folder.as_deref(), &content).await {
+                        Ok(_) => {
+                            println!("Appended to note: {}", title);
+                            Ok(())
+                        }
+                        Err(e) => {
+                            println!("Failed to append to note: {}", e);
+                            Err(e)
+                        }
+                    }
+                }
+                Some("edit") => {
+                    let Some(title) = note_title(&args) else {
+                        println!("Not enough arguments for note edit command");
+                        println!("Usage: ducktape note edit <title> [--folder <folder_name>]");
+                        return Ok(());
+                    };
+                    let folder = flag_value(&args, "folder");
+
+                    match crate::notes::edit_note(&title, folder.as_deref()).await {
+                        Ok(true) => {
+                            println!("Note updated: {}", title);
+                            Ok(())
+                        }
+                        Ok(false) => {
+                            println!("No changes made to note: {}", title);
+                            Ok(())
+                        }
+                        Err(e) => {
+                            println!("Failed to edit note: {}", e);
+                            Err(e)
+                        }
+                    }
+                }
+                Some("export") => {
+                    let Some(dir) = flag_value(&args, "dir") else {
+                        println!(
+                            "Usage: ducktape note export --dir <directory> [--folder <folder_name>]"
+                        );
+                        return Ok(());
+                    };
+                    let folder = flag_value(&args, "folder");
+
+                    let dir = std::path::PathBuf::from(dir);
+                    let report = crate::notes::export_notes(&dir, folder.as_deref()).await?;
+                    report.print_summary(&dir);
+                    Ok(())
+                }
+                Some("import") => {
+                    let Some(dir) = flag_value(&args, "dir") else {
+                        println!(
+                            "Usage: ducktape note import --dir <directory> [--folder <folder_name>]"
+                        );
+                        return Ok(());
+                    };
+                    let folder = flag_value(&args, "folder");
+
+                    let report =
+                        crate::notes::import_notes(std::path::Path::new(&dir), folder.as_deref())
+                            .await?;
+                    report.print_summary();
+                    Ok(())
+                }
                 Some("search") => {
                     if args.args.len() < 2 {
                         println!("Not enough arguments for note search command");
@@ -944,7 +1461,14 @@ impl CommandHandler for NotesHandler {
                         args.args[1].trim_matches('"').to_string()
                     };
 
-                    match crate::notes::search_notes(&keyword).await {
+                    let folder = flag_value(&args, "folder");
+                    let notes = crate::notes::search_notes(&keyword).await.map(|notes| {
+                        notes
+                            .into_iter()
+                            .filter(|note| folder.as_ref().is_none_or(|f| &note.folder == f))
+                            .collect::<Vec<_>>()
+                    });
+                    match notes {
                         Ok(notes) => {
                             if notes.is_empty() {
                                 println!("No notes found matching '{}'", keyword);
@@ -964,7 +1488,7 @@ impl CommandHandler for NotesHandler {
                 }
                 _ => {
                     println!(
-                        "Unknown notes command. Available commands: create/add, list, folders, delete, search"
+                        "Unknown notes command. Available commands: create/add, list, folders, append, edit, export, import, delete, search"
                     );
                     Ok(())
                 }
@@ -977,6 +1501,17 @@ impl CommandHandler for NotesHandler {
     }
 }
 
+/// The note title given after the subcommand, which may be split into several words
+fn note_title(args: &CommandArgs) -> Option<String> {
+    let parts: Vec<&str> = args.args[1..]
+        .iter()
+        .take_while(|arg| !arg.starts_with("--"))
+        .map(|arg| arg.trim_matches('"'))
+        .collect();
+    let title = parts.join(" ");
+    (!title.trim().is_empty()).then_some(title)
+}
+
 // Config handler
 #[derive(Debug)]
 pub struct ConfigHandler;
@@ -1025,6 +1560,28 @@ impl CommandHandler for ConfigHandler {
                         "notes.default_folder" => {
                             config.notes.default_folder = Some(value.clone());
                         }
+                        "notes.backend" => match value.to_lowercase().as_str() {
+                            "apple" => {
+                                config.notes.backend = Some(crate::config::NotesBackend::Apple);
+                            }
+                            "local" => {
+                                config.notes.backend = Some(crate::config::NotesBackend::Local);
+                            }
+                            _ => {
+                                println!("Invalid notes backend: {}", value);
+                                println!("Valid options are: apple, local");
+                                return Ok(());
+                            }
+                        },
+                        "state.backend" => match value.to_lowercase().as_str() {
+                            "json" => config.state.backend = crate::config::StateBackend::Json,
+                            "sqlite" => config.state.backend = crate::config::StateBackend::Sqlite,
+                            _ => {
+                                println!("Invalid state backend: {}", value);
+                                println!("Valid options are: json, sqlite");
+                                return Ok(());
+                            }
+                        },
                         "language_model.provider" => match value.to_lowercase().as_str() {
                             "grok" => {
                                 config.language_model.provider =
@@ -1034,9 +1591,30 @@ impl CommandHandler for ConfigHandler {
                                 config.language_model.provider =
                                     Some(crate::config::LLMProvider::DeepSeek);
                             }
+                            "openai-compatible" => {
+                                config.language_model.provider =
+                                    Some(crate::config::LLMProvider::OpenAICompatible);
+                            }
                             _ => {
                                 println!("Invalid language model provider: {}", value);
-                                println!("Valid options are: grok, deepseek");
+                                println!("Valid options are: grok, deepseek, openai-compatible");
+                                return Ok(());
+                            }
+                        },
+                        "language_model.base_url" => {
+                            config.language_model.base_url = Some(value.clone());
+                        }
+                        "language_model.model" => {
+                            config.language_model.model = Some(value.clone());
+                        }
+                        "language_model.api_key" => {
+                            config.language_model.api_key = Some(value.clone());
+                        }
+                        "language_model.timeout_secs" => match value.parse::<u64>() {
+                            Ok(secs) if secs > 0 => config.language_model.timeout_secs = Some(secs),
+                            _ => {
+                                println!("Invalid timeout: {}", value);
+                                println!("Give the number of seconds, e.g. 60");
                                 return Ok(());
                             }
                         },
@@ -1108,13 +1686,30 @@ impl CommandHandler for ConfigHandler {
                                     .unwrap_or_else(|| "Not set".to_string())
                             );
                         }
-                        "language_model.provider" => {
-                            let provider = match config.language_model.provider {
-                                Some(crate::config::LLMProvider::Grok) => "grok",
-                                Some(crate::config::LLMProvider::DeepSeek) => "deepseek",
-                                None => "none",
+                        "notes.backend" => {
+                            let backend = match config.notes.backend.clone().unwrap_or_default() {
+                                crate::config::NotesBackend::Apple => "apple",
+                                crate::config::NotesBackend::Local => "local",
                             };
-                            println!("language_model.provider = {}", provider);
+                            println!("notes.backend = {}", backend);
+                        }
+                        "state.backend" => {
+                            println!(
+                                "state.backend = {}",
+                                state_backend_name(config.state.backend)
+                            );
+                        }
+                        "language_model.provider" => {
+                            println!(
+                                "language_model.provider = {}",
+                                provider_name(&config.language_model)
+                            );
+                        }
+                        "language_model.base_url"
+                        | "language_model.model"
+                        | "language_model.api_key"
+                        | "language_model.timeout_secs" => {
+                            print_language_model_endpoint(&config.language_model);
                         }
                         "all" => {
                             println!("Current Configuration:");
@@ -1154,12 +1749,20 @@ impl CommandHandler for ConfigHandler {
                                     .default_folder
                                     .unwrap_or_else(|| "Not set".to_string())
                             );
-                            let provider = match config.language_model.provider {
-                                Some(crate::config::LLMProvider::Grok) => "grok",
-                                Some(crate::config::LLMProvider::DeepSeek) => "deepseek",
-                                None => "none",
+                            let backend = match config.notes.backend.clone().unwrap_or_default() {
+                                crate::config::NotesBackend::Apple => "apple",
+                                crate::config::NotesBackend::Local => "local",
                             };
-                            println!("language_model.provider = {}", provider);
+                            println!("notes.backend = {}", backend);
+                            println!(
+                                "state.backend = {}",
+                                state_backend_name(config.state.backend)
+                            );
+                            println!(
+                                "language_model.provider = {}",
+                                provider_name(&config.language_model)
+                            );
+                            print_language_model_endpoint(&config.language_model);
                         }
                         _ => {
                             println!("Unknown config key: {}", key);
@@ -1318,6 +1921,167 @@ impl CommandHandler for ContactGroupsHandler {
     }
 }
 
+// Search handler
+#[derive(Debug)]
+pub struct SearchHandler;
+
+impl CommandHandler for SearchHandler {
+    fn execute(&self, args: CommandArgs) -> Pin<Box<dyn Future<Output = Result<()>> + '_>> {
+        Box::pin(async move {
+            let mut query =
+                args.args.iter().map(|arg| quote_search_arg(arg)).collect::<Vec<_>>().join(" ");
+            if query.trim().is_empty() {
+                println!("Usage: ducktape search <query> [--type note|event|reminder] [--limit N]");
+                println!("Queries accept \"quoted phrases\", title:word and folder:name");
+                return Ok(());
+            }
+            if let Some(kind) = flag_value(&args, "type") {
+                query.push_str(&format!(" type:{}", kind));
+            }
+            let limit = match flag_value(&args, "limit") {
+                Some(value) => value
+                    .parse::<usize>()
+                    .map_err(|_| anyhow!("Invalid limit '{}'. Use a number", value))?,
+                None => 20,
+            };
+
+            let query = crate::search_index::SearchQuery::parse(&query)?;
+            let hits = crate::search_index::search(&query)?;
+            if hits.is_empty() {
+                println!("No matches found");
+                return Ok(());
+            }
+            for hit in hits.iter().take(limit) {
+                let document = &hit.document;
+                if document.folders.is_empty() {
+                    println!("  [{}] {}", document.kind, document.title);
+                } else {
+                    println!(
+                        "  [{}] {} ({})",
+                        document.kind,
+                        document.title,
+                        document.folders.join(", ")
+                    );
+                }
+            }
+            if hits.len() > limit {
+                println!("  ... and {} more", hits.len() - limit);
+            }
+            Ok(())
+        })
+    }
+
+    fn can_handle(&self, command: &str) -> bool {
+        command == "search"
+    }
+}
+
+/// Name of a state backend as written in the config
+fn state_backend_name(backend: crate::config::StateBackend) -> &'static str {
+    match backend {
+        crate::config::StateBackend::Json => "json",
+        crate::config::StateBackend::Sqlite => "sqlite",
+    }
+}
+
+fn provider_name(config: &crate::config::LanguageModelConfig) -> &'static str {
+    match config.provider {
+        Some(crate::config::LLMProvider::Grok) => "grok",
+        Some(crate::config::LLMProvider::DeepSeek) => "deepseek",
+        Some(crate::config::LLMProvider::OpenAICompatible) => "openai-compatible",
+        None => "none",
+    }
+}
+
+/// Print the OpenAI-compatible endpoint settings, without the API key itself
+fn print_language_model_endpoint(config: &crate::config::LanguageModelConfig) {
+    let not_set = || "Not set".to_string();
+    println!("language_model.base_url = {}", config.base_url.clone().unwrap_or_else(not_set));
+    println!("language_model.model = {}", config.model.clone().unwrap_or_else(not_set));
+    println!(
+        "language_model.api_key = {}",
+        if config.api_key.is_some() { "(set)" } else { "Not set" }
+    );
+    println!(
+        "language_model.timeout_secs = {}",
+        config.timeout_secs.map_or_else(not_set, |secs| secs.to_string())
+    );
+}
+
+/// Re-quote an argument the shell unquoted, so "weekly sync" stays a phrase
+fn quote_search_arg(arg: &str) -> String {
+    if !arg.contains(char::is_whitespace) || arg.contains('"') {
+        return arg.to_string();
+    }
+    match arg.split_once(':') {
+        Some((field, value)) if !field.is_empty() && field.chars().all(char::is_alphabetic) => {
+            format!("{}:\"{}\"", field, value)
+        }
+        _ => format!("\"{}\"", arg),
+    }
+}
+
+// State file handler
+#[derive(Debug)]
+pub struct StateHandler;
+
+impl CommandHandler for StateHandler {
+    fn execute(&self, args: CommandArgs) -> Pin<Box<dyn Future<Output = Result<()>> + '_>> {
+        Box::pin(async move {
+            match args.args.first().map(|s| s.as_str()) {
+                Some("migrate") => {
+                    let dry_run =
+                        args.flags.contains_key("dry_run") || args.flags.contains_key("dry-run");
+                    let steps = crate::state::StateManager::new()?.migrate(dry_run)?;
+                    if steps.is_empty() {
+                        println!(
+                            "State files are up to date (schema version {})",
+                            crate::state::SCHEMA_VERSION
+                        );
+                        return Ok(());
+                    }
+                    println!("{}", if dry_run { "Would migrate:" } else { "Migrated:" });
+                    for step in &steps {
+                        println!("  {}", step);
+                    }
+                    if dry_run {
+                        println!("Run without --dry-run to apply these changes");
+                    } else {
+                        println!("The previous files were kept in ~/.ducktape with a .bak suffix");
+                    }
+                    Ok(())
+                }
+                Some("import") => {
+                    let manager = crate::state::StateManager::with_backend(
+                        crate::state::StateManager::default_dir()?,
+                        crate::config::StateBackend::Sqlite,
+                    )?;
+                    for (filename, count) in manager.import_json()? {
+                        let noun = if count == 1 { "item" } else { "items" };
+                        println!("Imported {} {} from {}", count, noun, filename);
+                    }
+                    if crate::state::backend() != crate::config::StateBackend::Sqlite {
+                        println!(
+                            "Run `ducktape config set state.backend sqlite` to use the database"
+                        );
+                    }
+                    Ok(())
+                }
+                _ => {
+                    println!(
+                        "Unknown state command. Available commands: migrate [--dry-run], import"
+                    );
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    fn can_handle(&self, command: &str) -> bool {
+        command == "state"
+    }
+}
+
 // Version handler
 #[derive(Debug)]
 pub struct VersionHandler;
@@ -1388,6 +2152,8 @@ pub fn print_help() -> Result<()> {
     println!("  config    Manage configuration");
     println!("  contacts  Manage contact groups");
     println!("  utils     Utility commands");
+    println!("  search    Search local notes, events and reminders");
+    println!("  state     Upgrade state files (state migrate --dry-run)");
     println!("  help      Show this help message");
     println!("  version   Show version information");
     println!("  exit      Exit the application");
@@ -1436,6 +2202,8 @@ impl CommandProcessor {
             Box::new(ConfigHandler),
             Box::new(UtilitiesHandler),
             Box::new(ContactGroupsHandler),
+            Box::new(SearchHandler),
+            Box::new(StateHandler),
             Box::new(VersionHandler),
             Box::new(HelpHandler),
             Box::new(ExitHandler),
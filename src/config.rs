@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -12,6 +13,8 @@ pub struct Config {
     #[serde(default)]
     pub notes: NotesConfig,
     #[serde(default)]
+    pub state: StateConfig,
+    #[serde(default)]
     pub language_model: LanguageModelConfig,
 }
 
@@ -20,6 +23,13 @@ pub struct CalendarConfig {
     pub default_calendar: Option<String>,
     pub default_reminder_minutes: Option<i32>,
     pub default_duration_minutes: Option<i32>,
+    /// Start of the working day (HH:MM) used when suggesting free slots
+    pub working_hours_start: Option<String>,
+    /// End of the working day (HH:MM) used when suggesting free slots
+    pub working_hours_end: Option<String>,
+    /// CSV import column names keyed by event field, e.g. `title = "Subject"`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub csv_mapping: HashMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -31,6 +41,48 @@ pub struct ReminderConfig {
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct NotesConfig {
     pub default_folder: Option<String>,
+    /// Where notes are stored; defaults to Apple Notes on macOS and local JSON elsewhere
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<NotesBackend>,
+}
+
+impl NotesConfig {
+    /// The configured notes backend, or the platform default
+    pub fn backend(&self) -> NotesBackend {
+        self.backend.clone().unwrap_or_default()
+    }
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum NotesBackend {
+    /// Apple Notes via AppleScript
+    Apple,
+    /// JSON file under ~/.ducktape/notes
+    Local,
+}
+
+impl Default for NotesBackend {
+    fn default() -> Self {
+        if cfg!(target_os = "macos") { NotesBackend::Apple } else { NotesBackend::Local }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct StateConfig {
+    /// How the event, note and reminder history in ~/.ducktape is stored
+    #[serde(default)]
+    pub backend: StateBackend,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StateBackend {
+    /// One JSON file per kind of item
+    #[default]
+    Json,
+    /// A SQLite database, state.db
+    Sqlite,
 }
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Default)]
@@ -39,11 +91,26 @@ pub enum LLMProvider {
     #[default]
     Grok,
     DeepSeek,
+    /// Any server with an OpenAI-style chat completions endpoint, e.g. llama.cpp or Ollama
+    #[serde(rename = "openai-compatible")]
+    OpenAICompatible,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct LanguageModelConfig {
     pub provider: Option<LLMProvider>,
+    /// Endpoint of the OpenAI-compatible provider, e.g. `http://localhost:11434/v1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Model name sent to the OpenAI-compatible provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Bearer token for the OpenAI-compatible provider; local servers usually need none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Seconds to wait for the OpenAI-compatible provider to reply (default 30)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
 }
 
 impl Default for Config {
@@ -53,12 +120,16 @@ impl Default for Config {
                 default_calendar: Some("Calendar".to_string()),
                 default_reminder_minutes: Some(15),
                 default_duration_minutes: Some(60),
+                working_hours_start: Some("09:00".to_string()),
+                working_hours_end: Some("17:00".to_string()),
+                csv_mapping: HashMap::new(),
             },
             reminder: ReminderConfig {
                 default_list: Some("Reminders".to_string()),
                 default_reminder: true,
             },
-            notes: NotesConfig { default_folder: None },
+            notes: NotesConfig { default_folder: None, backend: None },
+            state: StateConfig::default(),
             language_model: LanguageModelConfig::default(),
         }
     }
@@ -96,6 +167,12 @@ impl Config {
                 LLMProvider::Grok | LLMProvider::DeepSeek => {
                     log::info!("Natural Language Mode detected: provider is {:?}", provider);
                 }
+                LLMProvider::OpenAICompatible => {
+                    log::info!(
+                        "Natural Language Mode detected: OpenAI-compatible endpoint {:?}",
+                        config.language_model.base_url
+                    );
+                }
             }
         }
 
@@ -149,13 +226,26 @@ mod tests {
                 default_calendar: Some("TestCalendar".to_string()),
                 default_reminder_minutes: Some(30),
                 default_duration_minutes: Some(45),
+                working_hours_start: Some("08:30".to_string()),
+                working_hours_end: None,
+                csv_mapping: HashMap::from([("title".to_string(), "Subject".to_string())]),
             },
             reminder: ReminderConfig {
                 default_list: Some("TestList".to_string()),
                 default_reminder: false,
             },
-            notes: NotesConfig { default_folder: Some("TestFolder".to_string()) },
-            language_model: LanguageModelConfig { provider: Some(LLMProvider::Grok) },
+            notes: NotesConfig {
+                default_folder: Some("TestFolder".to_string()),
+                backend: Some(NotesBackend::Local),
+            },
+            state: StateConfig { backend: StateBackend::Sqlite },
+            language_model: LanguageModelConfig {
+                provider: Some(LLMProvider::OpenAICompatible),
+                base_url: Some("http://localhost:11434/v1".to_string()),
+                model: Some("llama3.1".to_string()),
+                api_key: None,
+                timeout_secs: Some(120),
+            },
         };
 
         // Serialize and write directly to file
@@ -179,9 +269,22 @@ mod tests {
         assert_eq!(loaded_config.reminder.default_list, test_config.reminder.default_list);
         assert_eq!(loaded_config.reminder.default_reminder, test_config.reminder.default_reminder);
         assert_eq!(loaded_config.notes.default_folder, test_config.notes.default_folder);
+        assert_eq!(loaded_config.notes.backend, Some(NotesBackend::Local));
+        assert_eq!(loaded_config.state.backend, StateBackend::Sqlite);
+        assert_eq!(loaded_config.calendar.csv_mapping, test_config.calendar.csv_mapping);
+        assert_eq!(loaded_config.calendar.working_hours_start.as_deref(), Some("08:30"));
+        assert_eq!(loaded_config.calendar.working_hours_end, None);
 
         // Test that different LLM providers are correctly serialized/deserialized
-        assert!(matches!(loaded_config.language_model.provider, Some(LLMProvider::Grok)));
+        assert_eq!(loaded_config.language_model.provider, Some(LLMProvider::OpenAICompatible));
+        assert!(file_content.contains("provider = \"openai-compatible\""));
+        assert_eq!(
+            loaded_config.language_model.base_url.as_deref(),
+            Some("http://localhost:11434/v1")
+        );
+        assert_eq!(loaded_config.language_model.model.as_deref(), Some("llama3.1"));
+        assert_eq!(loaded_config.language_model.api_key, None);
+        assert_eq!(loaded_config.language_model.timeout_secs, Some(120));
 
         Ok(())
     }
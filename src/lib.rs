@@ -17,8 +17,10 @@ pub mod event_search;
 pub mod file_search;
 pub mod notes;
 pub mod parser; // New modular parser module
+pub mod preview;
 pub mod reminder;
 pub mod reminders;
+pub mod search_index;
 pub mod state;
 pub mod storage;
 // todo module removed in version 0.17.0, use reminder module instead
@@ -41,7 +41,7 @@ async fn main() -> Result<()> {
     let cli = cli::Cli::parse();
 
     // Create application instance early so we can use it for commands
-    let app = Application::new();
+    let app = Application::new().with_assume_yes(cli.yes);
 
     // Handle special flags
     if cli.api_server {
@@ -68,7 +68,8 @@ async fn main() -> Result<()> {
 
     // If we have command line arguments, process them directly with our new method
     // that supports natural language detection
-    if args.len() > 1 {
+    // (flags like --yes on their own start the terminal below)
+    if args.len() > 1 && cli.command.is_some() {
         return app.execute_from_args(args).await;
     }
 
@@ -1,39 +1,75 @@
 //! Notes management functionality.
 //!
-//! This module provides integration with macOS Notes.app.
+//! This module provides integration with macOS Notes.app, or with a local JSON
+//! note store on other platforms (see `NotesStore`).
 
 use anyhow::Result;
 
 mod notes_applescript;
+mod notes_export;
+mod notes_markdown;
+mod notes_store;
 mod notes_types;
 mod notes_util;
 mod notes_validation;
 
+pub use notes_export::{
+    MarkdownNote, NoteTransferFailure, NotesExportReport, NotesImportReport, export_notes,
+    import_notes,
+};
+pub use notes_markdown::{html_to_markdown, markdown_to_html};
+pub use notes_store::*;
 pub use notes_types::*;
 pub use notes_validation::*;
 
-/// Create a new note in Apple Notes
+/// Create a new note in the active note store
 pub async fn create_note(config: NoteConfig<'_>) -> Result<()> {
-    // Implementation relies on the notes_applescript module
-    notes_applescript::create_note(config).await
+    store().create_note(config).await
 }
 
-/// List all notes from Apple Notes
+/// List all notes, with their content as Markdown
 pub async fn list_notes() -> Result<Vec<NoteItem>> {
-    notes_applescript::list_notes().await
+    store().list_notes().await
 }
 
-/// Get notes folders from Apple Notes
+/// Get the names of all notes folders
 pub async fn get_note_folders() -> Result<Vec<String>> {
-    notes_applescript::get_note_folders().await
+    store().get_note_folders().await
 }
 
 /// Delete a note by title
 pub async fn delete_note(title: &str, folder: Option<&str>) -> Result<()> {
-    notes_applescript::delete_note(title, folder).await
+    store().delete_note(title, folder).await
 }
 
-/// Search notes by keyword
+/// Search notes by keyword, returning their content as Markdown
 pub async fn search_notes(keyword: &str) -> Result<Vec<NoteItem>> {
-    notes_applescript::search_notes(keyword).await
+    store().search_notes(keyword).await
+}
+
+/// Append Markdown to the end of the note with the given title
+pub async fn append_to_note(title: &str, folder: Option<&str>, content: &str) -> Result<()> {
+    let store = store();
+    let id = store.find_note_id(title, folder).await?;
+    store.update_note(&id, NoteUpdate::append(content)).await
+}
+
+/// Open the note with the given title in the user's editor as Markdown and save the result
+///
+/// Returns false if the text was left unchanged.
+pub async fn edit_note(title: &str, folder: Option<&str>) -> Result<bool> {
+    let store = store();
+    let id = store.find_note_id(title, folder).await?;
+    let note = store.get_note(&id).await?;
+    let edited = notes_util::edit_in_editor(&note.content)?;
+    if edited.trim_end() == note.content.trim_end() {
+        return Ok(false);
+    }
+    store.update_note(&id, NoteUpdate::replace(&edited)).await?;
+    Ok(true)
+}
+
+/// Update a note by its ID in the active note store
+pub async fn update_note(id: &str, update: NoteUpdate<'_>) -> Result<()> {
+    store().update_note(id, update).await
 }
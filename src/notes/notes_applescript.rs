@@ -4,13 +4,14 @@ use anyhow::{Result, anyhow};
 use log::{debug, error, info};
 use tokio::process::Command;
 
-use crate::notes::notes_types::{NoteConfig, NoteItem, NotesError};
-use crate::notes::notes_util::{escape_applescript_string, parse_notes_list};
+use crate::notes::notes_markdown::markdown_to_html;
+use crate::notes::notes_types::{NoteConfig, NoteContent, NoteItem, NoteUpdate, NotesError};
+use crate::notes::notes_util::{escape_applescript_string, parse_note_content, parse_note_records};
 use crate::notes::notes_validation::{
-    validate_folder_name, validate_note_config, validate_note_title, validate_search_keyword,
+    validate_folder_name, validate_note_config, validate_note_title, validate_note_update,
 };
 
-/// Creates a new note in Apple Notes
+/// Creates a new note in Apple Notes from Markdown content
 pub async fn create_note(config: NoteConfig<'_>) -> Result<()> {
     // Validate the note configuration
     validate_note_config(&config)?;
@@ -41,7 +42,7 @@ pub async fn create_note(config: NoteConfig<'_>) -> Result<()> {
 
     // Escape title and content to prevent command injection
     let escaped_title = escape_applescript_string(config.title);
-    let escaped_content = escape_applescript_string(config.content);
+    let escaped_content = escape_applescript_string(&markdown_to_html(config.content));
 
     let script = format!(
         r#"tell application "Notes"
@@ -77,57 +78,35 @@ pub async fn create_note(config: NoteConfig<'_>) -> Result<()> {
     }
 }
 
-/// Lists all notes from Apple Notes
+/// Lists all notes from Apple Notes, with their content as Markdown
 pub async fn list_notes() -> Result<Vec<NoteItem>> {
     // First ensure Notes.app is running
     ensure_notes_running().await?;
 
-    let script = r#"tell application "Notes"
+    let script = format!(
+        r#"{}
+tell application "Notes"
         try
-            set notesList to {}
+            set output to ""
             repeat with n in notes
-                set noteFolder to "Notes"
-                try
-                    set noteFolder to name of container of n
-                end try
-                set noteInfo to {name:name of n, folder:noteFolder}
-                copy noteInfo to end of notesList
+                set output to output & my noteRecord(n)
             end repeat
-            return notesList
+            return "Success: " & output
         on error errMsg
             return "Error: " & errMsg
         end try
-    end tell"#;
-
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(script)
-        .output()
-        .await
-        .map_err(|e| NotesError::ScriptError(e.to_string()))?;
-
-    let result = String::from_utf8_lossy(&output.stdout);
+    end tell"#,
+        NOTE_RECORD_HANDLERS
+    );
 
-    if result.contains("Error") {
-        error!("Failed to list notes: {}", result);
-        return Err(anyhow!("Failed to list notes: {}", result));
+    let result = run_notes_script(&script).await?;
+    match result.strip_prefix("Success: ") {
+        Some(records) => Ok(parse_note_records(records)),
+        None => {
+            error!("Failed to list notes: {}", result);
+            Err(anyhow!("Failed to list notes: {}", result.trim()))
+        }
     }
-
-    // Parse the notes list from the AppleScript output
-    let notes_list = parse_notes_list(&result);
-
-    // Convert to our NoteItem struct
-    let note_items: Vec<NoteItem> = notes_list
-        .into_iter()
-        .map(|(title, folder)| NoteItem {
-            title,
-            folder,
-            created: None, // Apple Notes doesn't easily provide creation date via AppleScript
-            modified: None, // Apple Notes doesn't easily provide modification date via AppleScript
-        })
-        .collect();
-
-    Ok(note_items)
 }
 
 /// Gets a list of all note folders from Apple Notes
@@ -183,17 +162,7 @@ pub async fn delete_note(title: &str, folder: Option<&str>) -> Result<()> {
     // First ensure Notes.app is running
     ensure_notes_running().await?;
 
-    let escaped_title = escape_applescript_string(title);
-
-    let folder_condition = if let Some(folder_name) = folder {
-        let escaped_folder = escape_applescript_string(folder_name);
-        format!(
-            "name of n is \"{}\" and name of container of n is \"{}\"",
-            escaped_title, escaped_folder
-        )
-    } else {
-        format!("name of n is \"{}\"", escaped_title)
-    };
+    let folder_condition = note_condition(title, folder);
 
     let script = format!(
         r#"tell application "Notes"
@@ -242,62 +211,193 @@ pub async fn delete_note(title: &str, folder: Option<&str>) -> Result<()> {
     }
 }
 
-/// Searches notes by keyword
-pub async fn search_notes(keyword: &str) -> Result<Vec<NoteItem>> {
-    // Validate the search keyword
-    validate_search_keyword(keyword)?;
+/// Finds the ID of the first note with the given title (and optionally folder)
+pub async fn find_note_id(title: &str, folder: Option<&str>) -> Result<String> {
+    validate_note_title(title)?;
+    if let Some(folder_name) = folder {
+        validate_folder_name(folder_name)?;
+    }
 
-    // First ensure Notes.app is running
     ensure_notes_running().await?;
 
-    let escaped_keyword = escape_applescript_string(keyword);
-
     let script = format!(
         r#"tell application "Notes"
             try
-                set matchingNotes to {{}}
                 repeat with n in notes
-                    if name of n contains "{0}" or body of n contains "{0}" then
-                        set noteFolder to "Notes"
-                        try
-                            set noteFolder to name of container of n
-                        end try
-                        set noteInfo to {{name:name of n, folder:noteFolder}}
-                        copy noteInfo to end of matchingNotes
+                    if {} then
+                        return "Success: " & (id of n as string)
                     end if
                 end repeat
-                return matchingNotes
+                return "Error: Note not found"
             on error errMsg
                 return "Error: " & errMsg
             end try
         end tell"#,
-        escaped_keyword
+        note_condition(title, folder)
     );
 
-    let output = Command::new("osascript")
-        .arg("-e")
-        .arg(&script)
-        .output()
-        .await
-        .map_err(|e| NotesError::ScriptError(e.to_string()))?;
+    let result = run_notes_script(&script).await?;
+    match result.trim().strip_prefix("Success: ") {
+        Some(id) => Ok(id.to_string()),
+        None if result.contains("Note not found") => {
+            Err(NotesError::NoteNotFound(title.to_string()).into())
+        }
+        None => Err(anyhow!("Failed to find note: {}", result.trim())),
+    }
+}
 
-    let result = String::from_utf8_lossy(&output.stdout);
+/// Reads a note's title, folder and content as Markdown by ID
+pub async fn get_note(id: &str) -> Result<NoteContent> {
+    ensure_notes_running().await?;
 
-    if result.contains("Error") {
-        error!("Failed to search notes: {}", result);
-        return Err(anyhow!("Failed to search notes: {}", result));
+    let script = format!(
+        r#"tell application "Notes"
+            try
+                set n to note id "{}"
+            on error
+                return "Error: Note not found"
+            end try
+            try
+                set noteFolder to "Notes"
+                try
+                    set noteFolder to name of container of n
+                end try
+                set sep to character id 31
+                return (id of n as string) & sep & noteFolder & sep & (name of n) & sep & (body of n)
+            on error errMsg
+                return "Error: " & errMsg
+            end try
+        end tell"#,
+        escape_applescript_string(id)
+    );
+
+    let result = run_notes_script(&script).await?;
+    if result.contains("Error: Note not found") {
+        error!("Note {} not found", id);
+        return Err(NotesError::NoteNotFound(id.to_string()).into());
+    } else if result.starts_with("Error: ") {
+        error!("Failed to read note {}: {}", id, result.trim());
+        return Err(anyhow!("Failed to read note: {}", result.trim()));
     }
+    Ok(parse_note_content(&result)?)
+}
 
-    // Parse the notes list from the AppleScript output
-    let notes_list = parse_notes_list(&result);
+/// Replaces or appends to the body of a note, and optionally renames it
+///
+/// Content is Markdown and is converted to the HTML Notes.app stores.
+pub async fn update_note(id: &str, update: NoteUpdate<'_>) -> Result<()> {
+    validate_note_update(&update)?;
 
-    // Convert to our NoteItem struct
-    let note_items: Vec<NoteItem> = notes_list
-        .into_iter()
-        .map(|(title, folder)| NoteItem { title, folder, created: None, modified: None })
-        .collect();
+    ensure_notes_running().await?;
+
+    let html = escape_applescript_string(&markdown_to_html(update.content));
+    let body = if update.append {
+        format!("set body of n to (body of n) & \"{}\"", html)
+    } else {
+        format!("set body of n to \"{}\"", html)
+    };
+    let rename = update
+        .title
+        .map(|title| format!("set name of n to \"{}\"", escape_applescript_string(title)))
+        .unwrap_or_default();
+
+    let script = format!(
+        r#"tell application "Notes"
+            try
+                set n to note id "{}"
+            on error
+                return "Error: Note not found"
+            end try
+            try
+                {}
+                {}
+                return "Success: Note updated"
+            on error errMsg
+                return "Error: " & errMsg
+            end try
+        end tell"#,
+        escape_applescript_string(id),
+        body,
+        rename
+    );
+
+    let result = run_notes_script(&script).await?;
+    if result.contains("Success") {
+        info!("Note updated: {}", id);
+        Ok(())
+    } else if result.contains("Note not found") {
+        error!("Note {} not found", id);
+        Err(NotesError::NoteNotFound(id.to_string()).into())
+    } else {
+        error!("Failed to update note: {}", result);
+        Err(anyhow!("Failed to update note: {}", result.trim()))
+    }
+}
+
+/// AppleScript handlers that print a note in the format read by `parse_note_records`
+const NOTE_RECORD_HANDLERS: &str = r#"on replaceText(t, searchText, replacement)
+    set AppleScript's text item delimiters to searchText
+    set parts to text items of t
+    set AppleScript's text item delimiters to replacement
+    set t to parts as string
+    set AppleScript's text item delimiters to ""
+    return t
+end replaceText
+
+on escapeField(v)
+    if v is missing value then return ""
+    set t to my replaceText(v as string, "\\", "\\\\")
+    set t to my replaceText(t, character id 31, "\\u")
+    return my replaceText(t, character id 30, "\\r")
+end escapeField
+
+on pad(v)
+    return text -2 thru -1 of ("0" & (v as integer))
+end pad
+
+on formatDate(d)
+    if d is missing value then return ""
+    return (year of d as string) & "-" & my pad(month of d as integer) & "-" & my pad(day of d) & "T" & my pad(hours of d) & ":" & my pad(minutes of d) & ":" & my pad(seconds of d)
+end formatDate
+
+on noteRecord(n)
+    set fieldSep to character id 31
+    tell application "Notes"
+        set noteFolder to "Notes"
+        try
+            set noteFolder to name of container of n
+        end try
+        set createdAt to my formatDate(creation date of n)
+        set modifiedAt to my formatDate(modification date of n)
+        return my escapeField(id of n) & fieldSep & my escapeField(name of n) & fieldSep & my escapeField(noteFolder) & fieldSep & createdAt & fieldSep & modifiedAt & fieldSep & my escapeField(body of n) & (character id 30)
+    end tell
+end noteRecord
+"#;
+
+/// AppleScript condition matching a note by title and, if given, folder
+fn note_condition(title: &str, folder: Option<&str>) -> String {
+    let escaped_title = escape_applescript_string(title);
+    if let Some(folder_name) = folder {
+        let escaped_folder = escape_applescript_string(folder_name);
+        format!(
+            "name of n is \"{}\" and name of container of n is \"{}\"",
+            escaped_title, escaped_folder
+        )
+    } else {
+        format!("name of n is \"{}\"", escaped_title)
+    }
+}
+
+/// Runs a Notes script and returns its output
+async fn run_notes_script(script: &str) -> Result<String> {
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .await
+        .map_err(|e| NotesError::ScriptError(e.to_string()))?;
 
-    Ok(note_items)
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
 /// Ensures the Notes application is running
@@ -0,0 +1,423 @@
+//! Export notes to, and import them from, a directory of Markdown files.
+//
+// Each note is written to `<dir>/<folder>/<title>.md` with YAML front matter
+// holding its ID, title, folder and creation time. Import reads every `.md`
+// file under a directory. A file whose ID belongs to a note in the store
+// updates that note; otherwise it updates the note with the same title, or a
+// new note is created. Files without a matching ID that share a title in one
+// folder are imported as "Title (2)", "Title (3)" and so on. Notes.app sets the
+// creation time itself, so `created_at` is only informational on import.
+
+use anyhow::{Context, Result, anyhow};
+use log::{debug, info, warn};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::notes::notes_types::{NoteConfig, NoteItem, NoteUpdate, NotesError};
+use crate::notes::{create_note, get_note_folders, list_notes, store, update_note};
+
+/// Longest file name stem written for a note, in characters
+const MAX_FILE_STEM: usize = 100;
+
+/// A note as stored in a Markdown file
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MarkdownNote {
+    /// ID of the exported note in its store, used to find it again on import
+    pub id: Option<String>,
+    pub title: String,
+    pub folder: Option<String>,
+    /// Creation time as YYYY-MM-DDTHH:MM:SS, when known
+    pub created_at: Option<String>,
+    /// Markdown content of the note
+    pub content: String,
+}
+
+impl MarkdownNote {
+    /// Build the file contents for a note read from Notes.app
+    pub fn from_note(note: &NoteItem) -> Self {
+        Self {
+            id: note.id.clone(),
+            title: note.title.clone(),
+            folder: Some(note.folder.clone()).filter(|f| !f.is_empty()),
+            created_at: note.created.clone(),
+            content: note.content.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Render the note as Markdown with YAML front matter
+    pub fn to_markdown(&self) -> String {
+        // JSON strings are valid YAML double-quoted scalars
+        let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+        let mut text = String::from("---\n");
+        if let Some(id) = &self.id {
+            text.push_str(&format!("id: {}\n", quote(id)));
+        }
+        text.push_str(&format!("title: {}\n", quote(&self.title)));
+        if let Some(folder) = &self.folder {
+            text.push_str(&format!("folder: {}\n", quote(folder)));
+        }
+        if let Some(created_at) = &self.created_at {
+            text.push_str(&format!("created_at: {}\n", created_at));
+        }
+        text.push_str("---\n");
+        text.push_str(&self.content);
+        if !self.content.ends_with('\n') {
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Read a Markdown file, using `default_title` if it has no front matter title
+    pub fn parse(text: &str, default_title: &str) -> Result<Self> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let mut note = Self { title: default_title.to_string(), ..Self::default() };
+
+        let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+            note.content = text.trim_end().to_string();
+            return Ok(note);
+        };
+        let mut lines = rest.split_inclusive('\n');
+        let mut consumed = 0;
+        let mut closed = false;
+        for line in lines.by_ref() {
+            consumed += line.len();
+            let line = line.trim_end();
+            if line == "---" {
+                closed = true;
+                break;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                return Err(anyhow!("Invalid front matter line '{}'", line));
+            };
+            let value = parse_yaml_scalar(value.trim())?;
+            match key.trim() {
+                "id" if !value.is_empty() => note.id = Some(value),
+                "title" if !value.is_empty() => note.title = value,
+                "folder" if !value.is_empty() => note.folder = Some(value),
+                "created_at" if !value.is_empty() => note.created_at = Some(value),
+                other => debug!("Ignoring front matter key '{}'", other),
+            }
+        }
+        if !closed {
+            return Err(anyhow!("Front matter is not closed with '---'"));
+        }
+        note.content = rest[consumed..].trim_end().to_string();
+        Ok(note)
+    }
+}
+
+/// Read a single-line YAML scalar: double-quoted, single-quoted or plain
+fn parse_yaml_scalar(value: &str) -> Result<String> {
+    if value.starts_with('"') {
+        serde_json::from_str(value).with_context(|| format!("Invalid quoted value {}", value))
+    } else if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        Ok(inner.replace("''", "'"))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+/// A file name stem for a note title, safe on macOS, Linux and Windows
+fn note_file_stem(title: &str) -> String {
+    let stem: String = title
+        .chars()
+        .map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { '-' } else { c })
+        .take(MAX_FILE_STEM)
+        .collect();
+    let stem = stem.trim().trim_matches('.').trim();
+    if stem.is_empty() { "Untitled".to_string() } else { stem.to_string() }
+}
+
+/// The first path under `dir` for `stem` that is not already in `used`
+fn unique_path(dir: &Path, stem: &str, used: &mut HashSet<PathBuf>) -> PathBuf {
+    let mut path = dir.join(format!("{}.md", stem));
+    let mut n = 2;
+    while !used.insert(path.clone()) {
+        path = dir.join(format!("{} ({}).md", stem, n));
+        n += 1;
+    }
+    path
+}
+
+/// The first of `title`, "title (2)", "title (3)"... not yet used in `folder`
+///
+/// Titles are compared ignoring case, as Notes.app does when looking them up.
+fn unique_title(
+    title: &str,
+    folder: Option<&str>,
+    used: &mut HashSet<(Option<String>, String)>,
+) -> String {
+    let folder = folder.map(str::to_lowercase);
+    let mut candidate = title.to_string();
+    let mut n = 2;
+    while !used.insert((folder.clone(), candidate.to_lowercase())) {
+        candidate = format!("{} ({})", title, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// A note that could not be exported or imported
+#[derive(Debug, Clone)]
+pub struct NoteTransferFailure {
+    /// The note's title, or the file it was read from
+    pub name: String,
+    /// Why the note was skipped
+    pub reason: String,
+}
+
+impl NoteTransferFailure {
+    fn new(name: impl Into<String>, reason: impl ToString) -> Self {
+        Self { name: name.into(), reason: reason.to_string() }
+    }
+}
+
+/// Outcome of `export_notes`
+#[derive(Debug, Clone, Default)]
+pub struct NotesExportReport {
+    /// Files written, one per note
+    pub written: Vec<PathBuf>,
+    /// Notes that could not be written
+    pub failed: Vec<NoteTransferFailure>,
+}
+
+impl NotesExportReport {
+    /// Print a human readable summary of the export
+    pub fn print_summary(&self, dir: &Path) {
+        println!("Exported {} note(s) to {}", self.written.len(), dir.display());
+        for failure in &self.failed {
+            println!("  Failed {}: {}", failure.name, failure.reason);
+        }
+    }
+}
+
+/// Outcome of `import_notes`
+#[derive(Debug, Clone, Default)]
+pub struct NotesImportReport {
+    /// Titles of notes created
+    pub created: Vec<String>,
+    /// Titles of existing notes whose content was replaced
+    pub updated: Vec<String>,
+    /// Files whose title was already used by another file for the same
+    /// folder, and the title they were imported under instead
+    pub renamed: Vec<(String, String)>,
+    /// Files that could not be imported
+    pub failed: Vec<NoteTransferFailure>,
+}
+
+impl NotesImportReport {
+    /// Print a human readable summary of the import
+    pub fn print_summary(&self) {
+        println!(
+            "Imported {} note(s): {} created, {} updated, {} failed",
+            self.created.len() + self.updated.len(),
+            self.created.len(),
+            self.updated.len(),
+            self.failed.len()
+        );
+        for (name, title) in &self.renamed {
+            println!("  Renamed {} to '{}' (duplicate title)", name, title);
+        }
+        for failure in &self.failed {
+            println!("  Failed {}: {}", failure.name, failure.reason);
+        }
+    }
+}
+
+/// Write every note, or those in `folder`, to Markdown files under `dir`
+pub async fn export_notes(dir: &Path, folder: Option<&str>) -> Result<NotesExportReport> {
+    if let Some(folder) = folder {
+        let folders = get_note_folders().await?;
+        if !folders.iter().any(|f| f == folder) {
+            return Err(NotesError::FolderNotFound(folder.to_string()).into());
+        }
+    }
+    let notes: Vec<NoteItem> = list_notes()
+        .await?
+        .into_iter()
+        .filter(|note| folder.is_none_or(|f| note.folder == f))
+        .collect();
+
+    let mut report = NotesExportReport::default();
+    let mut used = HashSet::new();
+    for note in &notes {
+        let folder_dir = dir.join(note_file_stem(&note.folder));
+        let path = unique_path(&folder_dir, &note_file_stem(&note.title), &mut used);
+        let written = fs::create_dir_all(&folder_dir)
+            .and_then(|_| fs::write(&path, MarkdownNote::from_note(note).to_markdown()));
+        match written {
+            Ok(_) => report.written.push(path),
+            Err(e) => report.failed.push(NoteTransferFailure::new(note.title.as_str(), e)),
+        }
+    }
+    info!("Exported {} of {} notes to {:?}", report.written.len(), notes.len(), dir);
+    Ok(report)
+}
+
+/// Create or update a note for every Markdown file under `dir`
+///
+/// Notes go to the folder in their front matter unless `folder` is given. A
+/// file exported from a note still in the store replaces that note's content.
+/// Other files replace the note with the same title in their folder, rather
+/// than duplicating it.
+pub async fn import_notes(dir: &Path, folder: Option<&str>) -> Result<NotesImportReport> {
+    if !dir.is_dir() {
+        return Err(anyhow!("{:?} is not a directory", dir));
+    }
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+        })
+        .collect();
+    files.sort();
+
+    let mut report = NotesImportReport::default();
+    let mut notes = Vec::new();
+    for path in files {
+        let name = path.strip_prefix(dir).unwrap_or(&path).display().to_string();
+        let stem = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+        match fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| MarkdownNote::parse(&text, &stem))
+        {
+            Ok(note) => notes.push((name, note)),
+            Err(e) => report.failed.push(NoteTransferFailure::new(name, e)),
+        }
+    }
+
+    // Files that still belong to a stored note keep their titles; the rest
+    // must not take those titles over
+    let stored: HashSet<String> =
+        list_notes().await?.into_iter().filter_map(|note| note.id).collect();
+    let is_stored = |note: &MarkdownNote| note.id.as_ref().is_some_and(|id| stored.contains(id));
+    let mut used_titles = HashSet::new();
+    for (_, note) in notes.iter().filter(|(_, note)| is_stored(note)) {
+        let target = folder.or(note.folder.as_deref());
+        used_titles.insert((target.map(str::to_lowercase), note.title.to_lowercase()));
+    }
+
+    for (name, mut note) in notes {
+        let target = folder.or(note.folder.as_deref()).map(str::to_string);
+        let target = target.as_deref();
+        let stored_id = note.id.clone().filter(|id| stored.contains(id));
+        if stored_id.is_none() {
+            let title = unique_title(&note.title, target, &mut used_titles);
+            if title != note.title {
+                warn!("{} has the same title as another note; importing it as '{}'", name, title);
+                report.renamed.push((name.clone(), title.clone()));
+                note.title = title;
+            }
+        }
+
+        let existing = match stored_id {
+            Some(id) => Ok(id),
+            None => store().find_note_id(&note.title, target).await,
+        };
+        let result = match existing {
+            Ok(id) => update_note(&id, NoteUpdate::replace(&note.content))
+                .await
+                .map(|_| report.updated.push(note.title.clone())),
+            Err(e) if matches!(e.downcast_ref(), Some(NotesError::NoteNotFound(_))) => {
+                let config =
+                    NoteConfig { title: &note.title, content: &note.content, folder: target };
+                create_note(config).await.map(|_| report.created.push(note.title.clone()))
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            report.failed.push(NoteTransferFailure::new(name, e));
+        }
+    }
+    info!(
+        "Imported notes from {:?}: {} created, {} updated, {} failed",
+        dir,
+        report.created.len(),
+        report.updated.len(),
+        report.failed.len()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> MarkdownNote {
+        MarkdownNote {
+            id: Some("x-coredata://1/ICNote/p7".to_string()),
+            title: "Weekly sync: \"Q3\"".to_string(),
+            folder: Some("Work".to_string()),
+            created_at: Some("2024-06-03T10:15:00".to_string()),
+            content: "# Weekly sync: \"Q3\"\n\n- [ ] Send notes".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_markdown_note_front_matter() {
+        assert_eq!(
+            sample().to_markdown(),
+            "---\nid: \"x-coredata://1/ICNote/p7\"\ntitle: \"Weekly sync: \\\"Q3\\\"\"\nfolder: \"Work\"\n\
+             created_at: 2024-06-03T10:15:00\n---\n# Weekly sync: \"Q3\"\n\n- [ ] Send notes\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_note_round_trip() {
+        let note = sample();
+        assert_eq!(MarkdownNote::parse(&note.to_markdown(), "ignored").unwrap(), note);
+
+        let untitled = MarkdownNote { title: "Scratch".to_string(), ..MarkdownNote::default() };
+        assert_eq!(MarkdownNote::parse(&untitled.to_markdown(), "other").unwrap(), untitled);
+    }
+
+    #[test]
+    fn test_markdown_note_parse() {
+        let plain = MarkdownNote::parse("Just text\n", "ideas").unwrap();
+        assert_eq!(plain.title, "ideas");
+        assert_eq!(plain.folder, None);
+        assert_eq!(plain.content, "Just text");
+
+        let yaml = "---\r\ntitle: 'It''s done'\r\nfolder: Home\r\ntags: [a, b]\r\n---\r\nBody\r\n";
+        let note = MarkdownNote::parse(yaml, "file").unwrap();
+        assert_eq!(note.title, "It's done");
+        assert_eq!(note.folder.as_deref(), Some("Home"));
+        assert_eq!(note.content, "Body");
+
+        assert!(MarkdownNote::parse("---\ntitle: x\nBody\n", "file").is_err());
+        assert!(MarkdownNote::parse("---\ntitle: \"open\n---\n", "file").is_err());
+    }
+
+    #[test]
+    fn test_note_file_stem() {
+        assert_eq!(note_file_stem("Plan: Q3/Q4?"), "Plan- Q3-Q4-");
+        assert_eq!(note_file_stem("  ..  "), "Untitled");
+        assert_eq!(note_file_stem(&"é".repeat(150)).chars().count(), MAX_FILE_STEM);
+    }
+
+    #[test]
+    fn test_unique_path() {
+        let mut used = HashSet::new();
+        let dir = Path::new("notes/Work");
+        assert_eq!(unique_path(dir, "Plan", &mut used), dir.join("Plan.md"));
+        assert_eq!(unique_path(dir, "Plan", &mut used), dir.join("Plan (2).md"));
+        assert_eq!(unique_path(dir, "Plan", &mut used), dir.join("Plan (3).md"));
+    }
+
+    #[test]
+    fn test_unique_title() {
+        let mut used = HashSet::new();
+        assert_eq!(unique_title("Plan", Some("Work"), &mut used), "Plan");
+        assert_eq!(unique_title("plan", Some("work"), &mut used), "plan (2)");
+        assert_eq!(unique_title("Plan", Some("Work"), &mut used), "Plan (3)");
+        assert_eq!(unique_title("Plan", Some("Home"), &mut used), "Plan");
+        assert_eq!(unique_title("Plan", None, &mut used), "Plan");
+    }
+}
@@ -0,0 +1,484 @@
+//! Conversion between Markdown and the HTML body of a note.
+//
+// Notes.app stores a small subset of HTML: one `<div>` per line, `<h1>`-`<h3>`
+// headings, `<ul>`/`<ol>` lists (nested lists follow their parent item rather
+// than sitting inside it), `<b>`, `<i>`, `<strike>`, `<tt>` and links. Checklists
+// can't be created through AppleScript, so Markdown checkboxes are written as
+// list items starting with ☐ or ☑ and read back as `[ ]` and `[x]`.
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+/// Marks an unchecked checklist item in the note body
+const UNCHECKED_BOX: &str = "☐";
+/// Marks a checked checklist item in the note body
+const CHECKED_BOX: &str = "☑";
+
+lazy_static! {
+    static ref LIST_ITEM_RE: Regex = Regex::new(r"^(\s*)([-*+]|\d+[.)])\s+(.*)$")
+        .expect("Failed to compile LIST_ITEM_RE regex");
+    static ref LINK_RE: Regex =
+        Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").expect("Failed to compile LINK_RE regex");
+    static ref BOLD_RE: Regex =
+        Regex::new(r"\*\*(.+?)\*\*|__(.+?)__").expect("Failed to compile BOLD_RE regex");
+    static ref STRIKE_RE: Regex =
+        Regex::new(r"~~(.+?)~~").expect("Failed to compile STRIKE_RE regex");
+    // `_` only starts emphasis outside a word, so snake_case names are left alone
+    static ref ITALIC_RE: Regex = Regex::new(
+        r"\*([^*\s](?:[^*]*[^*\s])?)\*|(^|[^A-Za-z0-9_])_([^_\s](?:[^_]*[^_\s])?)_"
+    )
+    .expect("Failed to compile ITALIC_RE regex");
+    static ref TAG_RE: Regex = Regex::new(r"<(/?)([A-Za-z][A-Za-z0-9]*)([^>]*)>")
+        .expect("Failed to compile TAG_RE regex");
+    static ref IGNORED_MARKUP_RE: Regex =
+        Regex::new(r"(?s)<!--.*?-->|<![^>]*>").expect("Failed to compile IGNORED_MARKUP_RE regex");
+    static ref HREF_RE: Regex = Regex::new(r#"href\s*=\s*(?:"([^"]*)"|'([^']*)')"#)
+        .expect("Failed to compile HREF_RE regex");
+    static ref ENTITY_RE: Regex = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z]+);")
+        .expect("Failed to compile ENTITY_RE regex");
+}
+
+/// Convert Markdown to the HTML body Notes.app stores
+///
+/// Supports `#` headings, bullet, numbered and checkbox lists, fenced code
+/// blocks, bold, italic, strikethrough, inline code and links. Every other
+/// line becomes its own paragraph, and blank lines are kept.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    // Open lists as (tag, indent)
+    let mut lists: Vec<(&str, usize)> = Vec::new();
+    let mut lines = markdown.lines();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") {
+            close_lists(&mut html, &mut lists, 0);
+            for code in lines.by_ref() {
+                if code.trim_start().starts_with("```") {
+                    break;
+                }
+                if code.is_empty() {
+                    html.push_str("<div><tt><br></tt></div>");
+                } else {
+                    html.push_str(&format!("<div><tt>{}</tt></div>", escape_html(code)));
+                }
+            }
+            continue;
+        }
+
+        if let Some(caps) = LIST_ITEM_RE.captures(line) {
+            let indent = caps[1].replace('\t', "    ").len();
+            let tag = if caps[2].starts_with(|c: char| c.is_ascii_digit()) { "ol" } else { "ul" };
+            close_lists(&mut html, &mut lists, indent + 1);
+            if lists.last().is_some_and(|&(open, at)| at == indent && open != tag) {
+                close_lists(&mut html, &mut lists, indent);
+            }
+            if lists.last().is_none_or(|&(_, at)| at < indent) {
+                html.push_str(&format!("<{}>", tag));
+                lists.push((tag, indent));
+            }
+            let item = &caps[3];
+            let item = if let Some(rest) = item.strip_prefix("[ ] ") {
+                format!("{} {}", UNCHECKED_BOX, inline_to_html(rest))
+            } else if let Some(rest) =
+                item.strip_prefix("[x] ").or_else(|| item.strip_prefix("[X] "))
+            {
+                format!("{} {}", CHECKED_BOX, inline_to_html(rest))
+            } else {
+                inline_to_html(item)
+            };
+            html.push_str(&format!("<li>{}</li>", item));
+            continue;
+        }
+
+        close_lists(&mut html, &mut lists, 0);
+        let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            // Notes.app has three heading levels
+            let level = hashes.min(3);
+            let text = inline_to_html(trimmed[hashes..].trim());
+            html.push_str(&format!("<div><h{0}>{1}</h{0}></div>", level, text));
+        } else if trimmed.is_empty() {
+            html.push_str("<div><br></div>");
+        } else {
+            html.push_str(&format!("<div>{}</div>", inline_to_html(line)));
+        }
+    }
+    close_lists(&mut html, &mut lists, 0);
+    html
+}
+
+/// Close open lists indented at `indent` or more
+fn close_lists(html: &mut String, lists: &mut Vec<(&str, usize)>, indent: usize) {
+    while let Some(&(tag, at)) = lists.last() {
+        if at < indent {
+            break;
+        }
+        html.push_str(&format!("</{}>", tag));
+        lists.pop();
+    }
+}
+
+/// Convert the inline Markdown of one line to HTML
+fn inline_to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut rest = text;
+    // Code spans are copied verbatim, everything else gets emphasis and links
+    while let Some(start) = rest.find('`') {
+        let Some(len) = rest[start + 1..].find('`') else {
+            break;
+        };
+        html.push_str(&emphasis_to_html(&rest[..start]));
+        html.push_str(&format!("<tt>{}</tt>", escape_html(&rest[start + 1..start + 1 + len])));
+        rest = &rest[start + len + 2..];
+    }
+    html.push_str(&emphasis_to_html(rest));
+    html
+}
+
+fn emphasis_to_html(text: &str) -> String {
+    let html = escape_html(text);
+    let html = LINK_RE.replace_all(&html, r#"<a href="$2">$1</a>"#);
+    let html = BOLD_RE.replace_all(&html, |caps: &Captures| {
+        format!("<b>{}</b>", caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str()))
+    });
+    let html = STRIKE_RE.replace_all(&html, "<strike>$1</strike>");
+    let html = ITALIC_RE.replace_all(&html, |caps: &Captures| match caps.get(1) {
+        Some(text) => format!("<i>{}</i>", text.as_str()),
+        None => format!("{}<i>{}</i>", &caps[2], &caps[3]),
+    });
+    html.into_owned()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// One line of Markdown produced from a note body
+struct MarkdownLine {
+    text: String,
+    /// Whether the line was entirely monospaced, making it part of a code block
+    code: bool,
+}
+
+/// State while walking the tags of a note body
+#[derive(Default)]
+struct HtmlToMarkdown {
+    lines: Vec<MarkdownLine>,
+    /// Heading or list marker for the current line
+    prefix: String,
+    current: String,
+    /// Whether anything, even a line break, was added since the last line ended
+    dirty: bool,
+    /// Open lists as (ordered, items so far)
+    lists: Vec<(bool, usize)>,
+    /// Targets of the open links
+    links: Vec<Option<String>>,
+    /// Whether the current line starts with monospaced text, and where that text ended
+    code_start: bool,
+    code_end: Option<usize>,
+    /// Depth of elements whose content is not part of the note
+    skip: usize,
+}
+
+impl HtmlToMarkdown {
+    fn end_line(&mut self) {
+        if !self.dirty && self.current.is_empty() {
+            self.prefix.clear();
+            return;
+        }
+        let mut text = std::mem::take(&mut self.current);
+        let code = self.code_start && self.code_end.is_none_or(|end| end == text.len());
+        if self.code_start && !code {
+            // The monospaced text was followed by more text, so it is inline code
+            if let Some(end) = self.code_end {
+                text.insert(end, '`');
+            }
+            text.insert(0, '`');
+        }
+        let prefix = std::mem::take(&mut self.prefix);
+        let text = if code {
+            text
+        } else if let Some(rest) = text.strip_prefix(UNCHECKED_BOX) {
+            format!("{}[ ]{}", prefix, rest)
+        } else if let Some(rest) = text.strip_prefix(CHECKED_BOX) {
+            format!("{}[x]{}", prefix, rest)
+        } else {
+            format!("{}{}", prefix, text)
+        };
+        self.lines.push(MarkdownLine { text, code });
+        self.dirty = false;
+        self.code_start = false;
+        self.code_end = None;
+    }
+
+    fn push_text(&mut self, text: &str) {
+        self.current.push_str(text);
+        self.dirty = true;
+    }
+
+    fn open(&mut self, tag: &str, attributes: &str) {
+        match tag {
+            "head" | "style" | "script" | "title" => self.skip += 1,
+            "div" | "p" | "tr" | "blockquote" | "pre" => self.end_line(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.end_line();
+                let level = tag[1..].parse::<usize>().unwrap_or(1);
+                self.prefix = format!("{} ", "#".repeat(level));
+            }
+            "ul" | "ol" => {
+                self.end_line();
+                self.lists.push((tag == "ol", 0));
+            }
+            "li" => {
+                self.end_line();
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some((true, count)) => {
+                        *count += 1;
+                        format!("{}. ", count)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.prefix = format!("{}{}", "  ".repeat(depth), marker);
+            }
+            "br" => {
+                if self.current.is_empty() {
+                    self.dirty = true;
+                } else {
+                    self.end_line();
+                }
+            }
+            "b" | "strong" => self.push_text("**"),
+            "i" | "em" => self.push_text("*"),
+            "strike" | "s" | "del" => self.push_text("~~"),
+            "tt" | "code" => {
+                if self.current.is_empty() && self.prefix.is_empty() && !self.code_start {
+                    self.code_start = true;
+                    self.dirty = true;
+                } else {
+                    self.push_text("`");
+                }
+            }
+            "a" => {
+                let href = HREF_RE
+                    .captures(attributes)
+                    .and_then(|caps| caps.get(1).or_else(|| caps.get(2)))
+                    .map(|m| decode_entities(m.as_str()));
+                if href.is_some() {
+                    self.push_text("[");
+                }
+                self.links.push(href);
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, tag: &str) {
+        match tag {
+            "head" | "style" | "script" | "title" => self.skip = self.skip.saturating_sub(1),
+            "div" | "p" | "tr" | "blockquote" | "pre" | "li" | "h1" | "h2" | "h3" | "h4" | "h5"
+            | "h6" => self.end_line(),
+            "ul" | "ol" => {
+                self.end_line();
+                self.lists.pop();
+            }
+            "b" | "strong" => self.push_text("**"),
+            "i" | "em" => self.push_text("*"),
+            "strike" | "s" | "del" => self.push_text("~~"),
+            "tt" | "code" => {
+                if self.code_start && self.code_end.is_none() {
+                    self.code_end = Some(self.current.len());
+                } else {
+                    self.push_text("`");
+                }
+            }
+            "a" => {
+                if let Some(Some(href)) = self.links.pop() {
+                    let text_start = self.current.rfind('[').map_or(0, |i| i + 1);
+                    if self.current[text_start..] == href {
+                        // An auto-linked URL reads better as plain text
+                        self.current.truncate(text_start.saturating_sub(1));
+                        self.push_text(&href);
+                    } else {
+                        self.push_text(&format!("]({})", href));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.skip > 0 || text.is_empty() {
+            return;
+        }
+        // Line breaks in the source are only formatting between tags
+        if text.trim().is_empty() && text.contains('\n') {
+            return;
+        }
+        let text = decode_entities(&text.replace(['\r', '\n'], " "));
+        self.push_text(&text);
+    }
+
+    fn finish(mut self) -> String {
+        self.end_line();
+        let mut markdown: Vec<String> = Vec::new();
+        let mut in_code = false;
+        for line in self.lines {
+            if line.code != in_code {
+                markdown.push("```".to_string());
+                in_code = line.code;
+            }
+            markdown.push(line.text);
+        }
+        if in_code {
+            markdown.push("```".to_string());
+        }
+        while markdown.last().is_some_and(|line| line.trim().is_empty()) {
+            markdown.pop();
+        }
+        let start = markdown.iter().position(|line| !line.trim().is_empty()).unwrap_or(0);
+        markdown[start..].join("\n")
+    }
+}
+
+/// Convert the HTML body of a note to Markdown
+///
+/// The inverse of `markdown_to_html` for the HTML Notes.app produces. Tags it
+/// doesn't know, such as `<span>` and `<font>`, are dropped and their text kept.
+/// A paragraph that is entirely inline code comes back as a code block.
+pub fn html_to_markdown(html: &str) -> String {
+    let html = IGNORED_MARKUP_RE.replace_all(html, "");
+    let mut converter = HtmlToMarkdown::default();
+    let mut last = 0;
+    for caps in TAG_RE.captures_iter(&html) {
+        let Some(whole) = caps.get(0) else {
+            continue;
+        };
+        converter.text(&html[last..whole.start()]);
+        last = whole.end();
+        let tag = caps[2].to_ascii_lowercase();
+        if &caps[1] == "/" {
+            converter.close(&tag);
+        } else if converter.skip == 0
+            || matches!(tag.as_str(), "head" | "style" | "script" | "title")
+        {
+            converter.open(&tag, &caps[3]);
+        }
+    }
+    converter.text(&html[last..]);
+    converter.finish()
+}
+
+/// Decode the HTML entities Notes.app writes
+fn decode_entities(text: &str) -> String {
+    ENTITY_RE
+        .replace_all(text, |caps: &Captures| {
+            let entity = &caps[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16).ok())
+                    .unwrap_or_else(|| entity.strip_prefix('#').and_then(|n| n.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| caps[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEETING_NOTES: &str = "# Weekly sync\n\n\
+        Attendees: **Ann** and *Joe*, see [the doc](https://example.com/a?b=1&c=2)\n\n\
+        ## Actions\n\
+        - [ ] Send the ~~draft~~ final plan\n\
+        - [x] Book a room\n\
+        - Follow up\n  \
+          - with `legal`\n\
+        1. First\n\
+        2. Second\n\n\
+        ```\n\
+        cargo test <all>\n\
+        \n\
+        done\n\
+        ```";
+
+    #[test]
+    fn test_markdown_to_html() {
+        let html = markdown_to_html(MEETING_NOTES);
+        assert!(html.starts_with("<div><h1>Weekly sync</h1></div><div><br></div>"));
+        assert!(html.contains(
+            "<div>Attendees: <b>Ann</b> and <i>Joe</i>, see \
+             <a href=\"https://example.com/a?b=1&amp;c=2\">the doc</a></div>"
+        ));
+        assert!(html.contains("<div><h2>Actions</h2></div>"));
+        assert!(html.contains(
+            "<ul><li>☐ Send the <strike>draft</strike> final plan</li><li>☑ Book a room</li>\
+             <li>Follow up</li><ul><li>with <tt>legal</tt></li></ul></ul>\
+             <ol><li>First</li><li>Second</li></ol>"
+        ));
+        assert!(html.ends_with(
+            "<div><tt>cargo test &lt;all&gt;</tt></div><div><tt><br></tt></div><div><tt>done</tt></div>"
+        ));
+    }
+
+    #[test]
+    fn test_markdown_round_trips_through_html() {
+        assert_eq!(html_to_markdown(&markdown_to_html(MEETING_NOTES)), MEETING_NOTES);
+        let plain = "Just a line\nand another, with \"quotes\" & <brackets>";
+        assert_eq!(html_to_markdown(&markdown_to_html(plain)), plain);
+    }
+
+    #[test]
+    fn test_inline_markdown_edge_cases() {
+        assert_eq!(inline_to_html("snake_case_name stays"), "snake_case_name stays");
+        assert_eq!(inline_to_html("2 * 3 * 4"), "2 * 3 * 4");
+        assert_eq!(
+            inline_to_html("`**not bold**` but **bold**"),
+            "<tt>**not bold**</tt> but <b>bold</b>"
+        );
+        assert_eq!(inline_to_html("_italic_, __bold__"), "<i>italic</i>, <b>bold</b>");
+        assert_eq!(inline_to_html("unclosed `tick"), "unclosed `tick");
+    }
+
+    #[test]
+    fn test_html_to_markdown_reads_notes_app_html() {
+        let html = "<html><head><style>div { }</style></head><body>\n\
+            <div><h1>Trip</h1></div>\n\
+            <div><span style=\"font-size: 12px\">Flight at <b>9:40</b>&nbsp;from T2</span><br></div>\n\
+            <div><br></div>\n\
+            <div>Visit <a href=\"https://example.com\">https://example.com</a> or \
+            <tt>run</tt> this</div>\n\
+            <ul>\n<li>Passport</li>\n<li>Charger &amp; cable</li>\n</ul>\n\
+            <div>Line one<br>Line two</div>\n\
+            </body></html>";
+        assert_eq!(
+            html_to_markdown(html),
+            "# Trip\nFlight at **9:40** from T2\n\nVisit https://example.com or `run` this\n\
+             - Passport\n- Charger & cable\nLine one\nLine two"
+        );
+    }
+
+    #[test]
+    fn test_decode_entities() {
+        assert_eq!(
+            decode_entities("&lt;a&gt; &#39;x&#x27; &amp;amp; &bogus;"),
+            "<a> 'x' &amp; &bogus;"
+        );
+    }
+}
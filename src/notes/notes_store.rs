@@ -0,0 +1,379 @@
+//! Pluggable note stores for DuckTape.
+//
+// The public functions in `notes` go through the process-wide `NotesStore`
+// returned by `store()`. It is chosen from `notes.backend` in the config: Apple
+// Notes via AppleScript, or the JSON file kept by `storage::notes::NotesStorage`,
+// which is the default off macOS. Tests can swap the store with `set_store`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+
+use crate::config::{Config, NotesBackend};
+use crate::notes::notes_applescript;
+use crate::notes::notes_types::{NoteConfig, NoteContent, NoteItem, NoteUpdate, NotesError};
+use crate::notes::notes_validation::{
+    validate_folder_name, validate_note_config, validate_note_title, validate_note_update,
+    validate_search_keyword,
+};
+use crate::search_index::{self, SearchDocument, SearchHit, SearchIndex, SearchQuery};
+use crate::storage::notes::{DEFAULT_FOLDER, Note, NotesStorage};
+
+/// Operations a note store must provide for DuckTape to manage notes
+#[async_trait]
+pub trait NotesStore: Send + Sync {
+    /// Create a note from Markdown content
+    async fn create_note(&self, config: NoteConfig<'_>) -> Result<()>;
+
+    /// All notes, with their content as Markdown
+    async fn list_notes(&self) -> Result<Vec<NoteItem>>;
+
+    /// Names of all folders that hold notes
+    async fn get_note_folders(&self) -> Result<Vec<String>>;
+
+    /// Delete the first note with the given title (and optionally folder)
+    async fn delete_note(&self, title: &str, folder: Option<&str>) -> Result<()>;
+
+    /// Notes matching `keyword`, best match first
+    ///
+    /// Accepts the `search_index` query syntax, such as quoted phrases and
+    /// `title:`/`folder:` filters.
+    async fn search_notes(&self, keyword: &str) -> Result<Vec<NoteItem>>;
+
+    /// ID of the first note with the given title (and optionally folder)
+    async fn find_note_id(&self, title: &str, folder: Option<&str>) -> Result<String>;
+
+    /// The note with the given ID, with its content as Markdown
+    async fn get_note(&self, id: &str) -> Result<NoteContent>;
+
+    /// Replace, append to or rename the note with the given ID
+    async fn update_note(&self, id: &str, update: NoteUpdate<'_>) -> Result<()>;
+}
+
+static STORE: Lazy<RwLock<Arc<dyn NotesStore>>> = Lazy::new(|| {
+    let backend = Config::load().map(|config| config.notes.backend()).unwrap_or_else(|e| {
+        log::warn!("Failed to load config, using the default notes backend: {}", e);
+        NotesBackend::default()
+    });
+    RwLock::new(store_for(&backend))
+});
+
+/// Build the note store for a backend
+pub fn store_for(backend: &NotesBackend) -> Arc<dyn NotesStore> {
+    match backend {
+        NotesBackend::Apple => Arc::new(AppleNotesStore),
+        NotesBackend::Local => Arc::new(LocalNotesStore::default()),
+    }
+}
+
+/// Get the note store currently in use
+pub fn store() -> Arc<dyn NotesStore> {
+    match STORE.read() {
+        Ok(guard) => Arc::clone(&guard),
+        Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+    }
+}
+
+/// Replace the note store used by all notes operations
+pub fn set_store(new_store: Arc<dyn NotesStore>) {
+    match STORE.write() {
+        Ok(mut guard) => *guard = new_store,
+        Err(poisoned) => *poisoned.into_inner() = new_store,
+    }
+}
+
+/// Note store backed by Apple Notes through AppleScript
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AppleNotesStore;
+
+#[async_trait]
+impl NotesStore for AppleNotesStore {
+    async fn create_note(&self, config: NoteConfig<'_>) -> Result<()> {
+        notes_applescript::create_note(config).await
+    }
+
+    async fn list_notes(&self) -> Result<Vec<NoteItem>> {
+        notes_applescript::list_notes().await
+    }
+
+    async fn get_note_folders(&self) -> Result<Vec<String>> {
+        notes_applescript::get_note_folders().await
+    }
+
+    async fn delete_note(&self, title: &str, folder: Option<&str>) -> Result<()> {
+        notes_applescript::delete_note(title, folder).await
+    }
+
+    async fn search_notes(&self, keyword: &str) -> Result<Vec<NoteItem>> {
+        validate_search_keyword(keyword)?;
+        let query = SearchQuery::parse(keyword)?;
+        // Notes.app can't report what changed, so its notes are re-read into
+        // the shared index on every search
+        let notes: HashMap<String, NoteItem> = notes_applescript::list_notes()
+            .await?
+            .into_iter()
+            .enumerate()
+            .map(|(position, note)| (format!("{}{}", APPLE_NOTE_PREFIX, position), note))
+            .collect();
+        let documents = notes
+            .iter()
+            .map(|(id, note)| SearchDocument {
+                id: id.clone(),
+                ..SearchDocument::from_note_item(note)
+            })
+            .collect();
+        search_index::sync_documents(APPLE_NOTE_PREFIX, documents)?;
+        Ok(ranked(search_index::search(&query)?, notes))
+    }
+
+    async fn find_note_id(&self, title: &str, folder: Option<&str>) -> Result<String> {
+        notes_applescript::find_note_id(title, folder).await
+    }
+
+    async fn get_note(&self, id: &str) -> Result<NoteContent> {
+        notes_applescript::get_note(id).await
+    }
+
+    async fn update_note(&self, id: &str, update: NoteUpdate<'_>) -> Result<()> {
+        notes_applescript::update_note(id, update).await
+    }
+}
+
+/// Prefix of the search index IDs of notes read from Notes.app
+const APPLE_NOTE_PREFIX: &str = "notes-app:";
+
+/// The items that `hits` refer to, in rank order
+fn ranked<T>(hits: Vec<SearchHit>, mut items: HashMap<String, T>) -> Vec<T> {
+    hits.into_iter().filter_map(|hit| items.remove(&hit.document.id)).collect()
+}
+
+/// Note store that keeps Markdown notes in a local JSON file
+#[derive(Debug, Default, Clone)]
+pub struct LocalNotesStore {
+    /// Directory holding notes.json; `~/.ducktape/notes` when None
+    dir: Option<PathBuf>,
+}
+
+impl LocalNotesStore {
+    /// Create a store that keeps its notes.json in `dir`
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: Some(dir.into()) }
+    }
+
+    fn storage(&self) -> Result<NotesStorage> {
+        match &self.dir {
+            Some(dir) => NotesStorage::with_dir(dir.clone()),
+            None => NotesStorage::new(),
+        }
+    }
+
+    /// Keep the search index current; only the default store is indexed
+    fn reindex(&self, note: &Note) {
+        if self.dir.is_none() {
+            search_index::index_document(SearchDocument::from_note(note));
+        }
+    }
+
+    /// The first stored note matching a title and, if given, folder
+    fn find(&self, title: &str, folder: Option<&str>) -> Result<Note> {
+        self.storage()?
+            .list_notes()?
+            .into_iter()
+            .find(|note| note.title == title && folder.is_none_or(|f| note.folder_name() == f))
+            .ok_or_else(|| NotesError::NoteNotFound(title.to_string()).into())
+    }
+}
+
+fn to_note_item(note: Note) -> NoteItem {
+    let timestamp = |t: chrono::DateTime<chrono::Local>| t.format("%Y-%m-%dT%H:%M:%S").to_string();
+    NoteItem {
+        id: Some(note.id.clone()),
+        folder: note.folder_name().to_string(),
+        created: Some(timestamp(note.created_at)),
+        modified: Some(timestamp(note.updated_at)),
+        title: note.title,
+        content: Some(note.content),
+    }
+}
+
+#[async_trait]
+impl NotesStore for LocalNotesStore {
+    async fn create_note(&self, config: NoteConfig<'_>) -> Result<()> {
+        validate_note_config(&config)?;
+        let note = Note::new(config.title, config.content, config.folder);
+        self.storage()?.add_note(&note)?;
+        self.reindex(&note);
+        log::info!("Note created: {}", config.title);
+        Ok(())
+    }
+
+    async fn list_notes(&self) -> Result<Vec<NoteItem>> {
+        Ok(self.storage()?.list_notes()?.into_iter().map(to_note_item).collect())
+    }
+
+    async fn get_note_folders(&self) -> Result<Vec<String>> {
+        let mut folders: Vec<String> = self
+            .storage()?
+            .list_notes()?
+            .iter()
+            .map(|note| note.folder_name().to_string())
+            .chain(std::iter::once(DEFAULT_FOLDER.to_string()))
+            .collect();
+        folders.sort();
+        folders.dedup();
+        Ok(folders)
+    }
+
+    async fn delete_note(&self, title: &str, folder: Option<&str>) -> Result<()> {
+        validate_note_title(title)?;
+        if let Some(folder_name) = folder {
+            validate_folder_name(folder_name)?;
+        }
+        let note = self.find(title, folder)?;
+        self.storage()?.delete_note(&note.id)?;
+        if self.dir.is_none() {
+            search_index::remove_document(&SearchDocument::from_note(&note).id);
+        }
+        log::info!("Note deleted: {}", title);
+        Ok(())
+    }
+
+    async fn search_notes(&self, keyword: &str) -> Result<Vec<NoteItem>> {
+        validate_search_keyword(keyword)?;
+        let query = SearchQuery::parse(keyword)?;
+        let notes: HashMap<String, Note> = self
+            .storage()?
+            .list_notes()?
+            .into_iter()
+            .map(|note| (SearchDocument::from_note(&note).id, note))
+            .collect();
+        let hits = if self.dir.is_none() {
+            search_index::search(&query)?
+        } else {
+            // Only the default store is kept in the shared index
+            let mut index = SearchIndex::new();
+            for note in notes.values() {
+                index.add(SearchDocument::from_note(note));
+            }
+            index.search(&query)
+        };
+        Ok(ranked(hits, notes).into_iter().map(to_note_item).collect())
+    }
+
+    async fn find_note_id(&self, title: &str, folder: Option<&str>) -> Result<String> {
+        validate_note_title(title)?;
+        if let Some(folder_name) = folder {
+            validate_folder_name(folder_name)?;
+        }
+        Ok(self.find(title, folder)?.id)
+    }
+
+    async fn get_note(&self, id: &str) -> Result<NoteContent> {
+        let note = self
+            .storage()?
+            .get_note(id)?
+            .ok_or_else(|| NotesError::NoteNotFound(id.to_string()))?;
+        Ok(NoteContent {
+            id: note.id.clone(),
+            folder: note.folder_name().to_string(),
+            title: note.title,
+            content: note.content,
+        })
+    }
+
+    async fn update_note(&self, id: &str, update: NoteUpdate<'_>) -> Result<()> {
+        validate_note_update(&update)?;
+        let storage = self.storage()?;
+        let mut note =
+            storage.get_note(id)?.ok_or_else(|| NotesError::NoteNotFound(id.to_string()))?;
+        if update.append {
+            if !note.content.is_empty() {
+                note.content = format!("{}\n", note.content.trim_end());
+            }
+            note.content.push_str(update.content);
+        } else {
+            note.content = update.content.to_string();
+        }
+        if let Some(title) = update.title {
+            note.title = title.to_string();
+        }
+        storage.save_note(&note)?;
+        self.reindex(&note);
+        log::info!("Note updated: {}", note.title);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_local_store_creates_lists_and_searches() {
+        let dir = tempdir().unwrap();
+        let store = LocalNotesStore::with_dir(dir.path());
+        store.create_note(NoteConfig::new("Groceries", "- milk\n- eggs")).await.unwrap();
+        store
+            .create_note(NoteConfig::with_folder("Plan", "Ship **v2**", "Work"))
+            .await
+            .unwrap();
+
+        let notes = store.list_notes().await.unwrap();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].folder, DEFAULT_FOLDER);
+        assert_eq!(notes[1].folder, "Work");
+        assert_eq!(notes[1].content.as_deref(), Some("Ship **v2**"));
+        assert!(notes[1].created.is_some());
+
+        assert_eq!(store.get_note_folders().await.unwrap(), ["Notes", "Work"]);
+        let found = store.search_notes("MILK").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].title, "Groceries");
+        assert!(store.search_notes("bread").await.unwrap().is_empty());
+        let found = store.search_notes("title:plan folder:work").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].title, "Plan");
+    }
+
+    #[tokio::test]
+    async fn test_local_store_updates_by_id() {
+        let dir = tempdir().unwrap();
+        let store = LocalNotesStore::with_dir(dir.path());
+        store
+            .create_note(NoteConfig::with_folder("Log", "Day 1", "Work"))
+            .await
+            .unwrap();
+
+        assert!(store.find_note_id("Log", Some("Home")).await.is_err());
+        let id = store.find_note_id("Log", Some("Work")).await.unwrap();
+        store.update_note(&id, NoteUpdate::append("Day 2")).await.unwrap();
+        assert_eq!(store.get_note(&id).await.unwrap().content, "Day 1\nDay 2");
+
+        store
+            .update_note(&id, NoteUpdate::replace("Fresh").with_title("Diary"))
+            .await
+            .unwrap();
+        let note = store.get_note(&id).await.unwrap();
+        assert_eq!((note.title.as_str(), note.content.as_str()), ("Diary", "Fresh"));
+
+        let missing = store.update_note("nope", NoteUpdate::replace("x")).await.unwrap_err();
+        assert!(matches!(missing.downcast_ref(), Some(NotesError::NoteNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_local_store_deletes_by_title() {
+        let dir = tempdir().unwrap();
+        let store = LocalNotesStore::with_dir(dir.path());
+        store.create_note(NoteConfig::new("Old", "text")).await.unwrap();
+
+        let missing = store.delete_note("Other", None).await.unwrap_err();
+        assert!(matches!(missing.downcast_ref(), Some(NotesError::NoteNotFound(_))));
+        store.delete_note("Old", Some("Notes")).await.unwrap();
+        assert!(store.list_notes().await.unwrap().is_empty());
+    }
+}
@@ -1,6 +1,6 @@
 //! Type definitions for the notes module.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// Configuration for creating a note
 #[derive(Debug, Clone)]
@@ -25,17 +25,78 @@ impl<'a> NoteConfig<'a> {
     }
 }
 
-/// Represents a note from Apple Notes
+/// Represents a note from the active note store
+///
+/// This is also the record kept in the state directory's notes.json, which
+/// older versions wrote with an optional `folder` and a `created_at` field.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NoteItem {
+    /// ID of the note in its store, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     /// Title of the note
     pub title: String,
     /// Folder containing the note
+    #[serde(default, deserialize_with = "folder_or_default")]
     pub folder: String,
     /// Creation date of the note (if available)
+    #[serde(alias = "created_at")]
     pub created: Option<String>,
     /// Modification date of the note (if available)
     pub modified: Option<String>,
+    /// Content of the note as Markdown (if it was read)
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// Read a folder name that may be null, as older state files wrote it
+fn folder_or_default<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// A note's identity and its content as plain text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteContent {
+    /// Notes.app ID of the note
+    pub id: String,
+    /// Title of the note
+    pub title: String,
+    /// Folder containing the note
+    pub folder: String,
+    /// Content as Markdown; Notes.app starts it with the title line
+    pub content: String,
+}
+
+/// Changes to make to an existing note
+#[derive(Debug, Clone)]
+pub struct NoteUpdate<'a> {
+    /// New title, if the note should be renamed
+    pub title: Option<&'a str>,
+    /// Text to write to the note
+    pub content: &'a str,
+    /// Add `content` to the end of the note instead of replacing its body
+    pub append: bool,
+}
+
+impl<'a> NoteUpdate<'a> {
+    /// Replace the note's body with `content`
+    pub fn replace(content: &'a str) -> Self {
+        Self { title: None, content, append: false }
+    }
+
+    /// Add `content` to the end of the note
+    pub fn append(content: &'a str) -> Self {
+        Self { title: None, content, append: true }
+    }
+
+    /// Also rename the note
+    pub fn with_title(mut self, title: &'a str) -> Self {
+        self.title = Some(title);
+        self
+    }
 }
 
 /// Custom error type for notes operations
@@ -1,5 +1,12 @@
 //! Utility functions for the notes module.
 
+use anyhow::{Context, Result, anyhow};
+
+use log::debug;
+
+use crate::notes::notes_markdown::html_to_markdown;
+use crate::notes::notes_types::{NoteContent, NoteItem, NotesError};
+
 /// Helper function to escape strings for AppleScript to prevent command injection
 pub fn escape_applescript_string(input: &str) -> String {
     // First replace double quotes with escaped quotes for AppleScript
@@ -12,41 +19,103 @@ pub fn escape_applescript_string(input: &str) -> String {
         .collect::<String>()
 }
 
-/// Parse a notes list from AppleScript output
-pub fn parse_notes_list(output: &str) -> Vec<(String, String)> {
-    let mut notes = Vec::new();
+/// Separates the fields of one note in the output of the note scripts
+const FIELD_SEPARATOR: char = '\u{1f}';
+/// Terminates each note in the output of the listing scripts
+const RECORD_SEPARATOR: char = '\u{1e}';
 
-    // Parse the output format which is expected to be a list of records
-    // in the format {name:"Note Title", folder:"Folder Name"}, ...
-    let output = output.trim_matches('{').trim_matches('}');
-
-    if output.is_empty() {
-        return notes;
-    }
+/// Parse the note records printed by the `noteRecord` AppleScript handler
+///
+/// Each record holds the ID, title, folder, creation and modification times and
+/// HTML body of a note. Fields escape `\\` as `\\\\` and the two separators as `\\u` and
+/// `\\r`; times are YYYY-MM-DDTHH:MM:SS. The body is returned as Markdown, and
+/// records with the wrong number of fields are skipped.
+pub fn parse_note_records(output: &str) -> Vec<NoteItem> {
+    // osascript ends its output with a newline
+    let output = output.strip_suffix('\n').unwrap_or(output);
+    output
+        .split_terminator(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let fields: Vec<String> = record.split(FIELD_SEPARATOR).map(unescape_field).collect();
+            let [id, title, folder, created, modified, body] = fields.as_slice() else {
+                debug!("Skipping unreadable note record: {:?}", record);
+                return None;
+            };
+            Some(NoteItem {
+                id: Some(id.clone()).filter(|id| !id.is_empty()),
+                title: title.clone(),
+                folder: folder.clone(),
+                created: Some(created.clone()).filter(|d| !d.is_empty()),
+                modified: Some(modified.clone()).filter(|d| !d.is_empty()),
+                content: Some(html_to_markdown(body)),
+            })
+        })
+        .collect()
+}
 
-    // Split by record boundaries
-    let records: Vec<&str> = output.split("}, {").collect();
-
-    for record in records {
-        let clean_record = record.replace(['{', '}'], "");
-        let mut title = String::new();
-        let mut folder = String::new();
-
-        // Extract properties
-        for prop in clean_record.split(", ") {
-            if prop.starts_with("name:") {
-                title = prop.trim_start_matches("name:").trim_matches('"').to_string();
-            } else if prop.starts_with("folder:") {
-                folder = prop.trim_start_matches("folder:").trim_matches('"').to_string();
-            }
+/// Undo the escaping applied to a field by `escapeField`
+fn unescape_field(field: &str) -> String {
+    let mut value = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
         }
-
-        if !title.is_empty() {
-            notes.push((title, folder));
+        match chars.next() {
+            Some('u') => value.push(FIELD_SEPARATOR),
+            Some('r') => value.push(RECORD_SEPARATOR),
+            Some(other) => value.push(other),
+            None => value.push('\\'),
         }
     }
+    value
+}
 
-    notes
+/// Parse the output of the script that reads a single note
+///
+/// The fields are the note's ID, folder, title and HTML body, separated by the
+/// unit separator character. The body is last so it may contain anything, and
+/// is returned as Markdown.
+pub fn parse_note_content(output: &str) -> Result<NoteContent, NotesError> {
+    // osascript ends its output with a newline
+    let output = output.strip_suffix('\n').unwrap_or(output);
+    let mut fields = output.splitn(4, FIELD_SEPARATOR);
+    match (fields.next(), fields.next(), fields.next(), fields.next()) {
+        (Some(id), Some(folder), Some(title), Some(content)) if !id.is_empty() => Ok(NoteContent {
+            id: id.to_string(),
+            title: title.to_string(),
+            folder: folder.to_string(),
+            content: html_to_markdown(content),
+        }),
+        _ => Err(NotesError::ParseError(output.to_string())),
+    }
+}
+
+/// Let the user edit `text` in `$VISUAL` or `$EDITOR` (falling back to vi)
+pub fn edit_in_editor(text: &str) -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let path = std::env::temp_dir().join(format!("ducktape-note-{}.txt", uuid::Uuid::new_v4()));
+    std::fs::write(&path, text)
+        .with_context(|| format!("Failed to write temporary file {:?}", path))?;
+
+    // Run through the shell so editors given with arguments, like "code --wait", work
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(&path)
+        .status();
+    let edited = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+
+    let status = status.with_context(|| format!("Failed to start editor '{}'", editor))?;
+    if !status.success() {
+        return Err(anyhow!("Editor '{}' exited with {}", editor, status));
+    }
+    edited.with_context(|| format!("Failed to read temporary file {:?}", path))
 }
 
 #[allow(dead_code)]
@@ -70,12 +139,40 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_notes_list() {
-        let input =
-            "{name:\"Note 1\", folder:\"Folder 1\"}, {name:\"Note 2\", folder:\"Folder 2\"}";
-        let notes = parse_notes_list(input);
+    fn test_parse_note_content() {
+        let note = parse_note_content(
+            "x-coredata://1/ICNote/p1\u{1f}Work\u{1f}Meeting log\u{1f}\
+             <div>Meeting log</div>\n<div>Agreed, ship it\u{1f}</div>\n",
+        )
+        .unwrap();
+        assert_eq!(note.id, "x-coredata://1/ICNote/p1");
+        assert_eq!(note.folder, "Work");
+        assert_eq!(note.title, "Meeting log");
+        assert_eq!(note.content, "Meeting log\nAgreed, ship it\u{1f}");
+
+        assert!(parse_note_content("Error: Note not found\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_note_records() {
+        let output = "x-coredata://1/ICNote/p7\u{1f}Plan\u{1f}Work\u{1f}2024-06-03T09:05:00\u{1f}\
+                      2024-06-04T17:30:12\u{1f}\
+                      <div><h1>Plan</h1></div><div>Ship <b>v2</b>, then rest</div>\u{1e}\
+                      \u{1f}Odd \\u title\\\\\u{1f}Notes\u{1f}\u{1f}\u{1f}\u{1e}\n";
+        let notes = parse_note_records(output);
         assert_eq!(notes.len(), 2);
-        assert_eq!(notes[0], ("Note 1".to_string(), "Folder 1".to_string()));
-        assert_eq!(notes[1], ("Note 2".to_string(), "Folder 2".to_string()));
+        assert_eq!(notes[0].id.as_deref(), Some("x-coredata://1/ICNote/p7"));
+        assert_eq!(notes[0].title, "Plan");
+        assert_eq!(notes[0].folder, "Work");
+        assert_eq!(notes[0].created.as_deref(), Some("2024-06-03T09:05:00"));
+        assert_eq!(notes[0].modified.as_deref(), Some("2024-06-04T17:30:12"));
+        assert_eq!(notes[0].content.as_deref(), Some("# Plan\nShip **v2**, then rest"));
+        assert_eq!(notes[1].title, "Odd \u{1f} title\\");
+        assert_eq!(notes[1].id, None);
+        assert_eq!(notes[1].created, None);
+        assert_eq!(notes[1].content.as_deref(), Some(""));
+
+        assert!(parse_note_records("").is_empty());
+        assert!(parse_note_records("Plan\u{1f}Work\u{1e}").is_empty());
     }
 }
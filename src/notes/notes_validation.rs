@@ -1,6 +1,6 @@
 //! Validation functions for notes operations.
 
-use crate::notes::notes_types::NoteConfig;
+use crate::notes::notes_types::{NoteConfig, NoteUpdate};
 use anyhow::{Result, anyhow};
 use log::debug;
 
@@ -36,6 +36,23 @@ pub fn validate_note_config(config: &NoteConfig) -> Result<()> {
     Ok(())
 }
 
+/// Validates changes to an existing note
+pub fn validate_note_update(update: &NoteUpdate) -> Result<()> {
+    if let Some(title) = update.title {
+        validate_note_title(title)?;
+    }
+
+    if update.append && update.content.trim().is_empty() {
+        return Err(anyhow!("Nothing to append to the note"));
+    }
+
+    if update.content.len() > 1_000_000 {
+        return Err(anyhow!("Note content is too large (max 1MB)"));
+    }
+
+    Ok(())
+}
+
 /// Validates a note title before performing operations
 pub fn validate_note_title(title: &str) -> Result<()> {
     if title.is_empty() {
@@ -107,6 +124,14 @@ mod tests {
         assert!(validate_note_title("").is_err());
     }
 
+    #[test]
+    fn test_validate_note_update() {
+        assert!(validate_note_update(&NoteUpdate::append("- Decided to ship")).is_ok());
+        assert!(validate_note_update(&NoteUpdate::append("  \n")).is_err());
+        assert!(validate_note_update(&NoteUpdate::replace("")).is_ok());
+        assert!(validate_note_update(&NoteUpdate::replace("Body").with_title("")).is_err());
+    }
+
     #[test]
     fn test_validate_folder_name() {
         assert!(validate_folder_name("Valid Folder").is_ok());
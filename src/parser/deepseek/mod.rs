@@ -2,43 +2,106 @@
 //!
 //! This module provides natural language processing capabilities
 //! using the DeepSeek API for parsing user input into structured commands.
+//!
+//! Requests go to `DEEPSEEK_API_BASE` (default `https://api.deepseek.com`)
+//! with the model in `DEEPSEEK_MODEL` (default `deepseek-chat`), so the parser
+//! can be pointed at a local mock server.
 
+use crate::parser::natural_language::chat::{self, ChatClient};
+use crate::parser::natural_language::context::ConversationContext;
 use crate::parser::traits::{ParseResult, Parser};
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use log::debug;
+use std::env;
+
+const DEFAULT_API_BASE: &str = "https://api.deepseek.com";
+const DEFAULT_MODEL: &str = "deepseek-chat";
 
 /// Parser that uses DeepSeek models for natural language understanding
-pub struct DeepSeekParser;
+pub struct DeepSeekParser {
+    client: ChatClient,
+}
 
 impl DeepSeekParser {
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        let api_key = env::var("DEEPSEEK_API_KEY").map_err(|_| {
+            anyhow!(
+                "DEEPSEEK_API_KEY environment variable not set. Please set your DeepSeek API key using: export DEEPSEEK_API_KEY='your-key-here'"
+            )
+        })?;
+        let base_url = env::var("DEEPSEEK_API_BASE").unwrap_or_else(|_| DEFAULT_API_BASE.into());
+        Self::with_base_url(&base_url, &api_key)
+    }
+
+    /// Create a parser that sends its requests to `base_url`
+    pub fn with_base_url(base_url: &str, api_key: &str) -> Result<Self> {
+        let model = env::var("DEEPSEEK_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.into());
+        let client =
+            ChatClient::new(base_url, &model, Some(api_key.to_string()), chat::DEFAULT_TIMEOUT)?;
+        Ok(Self { client })
     }
 }
 
 #[async_trait]
 impl Parser for DeepSeekParser {
     async fn parse_input(&self, input: &str) -> Result<ParseResult> {
-        // Note: Previously this used OpenAI parser as fallback, but we've removed that dependency
-        debug!("DeepSeek parser: Processing input: {}", input);
+        self.parse_input_with_context(input, &ConversationContext::new()).await
+    }
 
-        // Basic implementation that prefixes the input with "ducktape"
-        // This should be replaced with an actual implementation using the DeepSeek API
-        let command = if input.trim().starts_with("ducktape") {
-            input.trim().to_string()
-        } else {
-            format!("ducktape {}", input.trim())
-        };
+    async fn parse_input_with_context(
+        &self,
+        input: &str,
+        context: &ConversationContext,
+    ) -> Result<ParseResult> {
+        debug!("DeepSeek parser: Processing input: {}", input);
 
-        // Use our new utility function to sanitize the command
-        let sanitized = crate::parser::utils::sanitize_nlp_command(&command);
-        debug!("DeepSeek parser: Generated command: {}", sanitized);
+        let intent = self.client.request_intent(input, context).await?;
+        debug!("DeepSeek parser: Intent: {:?}", intent);
 
-        Ok(ParseResult::CommandString(sanitized))
+        Ok(ParseResult::StructuredCommand(intent.to_command_args()))
     }
 
     fn new() -> Result<Self> {
-        Ok(Self)
+        DeepSeekParser::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+    use super::*;
+    use crate::parser::natural_language::chat::mock;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_parse_input_with_mock_server() -> Result<()> {
+        let reply = r#"{"action": "create_event", "title": "Budget review", "date": "2025-06-03",
+            "start": "15:00", "end": "16:00", "attendees": ["Priya Patel"], "zoom": true}"#;
+        let (base_url, requests) = mock::serve(StatusCode::OK, reply).await;
+        let parser = DeepSeekParser::with_base_url(&base_url, "test-key")?;
+
+        let input = "zoom budget review with Priya Patel on June 3 at 3pm";
+        let ParseResult::StructuredCommand(args) = parser.parse_input(input).await? else {
+            panic!("Expected StructuredCommand parse result");
+        };
+        assert_eq!(args.command, "calendar");
+        assert_eq!(args.args, ["create", "Budget review", "2025-06-03", "15:00", "16:00"]);
+        assert!(args.flags.contains_key("zoom"));
+        assert_eq!(args.flags["contacts"].as_deref(), Some("Priya Patel"));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].0.as_deref(), Some("Bearer test-key"));
+        assert_eq!(requests[0].1["messages"][1]["content"], input);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parse_input_rejects_non_intents() -> Result<()> {
+        let (base_url, _) = mock::serve(StatusCode::OK, "ducktape calendar list").await;
+        let parser = DeepSeekParser::with_base_url(&base_url, "test-key")?;
+
+        assert!(parser.parse_input("show my calendar").await.is_err());
+        Ok(())
     }
 }
@@ -2,36 +2,48 @@
 //!
 //! This module provides natural language processing capabilities
 //! using the Grok/X.AI API for parsing user input into structured commands.
+//!
+//! Requests go to `XAI_API_BASE` (default `https://api.x.ai/v1`) with the
+//! model in `XAI_MODEL` (default `grok-2-latest`), so the parser can be
+//! pointed at a local mock server.
 
+use crate::parser::natural_language::chat::{self, ChatClient};
+use crate::parser::natural_language::context::ConversationContext;
 use crate::parser::traits::{ParseResult, Parser};
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
-use log::{debug, error};
-use regex::Regex;
+use log::debug;
 use std::env;
 
+const DEFAULT_API_BASE: &str = "https://api.x.ai/v1";
+const DEFAULT_MODEL: &str = "grok-2-latest";
+
 /// Parser that uses Grok/X.AI models for natural language understanding
-pub struct GrokParser;
+pub struct GrokParser {
+    client: ChatClient,
+}
 
 impl GrokParser {
     /// Create a new GrokParser instance
     pub fn new() -> Result<Self> {
-        // Check for XAI_API_KEY upfront to avoid misleading errors
-        check_xai_api_key()?;
-        Ok(Self)
+        let api_key = check_xai_api_key()?;
+        let base_url = env::var("XAI_API_BASE").unwrap_or_else(|_| DEFAULT_API_BASE.into());
+        Self::with_base_url(&base_url, &api_key)
     }
 
-    #[allow(dead_code)]
-    /// Check for the required XAI_API_KEY environment variable
-    fn check_env_vars() -> Result<()> {
-        check_xai_api_key()
+    /// Create a parser that sends its requests to `base_url`
+    pub fn with_base_url(base_url: &str, api_key: &str) -> Result<Self> {
+        let model = env::var("XAI_MODEL").unwrap_or_else(|_| DEFAULT_MODEL.into());
+        let client =
+            ChatClient::new(base_url, &model, Some(api_key.to_string()), chat::DEFAULT_TIMEOUT)?;
+        Ok(Self { client })
     }
 }
 
-/// Helper function to check for XAI_API_KEY environment variable
-fn check_xai_api_key() -> Result<()> {
+/// Helper function to read the XAI_API_KEY environment variable
+fn check_xai_api_key() -> Result<String> {
     match env::var("XAI_API_KEY") {
-        Ok(_) => Ok(()),
+        Ok(key) => Ok(key),
         Err(_) => Err(anyhow!(
             "XAI_API_KEY environment variable not set. Please set your X.AI API key using: export XAI_API_KEY='your-key-here'"
         )),
@@ -41,77 +53,72 @@ fn check_xai_api_key() -> Result<()> {
 #[async_trait]
 impl Parser for GrokParser {
     async fn parse_input(&self, input: &str) -> Result<ParseResult> {
-        debug!("Grok parser: Processing input: {}", input);
-
-        // Check that XAI_API_KEY is set
-        let api_key = match env::var("XAI_API_KEY") {
-            Ok(key) => key,
-            Err(_) => {
-                error!("XAI_API_KEY environment variable not set");
-                return Err(anyhow!("XAI_API_KEY environment variable not set"));
-            }
-        };
-
-        // TODO: Implement full Grok/X.AI API integration
-        // For now, provide a basic implementation that returns the input as a command string
-        debug!("Using XAI_API_KEY with length: {}", api_key.len());
-
-        // Basic sanitization of the input
-        let command = sanitize_nlp_command(input);
-
-        // Return the sanitized input as a command string
-        Ok(ParseResult::CommandString(command))
+        self.parse_input_with_context(input, &ConversationContext::new()).await
     }
 
-    fn new() -> Result<Self> {
-        Ok(Self)
-    }
-}
+    async fn parse_input_with_context(
+        &self,
+        input: &str,
+        context: &ConversationContext,
+    ) -> Result<ParseResult> {
+        debug!("Grok parser: Processing input: {}", input);
 
-/// Basic sanitization of natural language input keeping time information
-fn sanitize_nlp_command(command: &str) -> String {
-    // Extract time information if present using regex
-    let time_regex = Regex::new(r"(\d{1,2}(?::\d{2})?(?:\s*[ap]m)?)").unwrap();
-    let mut time_info = None;
+        let intent = self.client.request_intent(input, context).await?;
+        debug!("Grok parser: Intent: {:?}", intent);
 
-    if command.contains("calendar create") {
-        if let Some(captures) = time_regex.captures(command) {
-            time_info = captures.get(1).map(|m| m.as_str().to_string());
-        }
+        Ok(ParseResult::StructuredCommand(intent.to_command_args()))
     }
 
-    // Clean up the command
-    let sanitized = command
-        .trim()
-        .replace(';', " ")
-        .replace('&', " and ")
-        .replace(['|', '>', '<', '`'], " ");
-
-    // If we found time information, make sure it's preserved in the output
-    if let Some(time) = time_info {
-        if !sanitized.contains(&time) {
-            return format!("{} at {}", sanitized, time);
-        }
+    fn new() -> Result<Self> {
+        GrokParser::new()
     }
-
-    debug!("Sanitized command: {}", sanitized);
-    sanitized
 }
 
 #[cfg(test)]
+#[allow(deprecated)]
 mod tests {
     use super::*;
+    use crate::command_processor::CommandArgs;
+    use crate::parser::natural_language::chat::mock;
+    use axum::http::StatusCode;
+    use std::collections::HashMap;
     use std::env;
 
-    #[test]
-    fn test_sanitize_command() {
-        let input = "create meeting; rm -rf /";
-        let sanitized = sanitize_nlp_command(input);
-        assert_eq!(sanitized, "create meeting  rm -rf /");
-
-        let input = "schedule meeting & delete files";
-        let sanitized = sanitize_nlp_command(input);
-        assert_eq!(sanitized, "schedule meeting  and  delete files");
+    #[tokio::test]
+    async fn test_parse_input_rejects_non_intents() -> Result<()> {
+        let (base_url, _) = mock::serve(StatusCode::OK, "ducktape calendar list").await;
+        let parser = GrokParser::with_base_url(&base_url, "test-key")?;
+
+        assert!(parser.parse_input("show my calendar").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parse_input_with_context() -> Result<()> {
+        let reply = r#"{"action": "update_event", "title": "Lunch", "date": "2025-06-03", "start": "16:00"}"#;
+        let (base_url, requests) = mock::serve(StatusCode::OK, reply).await;
+        let parser = GrokParser::with_base_url(&base_url, "test-key")?;
+
+        let mut context = ConversationContext::new();
+        let args = ["create", "Lunch", "2025-06-03", "12:00", "13:00"];
+        let create = CommandArgs::new(
+            "calendar".to_string(),
+            args.iter().map(|arg| arg.to_string()).collect(),
+            HashMap::new(),
+        );
+        context.record("lunch on June 3 at noon", &create);
+
+        let ParseResult::StructuredCommand(args) =
+            parser.parse_input_with_context("move it to 4pm", &context).await?
+        else {
+            panic!("Expected a structured command");
+        };
+        assert_eq!(args.args, ["update", "Lunch", "2025-06-03"]);
+
+        let requests = requests.lock().unwrap();
+        let system_prompt = requests[0].1["messages"][0]["content"].as_str().unwrap();
+        assert!(system_prompt.contains("Last event: \"Lunch\" on 2025-06-03 12:00-13:00"));
+        Ok(())
     }
 
     #[test]
@@ -2,6 +2,7 @@ pub mod command;
 pub mod deepseek;
 pub mod grok;
 pub mod natural_language;
+pub mod openai_compatible;
 pub mod terminal;
 /// DuckTape Parser module
 ///
@@ -34,6 +35,11 @@ impl ParserFactory {
                 let parser = deepseek::DeepSeekParser::new()?;
                 Ok(Box::new(parser))
             }
+            Some(LLMProvider::OpenAICompatible) => {
+                info!("Creating OpenAI-compatible parser");
+                let parser = openai_compatible::OpenAICompatibleParser::new()?;
+                Ok(Box::new(parser))
+            }
             None => {
                 info!("Creating Terminal parser");
                 terminal::create_terminal_parser()
@@ -46,7 +52,7 @@ impl ParserFactory {
 pub fn sanitize_nlp_command(command: &str) -> String {
     if let Some(provider) = Config::load().ok().and_then(|c| c.language_model.provider) {
         match provider {
-            LLMProvider::Grok => {
+            LLMProvider::Grok | LLMProvider::DeepSeek | LLMProvider::OpenAICompatible => {
                 debug!("Using Grok command sanitizer");
                 // First apply command mapping to normalize "create" to "calendar create", etc.
                 let normalized = natural_language::command_mapping::normalize_command(command);
@@ -59,18 +65,6 @@ pub fn sanitize_nlp_command(command: &str) -> String {
                     crate::parser::natural_language::grok::utils::sanitize_nlp_command(command)
                 }
             }
-            LLMProvider::DeepSeek => {
-                debug!("Using DeepSeek command sanitizer");
-                // Apply command mapping for DeepSeek also for consistency
-                let normalized = natural_language::command_mapping::normalize_command(command);
-                if normalized != command {
-                    debug!("Normalized command: '{}'", normalized);
-                    format!("ducktape {}", normalized)
-                } else {
-                    // Use the DeepSeek-specific sanitizer when available
-                    format!("ducktape {}", natural_language::utils::sanitize_user_input(command))
-                }
-            }
         }
     } else {
         // Default sanitizer for when no specific provider is set
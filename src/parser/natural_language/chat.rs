@@ -0,0 +1,207 @@
+//! Chat-completions client for LLM-backed parsers
+//!
+//! The model is given a system prompt with the intent JSON Schema and must
+//! reply with a JSON object, which is validated and deserialized into an
+//! [`Intent`](super::intent::Intent).
+
+use crate::parser::natural_language::context::ConversationContext;
+use crate::parser::natural_language::intent::{self, Intent};
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use log::debug;
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Default time to wait for a model to reply
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum size of a model response (1MB)
+const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+
+/// Client for an OpenAI-style `/chat/completions` endpoint
+#[derive(Debug, Clone)]
+pub struct ChatClient {
+    client: Client,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+impl ChatClient {
+    /// Create a client for `base_url`, e.g. `https://api.deepseek.com` or `http://localhost:11434/v1`
+    pub fn new(
+        base_url: &str,
+        model: &str,
+        api_key: Option<String>,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let client = Client::builder().timeout(timeout).build()?;
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key,
+        })
+    }
+
+    /// Ask the model what `input` means, given what the session did before
+    pub async fn request_intent(
+        &self,
+        input: &str,
+        context: &ConversationContext,
+    ) -> Result<Intent> {
+        let mut system_prompt = intent::system_prompt(Local::now());
+        if !context.is_empty() {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&context.prompt_section());
+        }
+        let reply = self.complete(&system_prompt, input.trim()).await?;
+        intent::parse_intent(&reply)
+    }
+
+    /// Send a system prompt and user message, returning the model's JSON reply
+    pub async fn complete(&self, system_prompt: &str, input: &str) -> Result<String> {
+        let url = format!("{}/chat/completions", self.base_url);
+        debug!("Sending chat completion request to {} with model {}", url, self.model);
+
+        let mut request = self.client.post(&url).json(&json!({
+            "model": self.model,
+            "messages": [
+                { "role": "system", "content": system_prompt },
+                { "role": "user", "content": input }
+            ],
+            "temperature": 0.0,
+            "max_tokens": 512,
+            "response_format": { "type": "json_object" },
+            "stream": false
+        }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach the language model at {}: {}", url, e))?;
+        let status = response.status();
+        let response_text = response.text().await?;
+
+        // Limit response size to prevent DoS attacks
+        if response_text.len() > MAX_RESPONSE_SIZE {
+            return Err(anyhow!("Response size exceeds security limits"));
+        }
+        if !status.is_success() {
+            return Err(anyhow!(
+                "Language model API error: Status {}, Response: {}",
+                status,
+                response_text
+            ));
+        }
+
+        let response_json: Value = serde_json::from_str(&response_text)
+            .map_err(|e| anyhow!("Failed to parse language model response: {}", e))?;
+        let content = response_json["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Invalid response format: no message content"))?;
+        debug!("Language model replied: {}", content);
+        Ok(content.to_string())
+    }
+}
+
+/// A local `/chat/completions` endpoint that always gives the same reply
+#[cfg(test)]
+pub(crate) mod mock {
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{Value, json};
+    use std::sync::{Arc, Mutex};
+
+    /// Requests received by the mock server, with their Authorization header
+    pub type Requests = Arc<Mutex<Vec<(Option<String>, Value)>>>;
+
+    /// Serve `reply` with `status`, returning the base URL and the requests received
+    pub async fn serve(status: StatusCode, reply: &str) -> (String, Requests) {
+        let requests: Requests = Arc::default();
+        let state = (requests.clone(), status, reply.to_string());
+        let app = Router::new().route("/v1/chat/completions", post(complete)).with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (format!("http://{}/v1", address), requests)
+    }
+
+    async fn complete(
+        State((requests, status, reply)): State<(Requests, StatusCode, String)>,
+        headers: HeaderMap,
+        Json(body): Json<Value>,
+    ) -> (StatusCode, Json<Value>) {
+        let authorization =
+            headers.get("authorization").and_then(|v| v.to_str().ok()).map(String::from);
+        requests.lock().unwrap().push((authorization, body));
+        (
+            status,
+            Json(json!({ "choices": [{ "message": { "role": "assistant", "content": reply } }] })),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_client_sends_prompt_and_key() -> Result<()> {
+        let (base_url, requests) = mock::serve(StatusCode::OK, r#"{"action": "list_notes"}"#).await;
+        let client =
+            ChatClient::new(&base_url, "test-model", Some("secret".into()), DEFAULT_TIMEOUT)?;
+
+        let intent = client.request_intent("show my notes", &ConversationContext::new()).await?;
+        assert_eq!(intent, Intent::ListNotes { folder: None });
+
+        let requests = requests.lock().unwrap();
+        let (authorization, body) = &requests[0];
+        assert_eq!(authorization.as_deref(), Some("Bearer secret"));
+        assert_eq!(body["model"], "test-model");
+        assert_eq!(body["response_format"]["type"], "json_object");
+        assert!(body["messages"][0]["content"].as_str().unwrap().contains("\"create_event\""));
+        assert_eq!(body["messages"][1]["content"], "show my notes");
+        assert!(!body["messages"][0]["content"].as_str().unwrap().contains("Conversation so far"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_client_sends_conversation_context() -> Result<()> {
+        let reply = r#"{"action": "update_event", "title": "Lunch", "date": "2025-06-03", "start": "16:00"}"#;
+        let (base_url, requests) = mock::serve(StatusCode::OK, reply).await;
+        let client = ChatClient::new(&base_url, "test-model", None, DEFAULT_TIMEOUT)?;
+
+        let mut context = ConversationContext::new();
+        let create = intent::parse_intent(
+            r#"{"action": "create_event", "title": "Lunch", "date": "2025-06-03", "start": "12:00", "end": "13:00"}"#,
+        )?;
+        context.record("lunch on June 3 at noon", &create.to_command_args());
+
+        let intent = client.request_intent("move it to 4pm", &context).await?;
+        assert!(matches!(intent, Intent::UpdateEvent(update) if update.title == "Lunch"));
+
+        let requests = requests.lock().unwrap();
+        let system_prompt = requests[0].1["messages"][0]["content"].as_str().unwrap();
+        assert!(system_prompt.contains("Last event: \"Lunch\" on 2025-06-03 12:00-13:00"));
+        assert_eq!(requests[0].1["messages"][1]["content"], "move it to 4pm");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_client_reports_api_errors() -> Result<()> {
+        let (base_url, _) = mock::serve(StatusCode::UNAUTHORIZED, "").await;
+        let client = ChatClient::new(&base_url, "test-model", None, DEFAULT_TIMEOUT)?;
+
+        let error = client.complete("system text", "show my notes").await.unwrap_err();
+        assert!(error.to_string().contains("401"));
+        Ok(())
+    }
+}
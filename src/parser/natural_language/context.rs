@@ -0,0 +1,266 @@
+//! Conversation context for follow-up requests
+//!
+//! Every REPL session and WebSocket connection keeps a [`ConversationContext`]
+//! with its latest requests and the event, reminder and note they last
+//! created or changed. Parsers backed by a language model add it to the
+//! prompt, so "move it to 4pm" or "also invite Sarah" can be resolved.
+
+// Commands are recorded from the CommandArgs that ran, which is deprecated in
+// favour of the parser module but still drives the CLI.
+#![allow(deprecated)]
+
+use crate::command_processor::CommandArgs;
+use chrono::NaiveTime;
+use std::collections::VecDeque;
+
+/// Number of earlier requests kept
+const MAX_TURNS: usize = 5;
+
+/// What a session has done so far
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConversationContext {
+    turns: VecDeque<Turn>,
+    last_event: Option<EventRef>,
+    last_reminder: Option<ItemRef>,
+    last_note: Option<ItemRef>,
+}
+
+/// A request and the command it ran
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turn {
+    pub input: String,
+    pub command: String,
+}
+
+/// The event a session last created or changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRef {
+    pub title: String,
+    /// YYYY-MM-DD
+    pub date: String,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub calendar: Option<String>,
+}
+
+/// A reminder or note, with its list or folder
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemRef {
+    pub title: String,
+    pub container: Option<String>,
+}
+
+impl ConversationContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn turns(&self) -> impl Iterator<Item = &Turn> {
+        self.turns.iter()
+    }
+
+    pub fn last_event(&self) -> Option<&EventRef> {
+        self.last_event.as_ref()
+    }
+
+    pub fn last_reminder(&self) -> Option<&ItemRef> {
+        self.last_reminder.as_ref()
+    }
+
+    pub fn last_note(&self) -> Option<&ItemRef> {
+        self.last_note.as_ref()
+    }
+
+    /// Remember that `input` ran the command in `args`
+    pub fn record(&mut self, input: &str, args: &CommandArgs) {
+        let title = args.args.get(1).map(|title| title.trim_matches('"').to_string());
+        match (args.command.as_str(), args.args.first().map(String::as_str), title) {
+            ("calendar", Some("create"), Some(title)) => {
+                self.last_event = Some(EventRef {
+                    title,
+                    date: resolve_date(args.args.get(2).map_or("today", String::as_str)),
+                    start: args.args.get(3).cloned(),
+                    end: args.args.get(4).cloned(),
+                    calendar: args.args.get(5).cloned(),
+                });
+            }
+            ("calendar", Some("update"), Some(title)) => self.record_update(title, args),
+            ("calendar", Some("delete"), Some(title))
+                if self
+                    .last_event
+                    .as_ref()
+                    .is_some_and(|event| event.title.eq_ignore_ascii_case(&title)) =>
+            {
+                self.last_event = None;
+            }
+            ("reminder", Some("create" | "complete" | "uncomplete"), Some(title)) => {
+                self.last_reminder = Some(ItemRef { title, container: args.args.get(2).cloned() });
+            }
+            ("note", Some("create" | "append" | "edit"), Some(title)) => {
+                let folder = args.flags.get("folder").cloned().flatten();
+                self.last_note = Some(ItemRef { title, container: folder });
+            }
+            _ => {}
+        }
+
+        self.turns.push_back(Turn {
+            input: input.trim().to_string(),
+            command: crate::preview::command_line(args),
+        });
+        while self.turns.len() > MAX_TURNS {
+            self.turns.pop_front();
+        }
+    }
+
+    // Move the last event along with an update, or start tracking the
+    // updated event when it is a different one
+    fn record_update(&mut self, title: String, args: &CommandArgs) {
+        let flag = |name: &str| args.flags.get(name).cloned().flatten();
+        let mut event = match self.last_event.take() {
+            Some(event) if event.title.eq_ignore_ascii_case(&title) => event,
+            // Without a date the title is an event UID we know nothing about
+            _ if args.args.len() < 3 => return,
+            _ => EventRef {
+                title,
+                date: resolve_date(&args.args[2]),
+                start: None,
+                end: None,
+                calendar: None,
+            },
+        };
+
+        if let Some(date) = flag("date") {
+            event.date = resolve_date(&date);
+        }
+        if let Some(start) = flag("start") {
+            // The event keeps its length unless a new end is given
+            let length = event
+                .start
+                .as_deref()
+                .and_then(parse_time)
+                .zip(event.end.as_deref().and_then(parse_time))
+                .map(|(old_start, old_end)| old_end - old_start);
+            event.end = length
+                .zip(parse_time(&start))
+                .map(|(length, start)| (start + length).format("%H:%M").to_string());
+            event.start = Some(start);
+        }
+        if let Some(end) = flag("end") {
+            event.end = Some(end);
+        }
+        self.last_event = Some(event);
+    }
+
+    /// A description of the context for a language model prompt, or an empty
+    /// string when nothing has happened yet
+    pub fn prompt_section(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut lines = vec!["Conversation so far, oldest first:".to_string()];
+        for turn in &self.turns {
+            lines.push(format!("- {:?} ran: {}", turn.input, turn.command));
+        }
+        if let Some(event) = &self.last_event {
+            let mut line = format!("Last event: {:?} on {}", event.title, event.date);
+            if let Some(start) = &event.start {
+                line.push_str(&format!(" {}-{}", start, event.end.as_deref().unwrap_or("?")));
+            }
+            if let Some(calendar) = &event.calendar {
+                line.push_str(&format!(" in calendar {:?}", calendar));
+            }
+            lines.push(line);
+        }
+        for (kind, place, item) in
+            [("reminder", "list", &self.last_reminder), ("note", "folder", &self.last_note)]
+        {
+            if let Some(item) = item {
+                let mut line = format!("Last {}: {:?}", kind, item.title);
+                if let Some(container) = &item.container {
+                    line.push_str(&format!(" in {} {:?}", place, container));
+                }
+                lines.push(line);
+            }
+        }
+        lines.push(
+            "Words like \"it\", \"that\" or \"the meeting\" in the request refer to these."
+                .to_string(),
+        );
+        lines.join("\n")
+    }
+}
+
+fn resolve_date(date: &str) -> String {
+    crate::calendar::parse_date_argument(date.trim_matches('"'))
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn command(command: &str, args: &[&str], flags: &[(&str, &str)]) -> CommandArgs {
+        let flags = flags.iter().map(|(k, v)| (k.to_string(), Some(v.to_string()))).collect();
+        CommandArgs::new(command.to_string(), args.iter().map(|a| a.to_string()).collect(), flags)
+    }
+
+    #[test]
+    fn test_follow_up_moves_last_event() {
+        let mut context = ConversationContext::new();
+        assert!(context.is_empty() && context.prompt_section().is_empty());
+
+        let create = command("calendar", &["create", "Lunch", "2025-06-03", "12:00", "13:00"], &[]);
+        context.record("lunch with Priya on June 3 at noon", &create);
+        let update = command("calendar", &["update", "lunch", "2025-06-03"], &[("start", "16:00")]);
+        context.record("move it to 4pm", &update);
+
+        let event = context.last_event().unwrap();
+        assert_eq!(event.title, "Lunch");
+        assert_eq!((event.start.as_deref(), event.end.as_deref()), (Some("16:00"), Some("17:00")));
+
+        let prompt = context.prompt_section();
+        assert!(prompt.contains("Last event: \"Lunch\" on 2025-06-03 16:00-17:00"));
+        assert!(prompt.contains(
+            "\"move it to 4pm\" ran: ducktape calendar update lunch 2025-06-03 --start 16:00"
+        ));
+
+        context.record("cancel it", &command("calendar", &["delete", "Lunch", "2025-06-03"], &[]));
+        assert!(context.last_event().is_none());
+    }
+
+    #[test]
+    fn test_reminders_notes_and_turn_limit() {
+        let mut context = ConversationContext::new();
+        context.record("remind me", &command("reminder", &["create", "Call Jane", "Home"], &[]));
+        context.record(
+            "note it",
+            &CommandArgs::new(
+                "note".to_string(),
+                vec!["create".to_string(), "Ideas".to_string()],
+                HashMap::from([("folder".to_string(), Some("Work".to_string()))]),
+            ),
+        );
+        for _ in 0..MAX_TURNS {
+            context.record("what's on", &command("calendar", &["list"], &[]));
+        }
+
+        assert_eq!(context.last_reminder().unwrap().container.as_deref(), Some("Home"));
+        assert_eq!(context.last_note().unwrap().title, "Ideas");
+        assert_eq!(context.turns().count(), MAX_TURNS);
+        let prompt = context.prompt_section();
+        assert!(prompt.contains("Last reminder: \"Call Jane\" in list \"Home\""));
+        assert!(prompt.contains("Last note: \"Ideas\" in folder \"Work\""));
+        assert!(!prompt.contains("remind me"));
+    }
+}
@@ -0,0 +1,638 @@
+//! Structured intents returned by LLM-backed parsers
+//!
+//! Instead of a `ducktape ...` command line, the model replies with a JSON
+//! object such as `{"action": "create_event", "title": "Design review", ...}`.
+//! The reply is checked against [`INTENT_SCHEMA`], deserialized into an
+//! [`Intent`] and turned into the same `CommandArgs` the CLI produces, so no
+//! quoting, re-tokenizing or regex repair is needed on the way.
+
+// CommandArgs is deprecated in favour of the parser module but is still what
+// CommandProcessor runs, so intents are converted to it.
+#![allow(deprecated)]
+
+use crate::calendar::validate_email;
+use crate::command_processor::CommandArgs;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// JSON Schema every model reply must match
+pub static INTENT_SCHEMA: Lazy<Value> = Lazy::new(|| {
+    let text = json!({ "type": "string", "minLength": 1, "maxLength": 500 });
+    let date = json!({ "type": "string", "pattern": r"^(\d{4}-\d{2}-\d{2}|today|tomorrow)$" });
+    let time = json!({ "type": "string", "pattern": r"^([01]\d|2[0-3]):[0-5]\d$" });
+    let action = |name: &str, required: &[&str], properties: Value| {
+        let mut properties = properties;
+        properties["action"] = json!({ "const": name });
+        let mut required = required.to_vec();
+        required.insert(0, "action");
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false
+        })
+    };
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "DuckTape intent",
+        "oneOf": [
+            action("create_event", &["title", "date", "start", "end"], json!({
+                "title": text,
+                "date": date,
+                "start": time,
+                "end": time,
+                "timezone": { "type": "string", "minLength": 1, "maxLength": 50 },
+                "calendar": text,
+                "location": text,
+                "notes": { "type": "string", "maxLength": 5000 },
+                "attendees": { "type": "array", "items": text, "maxItems": 50 },
+                "zoom": { "type": "boolean" },
+                "recurrence": {
+                    "type": "object",
+                    "properties": {
+                        "frequency": { "enum": ["daily", "weekly", "monthly", "yearly"] },
+                        "interval": { "type": "integer", "minimum": 1, "maximum": 100 },
+                        "count": { "type": "integer", "minimum": 1, "maximum": 500 },
+                        "until": date,
+                        "days": {
+                            "type": "array",
+                            "items": { "type": "integer", "minimum": 0, "maximum": 6 },
+                            "uniqueItems": true
+                        }
+                    },
+                    "required": ["frequency"],
+                    "additionalProperties": false
+                }
+            })),
+            action("update_event", &["title", "date"], json!({
+                "title": text,
+                "date": date,
+                "new_date": date,
+                "start": time,
+                "end": time,
+                "location": { "type": "string", "maxLength": 500 },
+                "add_attendees": { "type": "array", "items": text, "maxItems": 50 }
+            })),
+            action("delete_event", &["title", "date"], json!({ "title": text, "date": date })),
+            action("list_events", &[], json!({ "from": date, "to": date })),
+            action("create_reminder", &["title"], json!({
+                "title": text,
+                "list": text,
+                "remind_at": {
+                    "type": "string",
+                    "pattern": r"^\d{4}-\d{2}-\d{2} ([01]\d|2[0-3]):[0-5]\d$"
+                },
+                "due": date,
+                "notes": { "type": "string", "maxLength": 5000 }
+            })),
+            action("complete_reminder", &["title"], json!({ "title": text, "list": text })),
+            action("list_reminders", &[], json!({ "list": text })),
+            action("create_note", &["title"], json!({
+                "title": text,
+                "content": { "type": "string", "maxLength": 20000 },
+                "folder": text
+            })),
+            action("search_notes", &["query"], json!({ "query": text, "folder": text })),
+            action("list_notes", &[], json!({ "folder": text }))
+        ]
+    })
+});
+
+static VALIDATOR: Lazy<jsonschema::Validator> =
+    Lazy::new(|| jsonschema::validator_for(&INTENT_SCHEMA).expect("INTENT_SCHEMA is valid"));
+
+/// What the user asked for, as described by the model
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Intent {
+    CreateEvent(EventIntent),
+    UpdateEvent(EventUpdateIntent),
+    DeleteEvent {
+        title: String,
+        date: String,
+    },
+    /// Events from `from` (default today) to `to` (default a week later)
+    ListEvents {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    CreateReminder(ReminderIntent),
+    CompleteReminder {
+        title: String,
+        list: Option<String>,
+    },
+    ListReminders {
+        list: Option<String>,
+    },
+    CreateNote {
+        title: String,
+        content: Option<String>,
+        folder: Option<String>,
+    },
+    SearchNotes {
+        query: String,
+        folder: Option<String>,
+    },
+    ListNotes {
+        folder: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EventIntent {
+    pub title: String,
+    /// YYYY-MM-DD, today or tomorrow
+    pub date: String,
+    /// HH:MM
+    pub start: String,
+    /// HH:MM
+    pub end: String,
+    /// IANA time zone the date and times are in, when not local time
+    pub timezone: Option<String>,
+    pub calendar: Option<String>,
+    pub location: Option<String>,
+    pub notes: Option<String>,
+    /// Contact names and email addresses to invite
+    #[serde(default)]
+    pub attendees: Vec<String>,
+    #[serde(default)]
+    pub zoom: bool,
+    pub recurrence: Option<RecurrenceIntent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EventUpdateIntent {
+    /// Current title of the event
+    pub title: String,
+    /// Current date of the event
+    pub date: String,
+    pub new_date: Option<String>,
+    /// New start time; the event keeps its length unless `end` is given
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// New location; an empty string clears it
+    pub location: Option<String>,
+    /// Contact names and email addresses to invite as well
+    #[serde(default)]
+    pub add_attendees: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RecurrenceIntent {
+    /// daily, weekly, monthly or yearly
+    pub frequency: String,
+    pub interval: Option<u32>,
+    pub count: Option<u32>,
+    /// YYYY-MM-DD
+    pub until: Option<String>,
+    /// Days of the week, 0=Sunday
+    #[serde(default)]
+    pub days: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReminderIntent {
+    pub title: String,
+    pub list: Option<String>,
+    /// YYYY-MM-DD HH:MM
+    pub remind_at: Option<String>,
+    /// YYYY-MM-DD, today or tomorrow
+    pub due: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// System prompt asking the model for an intent matching [`INTENT_SCHEMA`]
+pub fn system_prompt(now: DateTime<Local>) -> String {
+    format!(
+        r#"You turn requests for a calendar, reminders and notes assistant into JSON. The current date and time is {} ({}).
+
+Reply with one JSON object and nothing else. It must match this JSON Schema:
+{}
+
+Write dates as YYYY-MM-DD, today or tomorrow, and times as 24-hour HH:MM. Events last one hour unless the request says otherwise; if it gives no time, start at 09:00. If the request gives the time in another time zone (for example "3pm EST"), keep that time and set timezone to the IANA name, such as America/New_York. Leave out calendar, list and folder unless the request names one. Put the people and email addresses to invite in attendees, and set zoom to true for Zoom, video or online meetings. For recurrence days, 0 is Sunday. To change an event that already exists, such as one from earlier in the conversation, use update_event with its current title and date and only the fields that change."#,
+        now.format("%Y-%m-%d %H:%M"),
+        now.format("%A"),
+        *INTENT_SCHEMA
+    )
+}
+
+/// Validate a model reply against [`INTENT_SCHEMA`] and deserialize it
+pub fn parse_intent(reply: &str) -> Result<Intent> {
+    // Some models wrap JSON in a code block even when asked not to
+    let body = reply.trim();
+    let body = body
+        .strip_prefix("```json")
+        .or_else(|| body.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(body);
+
+    let value: Value = serde_json::from_str(body.trim())
+        .map_err(|e| anyhow!("The language model did not reply with JSON: {}", e))?;
+    let errors: Vec<String> = VALIDATOR
+        .iter_errors(&value)
+        .map(|error| match error.instance_path.as_str() {
+            "" => error.to_string(),
+            path => format!("{}: {}", path, error),
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(anyhow!(
+            "The language model's reply does not match the intent schema: {}",
+            errors.join("; ")
+        ));
+    }
+
+    let intent: Intent = serde_json::from_value(value)
+        .map_err(|e| anyhow!("Failed to read the language model's intent: {}", e))?;
+    intent.check()?;
+    Ok(intent)
+}
+
+impl Intent {
+    /// Checks the schema cannot express, such as real dates and events of some length
+    ///
+    /// An event that ends before it starts runs past midnight, so "10pm to 1am"
+    /// ends at 01:00 the next day, as in `calculate_meeting_duration`.
+    fn check(&self) -> Result<()> {
+        match self {
+            Intent::CreateEvent(event) => {
+                check_date(&event.date)?;
+                let start = NaiveTime::parse_from_str(&event.start, "%H:%M")?;
+                let end = NaiveTime::parse_from_str(&event.end, "%H:%M")?;
+                if end == start {
+                    return Err(anyhow!(
+                        "Event ends at {} when it starts at {}",
+                        event.end,
+                        event.start
+                    ));
+                }
+                if let Some(timezone) = &event.timezone {
+                    timezone
+                        .parse::<Tz>()
+                        .map_err(|_| anyhow!("Unknown time zone: {}", timezone))?;
+                }
+                if let Some(until) = event.recurrence.as_ref().and_then(|r| r.until.as_ref()) {
+                    NaiveDate::parse_from_str(until, "%Y-%m-%d")
+                        .map_err(|_| anyhow!("Invalid recurrence end date: {}", until))?;
+                }
+            }
+            Intent::UpdateEvent(update) => {
+                check_date(&update.date)?;
+                if let Some(new_date) = &update.new_date {
+                    check_date(new_date)?;
+                }
+                if let (Some(start), Some(end)) = (&update.start, &update.end) {
+                    if end == start {
+                        return Err(anyhow!("Event ends at {} when it starts at {}", end, start));
+                    }
+                }
+                if update.new_date.is_none()
+                    && update.start.is_none()
+                    && update.end.is_none()
+                    && update.location.is_none()
+                    && update.add_attendees.is_empty()
+                {
+                    return Err(anyhow!("The event update for '{}' changes nothing", update.title));
+                }
+            }
+            Intent::DeleteEvent { date, .. } => check_date(date)?,
+            Intent::ListEvents { from, to } => {
+                for date in [from, to].into_iter().flatten() {
+                    check_date(date)?;
+                }
+            }
+            Intent::CreateReminder(reminder) => {
+                if let Some(due) = &reminder.due {
+                    check_date(due)?;
+                }
+                if let Some(remind_at) = &reminder.remind_at {
+                    NaiveDateTime::parse_from_str(remind_at, "%Y-%m-%d %H:%M")
+                        .map_err(|_| anyhow!("Invalid reminder time: {}", remind_at))?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The command arguments the CLI would produce for this intent
+    pub fn to_command_args(&self) -> CommandArgs {
+        let mut args = Vec::new();
+        let mut flags = HashMap::new();
+        let mut flag = |name: &str, value: &Option<String>| {
+            if let Some(value) = value {
+                flags.insert(name.to_string(), Some(value.clone()));
+            }
+        };
+
+        let command = match self {
+            Intent::CreateEvent(event) => {
+                let (date, start, end) = event.local_times();
+                args.extend(["create", &event.title, &date, &start, &end].map(String::from));
+                args.extend(event.calendar.clone());
+                flag("timezone", &event.timezone);
+                flag("location", &event.location);
+                flag("notes", &event.notes);
+
+                let (emails, contacts) = split_attendees(&event.attendees);
+                flag("email", &emails);
+                flag("contacts", &contacts);
+                if event.zoom {
+                    flag("zoom", &Some("true".to_string()));
+                }
+
+                if let Some(recurrence) = &event.recurrence {
+                    flag("repeat", &Some(recurrence.frequency.clone()));
+                    flag("interval", &recurrence.interval.map(|i| i.to_string()));
+                    flag("count", &recurrence.count.map(|c| c.to_string()));
+                    flag("until", &recurrence.until);
+                    if !recurrence.days.is_empty() {
+                        let days: Vec<String> =
+                            recurrence.days.iter().map(|day| day.to_string()).collect();
+                        flag("days", &Some(days.join(",")));
+                    }
+                }
+                "calendar"
+            }
+            Intent::UpdateEvent(update) => {
+                args.push("update".to_string());
+                args.push(update.title.clone());
+                args.push(update.date.clone());
+                flag("date", &update.new_date);
+                flag("start", &update.start);
+                flag("end", &update.end);
+                flag("location", &update.location);
+                let (emails, contacts) = split_attendees(&update.add_attendees);
+                flag("add_email", &emails);
+                flag("add_contacts", &contacts);
+                "calendar"
+            }
+            Intent::DeleteEvent { title, date } => {
+                args.push("delete".to_string());
+                args.push(title.clone());
+                args.push(date.clone());
+                "calendar"
+            }
+            Intent::ListEvents { from, to } => {
+                args.push("agenda".to_string());
+                flag("from", from);
+                flag("to", to);
+                "calendar"
+            }
+            Intent::CreateReminder(reminder) => {
+                args.push("create".to_string());
+                args.push(reminder.title.clone());
+                args.extend(reminder.list.clone());
+                flag("remind", &reminder.remind_at);
+                flag("due", &reminder.due);
+                flag("notes", &reminder.notes);
+                "reminder"
+            }
+            Intent::CompleteReminder { title, list } => {
+                args.push("complete".to_string());
+                args.push(title.clone());
+                args.extend(list.clone());
+                "reminder"
+            }
+            Intent::ListReminders { list } => {
+                args.push("list".to_string());
+                args.extend(list.clone());
+                "reminder"
+            }
+            Intent::CreateNote { title, content, folder } => {
+                args.push("create".to_string());
+                args.push(title.clone());
+                flag("content", content);
+                flag("folder", folder);
+                "note"
+            }
+            Intent::SearchNotes { query, folder } => {
+                args.push("search".to_string());
+                args.push(query.clone());
+                flag("folder", folder);
+                "note"
+            }
+            Intent::ListNotes { folder } => {
+                args.push("list".to_string());
+                args.extend(folder.clone());
+                "note"
+            }
+        };
+
+        CommandArgs::new(command.to_string(), args, flags)
+    }
+}
+
+impl EventIntent {
+    /// The date, start and end in local time, converted from `timezone` if set
+    fn local_times(&self) -> (String, String, String) {
+        let unchanged = (self.date.clone(), self.start.clone(), self.end.clone());
+        let Some(tz) = self.timezone.as_ref().and_then(|tz| tz.parse::<Tz>().ok()) else {
+            return unchanged;
+        };
+        let date = match self.date.as_str() {
+            "today" => Local::now().date_naive(),
+            "tomorrow" => Local::now().date_naive() + chrono::Duration::days(1),
+            date => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                Ok(date) => date,
+                Err(_) => return unchanged,
+            },
+        };
+        let to_local = |date: NaiveDate, time: &str| {
+            let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+            Some(tz.from_local_datetime(&date.and_time(time)).earliest()?.with_timezone(&Local))
+        };
+        // An end before the start is on the next day in `timezone`
+        let end_date = if self.end < self.start { date + chrono::Duration::days(1) } else { date };
+        match (to_local(date, &self.start), to_local(end_date, &self.end)) {
+            (Some(start), Some(end)) => (
+                start.format("%Y-%m-%d").to_string(),
+                start.format("%H:%M").to_string(),
+                end.format("%H:%M").to_string(),
+            ),
+            _ => unchanged,
+        }
+    }
+}
+
+/// Comma-separated email addresses and contact names from a list of attendees
+fn split_attendees(attendees: &[String]) -> (Option<String>, Option<String>) {
+    let (emails, contacts): (Vec<&String>, Vec<&String>) =
+        attendees.iter().partition(|attendee| attendee.contains('@'));
+    let emails: Vec<&str> = emails
+        .into_iter()
+        .map(|email| email.as_str())
+        .filter(|email| validate_email(email))
+        .collect();
+    let contacts: Vec<&str> = contacts.into_iter().map(|contact| contact.as_str()).collect();
+    let join = |items: Vec<&str>| (!items.is_empty()).then(|| items.join(","));
+    (join(emails), join(contacts))
+}
+
+fn check_date(date: &str) -> Result<()> {
+    if date == "today" || date == "tomorrow" {
+        return Ok(());
+    }
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| anyhow!("Invalid date: {}", date))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_is_valid() {
+        assert!(jsonschema::meta::is_valid(&INTENT_SCHEMA));
+    }
+
+    #[test]
+    fn test_create_event_intent() -> Result<()> {
+        let reply = r#"{
+            "action": "create_event",
+            "title": "Design \"v2\" review",
+            "date": "2025-06-03",
+            "start": "15:00",
+            "end": "16:30",
+            "attendees": ["Jane Smith", "sam@example.com"],
+            "zoom": true,
+            "recurrence": {"frequency": "weekly", "count": 4, "days": [2]}
+        }"#;
+        let args = parse_intent(reply)?.to_command_args();
+
+        assert_eq!(args.command, "calendar");
+        assert_eq!(args.args, ["create", "Design \"v2\" review", "2025-06-03", "15:00", "16:30"]);
+        assert_eq!(args.flags["contacts"].as_deref(), Some("Jane Smith"));
+        assert_eq!(args.flags["email"].as_deref(), Some("sam@example.com"));
+        assert!(args.flags.contains_key("zoom"));
+        assert_eq!(args.flags["repeat"].as_deref(), Some("weekly"));
+        assert_eq!(args.flags["count"].as_deref(), Some("4"));
+        assert_eq!(args.flags["days"].as_deref(), Some("2"));
+        assert!(!args.flags.contains_key("interval"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_event_in_another_time_zone() -> Result<()> {
+        let reply = r#"{"action": "create_event", "title": "Standup", "date": "2025-01-15",
+            "start": "09:00", "end": "09:30", "timezone": "Asia/Tokyo"}"#;
+        let args = parse_intent(reply)?.to_command_args();
+
+        let tokyo: Tz = "Asia/Tokyo".parse().unwrap();
+        let start = tokyo.with_ymd_and_hms(2025, 1, 15, 9, 0, 0).unwrap().with_timezone(&Local);
+        assert_eq!(args.args[2], start.format("%Y-%m-%d").to_string());
+        assert_eq!(args.args[3], start.format("%H:%M").to_string());
+        assert_eq!(args.flags["timezone"].as_deref(), Some("Asia/Tokyo"));
+
+        let unknown = reply.replace("Asia/Tokyo", "Mars/Olympus_Mons");
+        assert!(parse_intent(&unknown).unwrap_err().to_string().contains("Unknown time zone"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_overnight_event() -> Result<()> {
+        let reply = r#"{"action": "create_event", "title": "Party", "date": "2025-06-03",
+            "start": "22:00", "end": "01:00""#;
+        let args = parse_intent(&format!("{}}}", reply))?.to_command_args();
+        assert_eq!(args.args, ["create", "Party", "2025-06-03", "22:00", "01:00"]);
+
+        let mut config = crate::calendar::EventConfig::new("Party", &args.args[2], &args.args[3]);
+        config.end_time = Some(args.args[4].clone());
+        let (start, end) = config.time_span()?;
+        assert_eq!(end - start, chrono::Duration::hours(3));
+
+        // The end is converted on the day after the start
+        let tokyo = format!(r#"{}, "timezone": "Asia/Tokyo"}}"#, reply);
+        let args = parse_intent(&tokyo)?.to_command_args();
+        let zone: Tz = "Asia/Tokyo".parse().unwrap();
+        let end = zone.with_ymd_and_hms(2025, 6, 4, 1, 0, 0).unwrap().with_timezone(&Local);
+        assert_eq!(args.args[4], end.format("%H:%M").to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_event_intent() -> Result<()> {
+        let reply = r#"{"action": "update_event", "title": "Lunch", "date": "2025-06-03",
+            "start": "16:00", "add_attendees": ["Sarah Chen", "sam@example.com"]}"#;
+        let args = parse_intent(reply)?.to_command_args();
+
+        assert_eq!(args.command, "calendar");
+        assert_eq!(args.args, ["update", "Lunch", "2025-06-03"]);
+        assert_eq!(args.flags["start"].as_deref(), Some("16:00"));
+        assert_eq!(args.flags["add_contacts"].as_deref(), Some("Sarah Chen"));
+        assert_eq!(args.flags["add_email"].as_deref(), Some("sam@example.com"));
+        assert!(!args.flags.contains_key("end") && !args.flags.contains_key("date"));
+
+        let unchanged = r#"{"action": "update_event", "title": "Lunch", "date": "today"}"#;
+        assert!(parse_intent(unchanged).unwrap_err().to_string().contains("changes nothing"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_intents() -> Result<()> {
+        let reminder = parse_intent(
+            "```json\n{\"action\": \"create_reminder\", \"title\": \"Call Sam\", \"list\": \"Home\", \"remind_at\": \"2025-06-03 09:00\"}\n```",
+        )?
+        .to_command_args();
+        assert_eq!(reminder.command, "reminder");
+        assert_eq!(reminder.args, ["create", "Call Sam", "Home"]);
+        assert_eq!(reminder.flags["remind"].as_deref(), Some("2025-06-03 09:00"));
+
+        let agenda = parse_intent(r#"{"action": "list_events", "to": "2025-06-08"}"#)?;
+        let agenda = agenda.to_command_args();
+        assert_eq!(
+            (agenda.command.as_str(), agenda.args),
+            ("calendar", vec!["agenda".to_string()])
+        );
+        assert_eq!(agenda.flags["to"].as_deref(), Some("2025-06-08"));
+        assert!(!agenda.flags.contains_key("from"));
+
+        let delete =
+            parse_intent(r#"{"action": "delete_event", "title": "Lunch", "date": "today"}"#)?;
+        assert_eq!(delete.to_command_args().args, ["delete", "Lunch", "today"]);
+
+        let notes = parse_intent(r#"{"action": "list_notes"}"#)?.to_command_args();
+        assert_eq!((notes.command.as_str(), notes.args), ("note", vec!["list".to_string()]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_replies_outside_the_schema() {
+        let cases = [
+            ("ducktape calendar list", "did not reply with JSON"),
+            (r#"{"action": "drop_calendar"}"#, "does not match"),
+            (r#"{"action": "list_events", "shell": "rm -rf ~"}"#, "does not match"),
+            (r#"{"action": "delete_event", "title": "Lunch"}"#, "does not match"),
+            (
+                r#"{"action": "update_event", "title": "Lunch", "start": "16:00"}"#,
+                "does not match",
+            ),
+            (
+                r#"{"action": "create_event", "title": "Lunch", "date": "2025-06-03", "start": "12:00"}"#,
+                "does not match",
+            ),
+            (
+                r#"{"action": "create_event", "title": "Lunch", "date": "2025-06-03", "start": "25:00", "end": "26:00"}"#,
+                "does not match",
+            ),
+            (
+                r#"{"action": "create_event", "title": "Lunch", "date": "2025-02-30", "start": "12:00", "end": "13:00"}"#,
+                "Invalid date",
+            ),
+            (
+                r#"{"action": "create_event", "title": "Lunch", "date": "today", "start": "12:00", "end": "12:00"}"#,
+                "ends at 12:00 when it starts at 12:00",
+            ),
+        ];
+        for (reply, expected) in cases {
+            let error = parse_intent(reply).unwrap_err().to_string();
+            assert!(error.contains(expected), "{}: {}", reply, error);
+        }
+    }
+}
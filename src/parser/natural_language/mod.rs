@@ -152,8 +152,11 @@ pub mod utils {
 }
 
 // Re-export submodules
+pub mod chat;
 pub mod command_mapping;
+pub mod context;
 pub mod grok;
+pub mod intent;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,126 @@
+//! OpenAI-compatible parser module for DuckTape
+//!
+//! This module sends natural language input to any server with an OpenAI-style
+//! `/chat/completions` endpoint, such as llama.cpp or Ollama running locally,
+//! so calendar data never has to leave the machine.
+//!
+//! The endpoint, model, optional API key and timeout come from the
+//! `[language_model]` section of config.toml.
+
+use crate::config::{Config, LanguageModelConfig};
+use crate::parser::natural_language::chat::{self, ChatClient};
+use crate::parser::natural_language::context::ConversationContext;
+use crate::parser::traits::{ParseResult, Parser};
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use log::debug;
+use std::time::Duration;
+
+/// Parser that uses a self-hosted or third-party OpenAI-compatible model
+pub struct OpenAICompatibleParser {
+    client: ChatClient,
+}
+
+impl OpenAICompatibleParser {
+    pub fn new() -> Result<Self> {
+        Self::from_config(&Config::load()?.language_model)
+    }
+
+    /// Create a parser for the endpoint and model in `config`
+    pub fn from_config(config: &LanguageModelConfig) -> Result<Self> {
+        let base_url = config.base_url.as_deref().filter(|url| !url.trim().is_empty()).ok_or_else(
+            || {
+                anyhow!(
+                    "language_model.base_url is not set. Point it at your server with: ducktape config set language_model.base_url http://localhost:11434/v1"
+                )
+            },
+        )?;
+        let model = config.model.as_deref().filter(|model| !model.trim().is_empty()).ok_or_else(
+            || {
+                anyhow!(
+                    "language_model.model is not set. Choose a model with: ducktape config set language_model.model <name>"
+                )
+            },
+        )?;
+        let timeout = config.timeout_secs.map(Duration::from_secs).unwrap_or(chat::DEFAULT_TIMEOUT);
+
+        let client = ChatClient::new(base_url, model, config.api_key.clone(), timeout)?;
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl Parser for OpenAICompatibleParser {
+    async fn parse_input(&self, input: &str) -> Result<ParseResult> {
+        self.parse_input_with_context(input, &ConversationContext::new()).await
+    }
+
+    async fn parse_input_with_context(
+        &self,
+        input: &str,
+        context: &ConversationContext,
+    ) -> Result<ParseResult> {
+        debug!("OpenAI-compatible parser: Processing input: {}", input);
+
+        let intent = self.client.request_intent(input, context).await?;
+        debug!("OpenAI-compatible parser: Intent: {:?}", intent);
+
+        Ok(ParseResult::StructuredCommand(intent.to_command_args()))
+    }
+
+    fn new() -> Result<Self> {
+        OpenAICompatibleParser::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+    use super::*;
+    use crate::config::LLMProvider;
+    use crate::parser::natural_language::chat::mock;
+    use axum::http::StatusCode;
+
+    fn local_config(base_url: &str) -> LanguageModelConfig {
+        LanguageModelConfig {
+            provider: Some(LLMProvider::OpenAICompatible),
+            base_url: Some(base_url.to_string()),
+            model: Some("llama3.1".to_string()),
+            api_key: None,
+            timeout_secs: Some(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_input_with_local_server() -> Result<()> {
+        let reply = r#"{"action": "create_reminder", "title": "Water the plants"}"#;
+        let (base_url, requests) = mock::serve(StatusCode::OK, reply).await;
+        let parser = OpenAICompatibleParser::from_config(&local_config(&base_url))?;
+
+        let ParseResult::StructuredCommand(args) =
+            parser.parse_input("remind me to water the plants").await?
+        else {
+            panic!("Expected StructuredCommand parse result");
+        };
+        assert_eq!(args.command, "reminder");
+        assert_eq!(args.args, ["create", "Water the plants"]);
+
+        // No key is configured, so none is sent
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].0, None);
+        assert_eq!(requests[0].1["model"], "llama3.1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_requires_base_url_and_model() {
+        let mut config = local_config("http://localhost:11434/v1");
+        config.model = None;
+        let error = OpenAICompatibleParser::from_config(&config).err().unwrap();
+        assert!(error.to_string().contains("language_model.model"));
+
+        config.base_url = None;
+        let error = OpenAICompatibleParser::from_config(&config).err().unwrap();
+        assert!(error.to_string().contains("language_model.base_url"));
+    }
+}
@@ -3,6 +3,7 @@
 //! This module defines the core traits and types for the parser system,
 //! providing a unified interface for different parser implementations.
 
+use crate::parser::natural_language::context::ConversationContext;
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 
@@ -21,6 +22,16 @@ pub trait Parser: Send + Sync {
     /// Parse input to either a command string or structured command
     async fn parse_input(&self, input: &str) -> Result<ParseResult>;
 
+    /// Parse input that may refer to earlier requests in the same session,
+    /// e.g. "move it to 4pm". Parsers that cannot use the context ignore it.
+    async fn parse_input_with_context(
+        &self,
+        input: &str,
+        _context: &ConversationContext,
+    ) -> Result<ParseResult> {
+        self.parse_input(input).await
+    }
+
     /// Create a new instance of this parser
     fn new() -> Result<Self>
     where
@@ -38,6 +49,10 @@ pub fn create_parser(name: &str) -> Result<Box<dyn Parser + Send + Sync>> {
             let parser = crate::parser::deepseek::DeepSeekParser::new()?;
             Ok(Box::new(parser))
         }
+        "openai-compatible" => {
+            let parser = crate::parser::openai_compatible::OpenAICompatibleParser::new()?;
+            Ok(Box::new(parser))
+        }
         "terminal" => crate::parser::terminal::create_terminal_parser(),
         "command" => {
             let parser = crate::parser::command::CommandParser::new()?;
@@ -0,0 +1,465 @@
+//! Previews of commands generated from natural language
+//!
+//! Before a command produced by a language model runs, the user sees what it
+//! will do and can run it, change one of its fields or cancel it. The terminal
+//! asks through [`confirm`]; the WebSocket server sends the same
+//! [`CommandPreview`] in a "confirm" message and applies [`edit`]s from the
+//! client's replies.
+
+// Previews read and edit CommandArgs, which is deprecated in favour of the
+// parser module but still carries every command to CommandProcessor.
+#![allow(deprecated)]
+
+use std::fmt;
+use std::io::{BufRead, Write};
+
+use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::calendar::{RecurrenceFrequency, validate_date_format};
+use crate::command_processor::CommandArgs;
+
+// Where a field's value lives in the command arguments
+#[derive(Clone, Copy)]
+enum Slot {
+    Arg(usize),
+    Flag(&'static str),
+    Switch(&'static str),
+    Repeat,
+}
+
+struct FieldSpec {
+    name: &'static str,
+    label: &'static str,
+    slot: Slot,
+    required: bool,
+}
+
+const fn field(name: &'static str, label: &'static str, slot: Slot, required: bool) -> FieldSpec {
+    FieldSpec { name, label, slot, required }
+}
+
+const EVENT_FIELDS: &[FieldSpec] = &[
+    field("title", "Title", Slot::Arg(1), true),
+    field("date", "Date", Slot::Arg(2), true),
+    field("start", "Start", Slot::Arg(3), true),
+    field("end", "End", Slot::Arg(4), true),
+    field("calendar", "Calendar", Slot::Arg(5), false),
+    field("location", "Location", Slot::Flag("location"), false),
+    field("invitees", "Invitees", Slot::Flag("contacts"), false),
+    field("emails", "Emails", Slot::Flag("email"), false),
+    field("zoom", "Zoom", Slot::Switch("zoom"), false),
+    field("repeat", "Repeats", Slot::Repeat, false),
+    field("notes", "Notes", Slot::Flag("notes"), false),
+];
+
+const UPDATE_FIELDS: &[FieldSpec] = &[
+    field("title", "Event", Slot::Arg(1), true),
+    field("date", "On", Slot::Arg(2), false),
+    field("new_date", "New date", Slot::Flag("date"), false),
+    field("start", "New start", Slot::Flag("start"), false),
+    field("end", "New end", Slot::Flag("end"), false),
+    field("location", "Location", Slot::Flag("location"), false),
+    field("invitees", "Invite", Slot::Flag("add_contacts"), false),
+    field("emails", "Emails", Slot::Flag("add_email"), false),
+];
+
+const REMINDER_FIELDS: &[FieldSpec] = &[
+    field("title", "Title", Slot::Arg(1), true),
+    field("list", "List", Slot::Arg(2), false),
+    field("remind", "Alert", Slot::Flag("remind"), false),
+    field("due", "Due", Slot::Flag("due"), false),
+    field("notes", "Notes", Slot::Flag("notes"), false),
+];
+
+const NOTE_FIELDS: &[FieldSpec] = &[
+    field("title", "Title", Slot::Arg(1), true),
+    field("folder", "Folder", Slot::Flag("folder"), false),
+    field("content", "Content", Slot::Flag("content"), false),
+];
+
+// Flags that only qualify --repeat
+const RECURRENCE_FLAGS: &[&str] = &["repeat", "recurring", "interval", "count", "until", "days"];
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+fn field_specs(args: &CommandArgs) -> &'static [FieldSpec] {
+    match (args.command.as_str(), args.args.first().map(String::as_str)) {
+        ("calendar", Some("create")) => EVENT_FIELDS,
+        ("calendar", Some("update")) => UPDATE_FIELDS,
+        ("reminder", Some("create")) => REMINDER_FIELDS,
+        ("note", Some("create")) => NOTE_FIELDS,
+        _ => &[],
+    }
+}
+
+/// A field of a previewed command the user can change
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PreviewField {
+    pub name: String,
+    pub label: String,
+    pub value: Option<String>,
+}
+
+/// What a command will do, in terms the user can check
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandPreview {
+    /// The equivalent `ducktape ...` command line
+    pub command: String,
+    /// Editable fields; empty for commands that cannot be edited
+    pub fields: Vec<PreviewField>,
+    /// The event's date and times in the time zone the request used, if any
+    pub original_time: Option<String>,
+}
+
+impl CommandPreview {
+    pub fn new(args: &CommandArgs) -> Self {
+        let fields = field_specs(args)
+            .iter()
+            .map(|spec| PreviewField {
+                name: spec.name.to_string(),
+                label: spec.label.to_string(),
+                value: read_slot(args, spec.slot),
+            })
+            .collect();
+        Self { command: command_line(args), fields, original_time: original_time(args) }
+    }
+}
+
+impl fmt::Display for CommandPreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for field in &self.fields {
+            let value = match (&field.value, field.name.as_str()) {
+                (Some(value), _) => value.as_str(),
+                (None, "calendar" | "list") => "(default)",
+                (None, _) => "-",
+            };
+            writeln!(f, "  {:<10} {}", format!("{}:", field.label), value)?;
+            if field.name == "end" {
+                if let Some(original) = &self.original_time {
+                    writeln!(f, "  {:<10} {}", "Requested:", original)?;
+                }
+            }
+        }
+        write!(f, "  {:<10} {}", "Command:", self.command)
+    }
+}
+
+/// Change `field` of a previewed command to `value`; an empty value clears
+/// optional fields
+pub fn edit(args: &mut CommandArgs, field: &str, value: &str) -> Result<()> {
+    let specs = field_specs(args);
+    let spec =
+        specs.iter().find(|spec| spec.name.eq_ignore_ascii_case(field)).ok_or_else(|| {
+            if specs.is_empty() {
+                anyhow!("This command has no fields to edit")
+            } else {
+                let names: Vec<&str> = specs.iter().map(|spec| spec.name).collect();
+                anyhow!("Unknown field '{}'. Fields: {}", field, names.join(", "))
+            }
+        })?;
+
+    let value = value.trim();
+    let clear = value.is_empty() || value.eq_ignore_ascii_case("none");
+    if clear && spec.required {
+        return Err(anyhow!("{} cannot be empty", spec.label));
+    }
+    match spec.name {
+        "date" | "new_date" if !clear && !is_date(value) => {
+            return Err(anyhow!("Invalid date '{}': use YYYY-MM-DD, today or tomorrow", value));
+        }
+        "start" | "end" if !clear && NaiveTime::parse_from_str(value, "%H:%M").is_err() => {
+            return Err(anyhow!("Invalid time '{}': use 24-hour HH:MM", value));
+        }
+        _ => {}
+    }
+
+    match spec.slot {
+        Slot::Arg(index) if clear => args.args.truncate(index),
+        Slot::Arg(index) if index < args.args.len() => args.args[index] = value.to_string(),
+        Slot::Arg(index) if index == args.args.len() => args.args.push(value.to_string()),
+        Slot::Arg(_) => return Err(anyhow!("Set the fields before {} first", spec.label)),
+        Slot::Flag(name) if clear => {
+            args.flags.remove(name);
+        }
+        Slot::Flag(name) => {
+            args.flags.insert(name.to_string(), Some(value.to_string()));
+        }
+        Slot::Switch(name) => match value.to_lowercase().as_str() {
+            "yes" | "y" | "true" | "on" => {
+                args.flags.insert(name.to_string(), Some("true".to_string()));
+            }
+            "no" | "n" | "false" | "off" | "" | "none" => {
+                args.flags.remove(name);
+            }
+            _ => return Err(anyhow!("Answer yes or no for {}", spec.label)),
+        },
+        Slot::Repeat => {
+            for flag in RECURRENCE_FLAGS {
+                args.flags.remove(*flag);
+            }
+            if !clear {
+                RecurrenceFrequency::from_str_custom(value)?;
+                args.flags.insert("repeat".to_string(), Some(value.to_lowercase()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Show the preview of `args` and ask whether to run it, letting the user
+/// edit fields first. Returns false if the user cancels or input ends.
+pub fn confirm(
+    args: &mut CommandArgs,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<bool> {
+    loop {
+        writeln!(output, "{}", CommandPreview::new(args))?;
+        let editable = !field_specs(args).is_empty();
+        let question =
+            if editable { "Run it? [y]es, [e]dit, [n]o: " } else { "Run it? [y]es, [n]o: " };
+
+        let Some(answer) = ask(question, input, output)? else {
+            writeln!(output)?;
+            return Ok(false);
+        };
+        match answer.to_lowercase().as_str() {
+            "y" | "yes" => return Ok(true),
+            "n" | "no" | "c" | "cancel" => return Ok(false),
+            "e" | "edit" if editable => {
+                let Some(field) = ask("Field to change: ", input, output)? else {
+                    return Ok(false);
+                };
+                let Some(value) = ask("New value (empty to clear): ", input, output)? else {
+                    return Ok(false);
+                };
+                if let Err(e) = edit(args, &field, &value) {
+                    writeln!(output, "{}", e)?;
+                }
+            }
+            _ => writeln!(
+                output,
+                "Please answer {}.",
+                if editable { "y, e or n" } else { "y or n" }
+            )?,
+        }
+    }
+}
+
+// Prompt for one line, returning None at the end of input
+fn ask(
+    question: &str,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<Option<String>> {
+    write!(output, "{}", question)?;
+    output.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+fn read_slot(args: &CommandArgs, slot: Slot) -> Option<String> {
+    match slot {
+        Slot::Arg(index) => args.args.get(index).cloned(),
+        Slot::Flag(name) => args.flags.get(name).cloned().flatten(),
+        Slot::Switch(name) => {
+            Some(if args.flags.contains_key(name) { "yes" } else { "no" }.to_string())
+        }
+        Slot::Repeat => describe_recurrence(args),
+    }
+}
+
+fn flag<'a>(args: &'a CommandArgs, name: &str) -> Option<&'a str> {
+    args.flags.get(name).and_then(|value| value.as_deref())
+}
+
+fn describe_recurrence(args: &CommandArgs) -> Option<String> {
+    let mut parts = vec![flag(args, "repeat").or(flag(args, "recurring"))?.to_lowercase()];
+    if let Some(interval) = flag(args, "interval").filter(|i| *i != "1") {
+        parts.push(format!("every {}", interval));
+    }
+    if let Some(days) = flag(args, "days") {
+        let days: Vec<&str> = days
+            .split(',')
+            .filter_map(|day| WEEKDAYS.get(day.trim().parse::<usize>().ok()?).copied())
+            .collect();
+        parts.push(format!("on {}", days.join(", ")));
+    }
+    if let Some(count) = flag(args, "count") {
+        parts.push(format!("{} times", count));
+    }
+    if let Some(until) = flag(args, "until") {
+        parts.push(format!("until {}", until));
+    }
+    Some(parts.join(", "))
+}
+
+// The event's local date and times shown in the time zone of the request
+fn original_time(args: &CommandArgs) -> Option<String> {
+    if field_specs(args).as_ptr() != EVENT_FIELDS.as_ptr() {
+        return None;
+    }
+    let tz: Tz = flag(args, "timezone")?.parse().ok()?;
+    let date = match args.args.get(2)?.as_str() {
+        "today" => Local::now().date_naive(),
+        "tomorrow" => Local::now().date_naive() + chrono::Duration::days(1),
+        date => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?,
+    };
+    let in_tz = |time: &String| {
+        let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+        Some(Local.from_local_datetime(&date.and_time(time)).earliest()?.with_timezone(&tz))
+    };
+    let start = in_tz(args.args.get(3)?)?;
+    let end = in_tz(args.args.get(4)?)?;
+    Some(format!(
+        "{}-{} {}",
+        start.format("%Y-%m-%d %H:%M"),
+        end.format("%H:%M"),
+        tz.name()
+    ))
+}
+
+fn is_date(value: &str) -> bool {
+    value == "today" || value == "tomorrow" || validate_date_format(value)
+}
+
+/// The `ducktape ...` command line that would produce `args`
+pub fn command_line(args: &CommandArgs) -> String {
+    let mut words = vec!["ducktape".to_string(), args.command.clone()];
+    words.extend(args.args.iter().map(|arg| shell_words::quote(arg).into_owned()));
+
+    let mut flags: Vec<_> = args.flags.iter().collect();
+    flags.sort();
+    for (name, value) in flags {
+        words.push(format!("--{}", name.replace('_', "-")));
+        match value.as_deref() {
+            Some("true") | None => {}
+            Some(value) => words.push(shell_words::quote(value).into_owned()),
+        }
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    fn event() -> CommandArgs {
+        let args = ["create", "Budget review", "2025-06-03", "15:00", "16:00"];
+        let flags = HashMap::from([
+            ("contacts".to_string(), Some("Priya Patel".to_string())),
+            ("zoom".to_string(), Some("true".to_string())),
+            ("repeat".to_string(), Some("weekly".to_string())),
+            ("count".to_string(), Some("4".to_string())),
+            ("days".to_string(), Some("2".to_string())),
+        ]);
+        CommandArgs::new("calendar".to_string(), args.map(String::from).to_vec(), flags)
+    }
+
+    fn value(preview: &CommandPreview, name: &str) -> Option<String> {
+        preview.fields.iter().find(|field| field.name == name)?.value.clone()
+    }
+
+    #[test]
+    fn test_event_preview() {
+        let preview = CommandPreview::new(&event());
+        assert_eq!(value(&preview, "title").as_deref(), Some("Budget review"));
+        assert_eq!(value(&preview, "calendar"), None);
+        assert_eq!(value(&preview, "invitees").as_deref(), Some("Priya Patel"));
+        assert_eq!(value(&preview, "zoom").as_deref(), Some("yes"));
+        assert_eq!(value(&preview, "repeat").as_deref(), Some("weekly, on Tue, 4 times"));
+        assert_eq!(
+            preview.command,
+            "ducktape calendar create 'Budget review' 2025-06-03 15:00 16:00 --contacts 'Priya Patel' --count 4 --days 2 --repeat weekly --zoom"
+        );
+
+        let text = preview.to_string();
+        assert!(text.contains("Calendar:  (default)"));
+        assert!(text.contains("Repeats:   weekly, on Tue, 4 times"));
+    }
+
+    #[test]
+    fn test_original_time_zone() {
+        let mut args = event();
+        args.flags.insert("timezone".to_string(), Some("Asia/Tokyo".to_string()));
+        let preview = CommandPreview::new(&args);
+
+        let tokyo: Tz = "Asia/Tokyo".parse().unwrap();
+        let start = Local.with_ymd_and_hms(2025, 6, 3, 15, 0, 0).unwrap().with_timezone(&tokyo);
+        let original = preview.original_time.clone().unwrap();
+        assert!(original.starts_with(&start.format("%Y-%m-%d %H:%M").to_string()));
+        assert!(original.ends_with("Asia/Tokyo"));
+        assert!(preview.to_string().contains("Requested:"));
+    }
+
+    #[test]
+    fn test_edit_fields() -> Result<()> {
+        let mut args = event();
+        edit(&mut args, "start", "15:30")?;
+        edit(&mut args, "calendar", "Work")?;
+        edit(&mut args, "Zoom", "no")?;
+        edit(&mut args, "repeat", "none")?;
+        edit(&mut args, "location", "Room 4")?;
+
+        assert_eq!(args.args, ["create", "Budget review", "2025-06-03", "15:30", "16:00", "Work"]);
+        assert!(!args.flags.contains_key("zoom"));
+        assert!(!args.flags.contains_key("count") && !args.flags.contains_key("repeat"));
+        assert_eq!(args.flags["location"].as_deref(), Some("Room 4"));
+
+        assert!(edit(&mut args, "start", "3pm").is_err());
+        assert!(edit(&mut args, "title", "").is_err());
+        assert!(edit(&mut args, "color", "red").unwrap_err().to_string().contains("title, date"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_preview() -> Result<()> {
+        let flags = HashMap::from([("add_contacts".to_string(), Some("Sarah Chen".to_string()))]);
+        let mut args = CommandArgs::new(
+            "calendar".to_string(),
+            vec!["update".to_string(), "Lunch".to_string(), "2025-06-03".to_string()],
+            flags,
+        );
+        edit(&mut args, "start", "16:00")?;
+        assert!(edit(&mut args, "new_date", "next week").is_err());
+
+        let text = CommandPreview::new(&args).to_string();
+        assert!(text.contains("Invite:    Sarah Chen"));
+        assert!(text.contains("New start: 16:00"));
+        assert!(text.contains("New end:   -"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_confirm_with_edit() -> Result<()> {
+        let mut args = event();
+        let mut input = Cursor::new("e\nend\n17:00\nmaybe\ny\n");
+        let mut output = Vec::new();
+
+        assert!(confirm(&mut args, &mut input, &mut output)?);
+        assert_eq!(args.args[4], "17:00");
+        let output = String::from_utf8(output)?;
+        assert!(output.contains("Please answer y, e or n."));
+        assert_eq!(output.matches("Run it?").count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_confirm_cancel_and_end_of_input() -> Result<()> {
+        let list = CommandArgs::new("note".to_string(), vec!["list".to_string()], HashMap::new());
+        let mut output = Vec::new();
+
+        assert!(!confirm(&mut list.clone(), &mut Cursor::new("n\n"), &mut output)?);
+        assert!(!confirm(&mut list.clone(), &mut Cursor::new(""), &mut output)?);
+        assert!(String::from_utf8(output)?.contains("Run it? [y]es, [n]o: "));
+        Ok(())
+    }
+}
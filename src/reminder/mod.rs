@@ -2,7 +2,7 @@
 //
 // This module provides integration with macOS Reminders.app.
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 
 mod reminder_applescript;
 mod reminder_types;
@@ -26,8 +26,48 @@ pub async fn list_reminder_lists() -> Result<Vec<String>> {
 }
 
 /// Get reminders from a specific list or all lists
-pub async fn get_reminders(list_name: Option<&str>) -> Result<Vec<ReminderItem>> {
-    reminder_applescript::fetch_reminders(list_name).await
+pub async fn get_reminders(
+    list_name: Option<&str>,
+    filter: ReminderFilter,
+) -> Result<Vec<ReminderItem>> {
+    reminder_applescript::fetch_reminders(list_name, filter).await
+}
+
+/// Mark a reminder, given by title or ID, as completed
+///
+/// For a repeating reminder the next occurrence is created as well, and its
+/// due date is returned alongside the completed reminder. If the next
+/// occurrence cannot be created the reminder is marked as not completed
+/// again, so the series does not silently end.
+pub async fn complete_reminder(
+    target: &str,
+    list_name: Option<&str>,
+) -> Result<(ReminderItem, Option<String>)> {
+    let reminder = reminder_applescript::set_reminder_completed(target, list_name, true).await?;
+    match reminder_applescript::create_next_occurrence(&reminder).await {
+        Ok(next_due) => Ok((reminder, next_due)),
+        Err(e) => {
+            let id = reminder.id.as_deref().unwrap_or(target);
+            if let Err(undo) = reminder_applescript::set_reminder_completed(id, None, false).await {
+                return Err(anyhow!(
+                    "Failed to create the next occurrence of '{}' ({}), and it could not be marked as not completed again: {}",
+                    reminder.title,
+                    e,
+                    undo
+                ));
+            }
+            Err(anyhow!(
+                "Failed to create the next occurrence of '{}', so it was left not completed: {}",
+                reminder.title,
+                e
+            ))
+        }
+    }
+}
+
+/// Mark a completed reminder, given by title or ID, as not completed
+pub async fn uncomplete_reminder(target: &str, list_name: Option<&str>) -> Result<ReminderItem> {
+    reminder_applescript::set_reminder_completed(target, list_name, false).await
 }
 
 /// Delete a reminder by title and list
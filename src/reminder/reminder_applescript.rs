@@ -2,11 +2,15 @@
 //
 // This module provides functions to interact with the Reminders application via AppleScript
 
-use super::reminder_types::{ReminderConfig, ReminderError, ReminderItem};
+use super::reminder_types::{ReminderConfig, ReminderError, ReminderFilter, ReminderItem};
 use super::reminder_util::{
-    escape_applescript_string, format_reminder_time, parse_natural_language_time,
+    escape_applescript_string, first_recurrence, format_reminder_time, next_recurrence,
+    parse_natural_language_time, parse_reminder_list_output, reminder_body,
 };
+use super::reminder_validation::validate_reminder_config;
+use crate::calendar::parse_ical_recurrence;
 use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDate, NaiveDateTime};
 use log::{debug, error, info};
 use std::process::Command;
 
@@ -27,30 +31,38 @@ pub async fn ensure_reminders_running() -> Result<()> {
 
 /// Create a single reminder using AppleScript
 pub(crate) async fn create_single_reminder(config: ReminderConfig<'_>) -> Result<()> {
+    // Resolve the alert time first so that validation sees YYYY-MM-DD HH:MM
+    let resolved_time = match config.reminder_time {
+        Some(time_str) if format_reminder_time(time_str).is_ok() => Some(time_str.to_string()),
+        Some(time_str) => Some(parse_natural_language_time(time_str)?),
+        None => None,
+    };
+    // A repeating reminder without a due date is first due on its first occurrence from today
+    let first_due = match (&config.recurrence, config.due_date) {
+        (Some(pattern), None) => first_recurrence(pattern, Local::now().date_naive())
+            .map(|date| date.format("%Y-%m-%d").to_string()),
+        _ => None,
+    };
+    let config = ReminderConfig {
+        reminder_time: resolved_time.as_deref(),
+        due_date: config.due_date.or(first_due.as_deref()),
+        ..config
+    };
+    validate_reminder_config(&config)?;
+
     // Make sure Reminders app is running
     ensure_reminders_running().await?;
 
     // Extract parameters from config
     let title = config.title;
-    let time_str = config.reminder_time.unwrap_or_default();
-    let list_names = if config.lists.is_empty() { vec!["Reminders"] } else { config.lists };
-
-    // First try to parse the time string as a natural language expression
-    let formatted_time = match parse_natural_language_time(time_str) {
-        Ok(parsed_time) => format_reminder_time(&parsed_time)?,
-        Err(_) => {
-            // If natural language parsing fails, try the original format
-            format_reminder_time(time_str)?
-        }
-    };
+    let list_names = if config.lists.is_empty() { vec!["Reminders"] } else { config.lists.clone() };
+    let properties = reminder_properties(&config)?;
 
     // Create reminder for each list specified
     let mut success_count = 0;
     for list_name in list_names {
         // Escape strings for AppleScript
-        let escaped_title = escape_applescript_string(title);
         let escaped_list_name = escape_applescript_string(list_name);
-        let escaped_notes = escape_applescript_string(&config.notes.clone().unwrap_or_default());
 
         // Build the AppleScript command
         let script = format!(
@@ -63,14 +75,14 @@ pub(crate) async fn create_single_reminder(config: ReminderConfig<'_>) -> Result
                         set targetList to make new list with properties {{name:"{}"}}
                     end if
                     
-                    set newReminder to make new reminder in targetList with properties {{name:"{}", body:"{}", remind me date:date "{}"}}
+                    set newReminder to make new reminder in targetList with properties {{{}}}
                     
                     return "Success: Reminder created"
                 on error errMsg
                     return "Error: " & errMsg
                 end try
             end tell"#,
-            escaped_list_name, escaped_list_name, escaped_title, escaped_notes, formatted_time
+            escaped_list_name, escaped_list_name, properties
         );
 
         debug!("Executing AppleScript: {}", script);
@@ -97,6 +109,33 @@ pub(crate) async fn create_single_reminder(config: ReminderConfig<'_>) -> Result
     }
 }
 
+/// The AppleScript property record for a new reminder, without the braces
+fn reminder_properties(config: &ReminderConfig<'_>) -> Result<String> {
+    let body = reminder_body(config.notes.as_deref(), config.recurrence.as_ref())?;
+    let mut properties = vec![
+        format!("name:\"{}\"", escape_applescript_string(config.title)),
+        format!("body:\"{}\"", escape_applescript_string(&body)),
+    ];
+    if let Some(time) = config.reminder_time {
+        properties.push(format!("remind me date:date \"{}\"", format_reminder_time(time)?));
+    }
+    if let Some(due_date) = config.due_date.map(str::trim) {
+        if due_date.contains(' ') {
+            properties.push(format!("due date:date \"{}\"", format_reminder_time(due_date)?));
+        } else {
+            let midnight = format_reminder_time(&format!("{} 00:00", due_date))?;
+            properties.push(format!("allday due date:date \"{}\"", midnight));
+        }
+    }
+    if let Some(priority) = config.priority {
+        properties.push(format!("priority:{}", priority.to_applescript()));
+    }
+    if config.flagged {
+        properties.push("flagged:true".to_string());
+    }
+    Ok(properties.join(", "))
+}
+
 /// Run an AppleScript command and return the output
 fn run_applescript(script: &str) -> Result<String> {
     let output = Command::new("osascript").arg("-e").arg(script).output()?;
@@ -142,113 +181,223 @@ end tell"#;
     Ok(lists)
 }
 
-/// Fetch reminders from a specific list or all lists
-pub async fn fetch_reminders(list_name: Option<&str>) -> Result<Vec<ReminderItem>> {
+/// Fetch reminders from a specific list or all lists, keeping those that match `filter`
+pub async fn fetch_reminders(
+    list_name: Option<&str>,
+    filter: ReminderFilter,
+) -> Result<Vec<ReminderItem>> {
     // Make sure Reminders app is running
     ensure_reminders_running().await?;
 
-    let script = if let Some(list) = list_name {
-        let escaped_list = escape_applescript_string(list);
-        format!(
-            r#"tell application "Reminders"
-    set reminderList to {}
-    set listObj to first list whose name is "{0}"
-    repeat with r in (reminders in listObj)
-        set reminderTitle to name of r
-        set reminderCompleted to completed of r
-        set reminderBody to ""
-        try
-            set reminderBody to body of r
-        end try
-        set reminderItem to {{title:reminderTitle, notes:reminderBody, completed:reminderCompleted, listName:"{0}"}}
-        copy reminderItem to end of reminderList
+    let condition = filter
+        .applescript_condition()
+        .map(|c| format!(" whose {}", c))
+        .unwrap_or_default();
+    let script = format!(
+        r#"{handlers}
+tell application "Reminders"
+    set output to ""
+    repeat with l in {lists}
+        repeat with r in (reminders in l{condition})
+            set output to output & my reminderRecord(r, name of l)
+        end repeat
     end repeat
-    return reminderList
+    return output
 end tell"#,
-            escaped_list
-        )
-    } else {
-        r#"tell application "Reminders"
-    set reminderList to {}
-    repeat with l in lists
-        set listName to name of l
-        repeat with r in (reminders in l)
-            set reminderTitle to name of r
-            set reminderCompleted to completed of r
-            set reminderBody to ""
-            try
-                set reminderBody to body of r
-            end try
-            set reminderItem to {title:reminderTitle, notes:reminderBody, completed:reminderCompleted, listName:listName}
-            copy reminderItem to end of reminderList
+        handlers = REMINDER_RECORD_HANDLERS,
+        lists = lists_clause(list_name),
+        condition = condition,
+    );
+
+    let mut reminders = parse_reminder_list_output(&run_applescript(&script)?);
+    reminders.retain(|reminder| filter.matches(reminder));
+    debug!("Fetched {} reminders", reminders.len());
+    Ok(reminders)
+}
+
+/// The lists to search: the named list, or all of them
+fn lists_clause(list_name: Option<&str>) -> String {
+    match list_name {
+        Some(list) => format!("(lists whose name is \"{}\")", escape_applescript_string(list)),
+        None => "lists".to_string(),
+    }
+}
+
+/// AppleScript handlers that print a reminder in the format read by `parse_reminder_list_output`
+const REMINDER_RECORD_HANDLERS: &str = r#"on pad(n)
+    return text -2 thru -1 of ("0" & (n as integer))
+end pad
+
+on formatDate(d)
+    if d is missing value then return ""
+    return ((year of d) as string) & "-" & my pad(month of d as integer) & "-" & my pad(day of d) & " " & my pad(hours of d) & ":" & my pad(minutes of d)
+end formatDate
+
+on replaceText(t, searchText, replacement)
+    set AppleScript's text item delimiters to searchText
+    set parts to text items of t
+    set AppleScript's text item delimiters to replacement
+    set t to parts as string
+    set AppleScript's text item delimiters to ""
+    return t
+end replaceText
+
+on escapeField(v)
+    if v is missing value then return ""
+    set t to my replaceText(v as string, "\\", "\\\\")
+    set t to my replaceText(t, character id 31, "\\u")
+    return my replaceText(t, character id 30, "\\r")
+end escapeField
+
+on reminderRecord(r, listName)
+    set fieldSep to character id 31
+    tell application "Reminders"
+        if allday due date of r is missing value then
+            set dueText to my formatDate(due date of r)
+        else
+            set dueText to text 1 thru 10 of my formatDate(allday due date of r)
+        end if
+        return my escapeField(id of r) & fieldSep & my escapeField(listName) & fieldSep & my escapeField(name of r) & fieldSep & ((completed of r) as string) & fieldSep & my escapeField(body of r) & fieldSep & dueText & fieldSep & my formatDate(remind me date of r) & fieldSep & ((priority of r) as string) & fieldSep & ((flagged of r) as string) & (character id 30)
+    end tell
+end reminderRecord
+"#;
+
+/// Mark the reminder with the given title or ID as completed, or as not completed
+///
+/// Only reminders in the opposite state are considered. When a title matches
+/// reminders in several lists (or several in one list), nothing is changed and
+/// the error lists the matches so the caller can pass a list name or an ID.
+pub async fn set_reminder_completed(
+    target: &str,
+    list_name: Option<&str>,
+    completed: bool,
+) -> Result<ReminderItem> {
+    ensure_reminders_running().await?;
+
+    let escaped_target = escape_applescript_string(target);
+    let lookup_script = format!(
+        r#"{handlers}
+tell application "Reminders"
+    set output to ""
+    repeat with l in {lists}
+        repeat with r in (reminders in l whose (id is "{target}" or name is "{target}") and completed is {current})
+            set output to output & my reminderRecord(r, name of l)
         end repeat
     end repeat
-    return reminderList
-end tell"#
-        .to_string()
+    return output
+end tell"#,
+        handlers = REMINDER_RECORD_HANDLERS,
+        lists = lists_clause(list_name),
+        target = escaped_target,
+        current = !completed,
+    );
+    debug!("Executing AppleScript: {}", lookup_script);
+    let matches = parse_reminder_list_output(&run_applescript(&lookup_script)?);
+    let reminder = select_reminder(target, matches, completed)?;
+
+    let id = reminder.id.as_deref().unwrap_or_default();
+    let update_script = format!(
+        r#"tell application "Reminders"
+    set completed of (first reminder whose id is "{}") to {}
+    return "Success: Reminder updated"
+end tell"#,
+        escape_applescript_string(id),
+        completed
+    );
+    let result = run_applescript(&update_script)?;
+    if !result.contains("Success") {
+        return Err(anyhow!(ReminderError::ScriptError(result)));
+    }
+
+    info!(
+        "Marked reminder '{}' in {} as {}",
+        reminder.title,
+        reminder.lists.join(", "),
+        if completed { "completed" } else { "not completed" }
+    );
+    Ok(ReminderItem { completed, ..reminder })
+}
+
+/// Create the next occurrence of a completed repeating reminder
+///
+/// Returns the new reminder's due date, or None if the reminder does not
+/// repeat or its rule has ended. An alert time moves by as many days as the
+/// due date does.
+pub async fn create_next_occurrence(reminder: &ReminderItem) -> Result<Option<String>> {
+    let Some(rule) = reminder.recurrence.as_deref() else {
+        return Ok(None);
+    };
+    let pattern = parse_ical_recurrence(rule)
+        .ok_or_else(|| anyhow!("Reminder '{}' has an invalid repeat rule", reminder.title))?;
+    let (due_day, due_time) = match reminder.due_date.as_deref() {
+        Some(due) => {
+            let (day, time) = due.split_once(' ').map_or((due, None), |(d, t)| (d, Some(t)));
+            (NaiveDate::parse_from_str(day, "%Y-%m-%d")?, time)
+        }
+        None => (Local::now().date_naive(), None),
+    };
+    let Some((next_day, remaining)) = next_recurrence(&pattern, due_day) else {
+        info!("Repeating reminder '{}' has no further occurrences", reminder.title);
+        return Ok(None);
     };
 
-    let output = Command::new("osascript").arg("-e").arg(script).output()?;
-    if !output.status.success() {
-        return Err(anyhow!(ReminderError::ScriptError(
-            String::from_utf8_lossy(&output.stderr).to_string()
-        )));
-    }
+    let due_date = match due_time {
+        Some(time) => format!("{} {}", next_day.format("%Y-%m-%d"), time),
+        None => next_day.format("%Y-%m-%d").to_string(),
+    };
+    let reminder_time = match reminder.reminder_time.as_deref() {
+        Some(time) => {
+            let alert = NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M")?;
+            Some((alert + (next_day - due_day)).format("%Y-%m-%d %H:%M").to_string())
+        }
+        None => None,
+    };
+    let config = ReminderConfig {
+        title: &reminder.title,
+        lists: reminder.lists.iter().map(String::as_str).collect(),
+        reminder_time: reminder_time.as_deref(),
+        notes: reminder.notes.clone(),
+        due_date: Some(&due_date),
+        priority: reminder.priority,
+        flagged: reminder.flagged,
+        recurrence: Some(remaining),
+    };
+    create_single_reminder(config).await?;
+    info!("Created next occurrence of '{}' due {}", reminder.title, due_date);
+    Ok(Some(due_date))
+}
 
-    // Parse the AppleScript output into ReminderItem structs
-    let reminders_output = String::from_utf8_lossy(&output.stdout);
-    let mut reminders = Vec::new();
-
-    // Very basic parsing of AppleScript output - in a real implementation
-    // you might want a more robust parser
-    for line in reminders_output.lines() {
-        if line.contains("title:") && line.contains("notes:") {
-            // Extract data from line
-            let mut title = String::new();
-            let mut notes = None;
-            let mut completed = false;
-            let mut list_name = String::new();
-
-            // Super simple parsing - you might want to improve this
-            if let Some(title_start) = line.find("title:") {
-                if let Some(title_end) = line[title_start..].find(",") {
-                    title = line[title_start + 6..title_start + title_end].trim().to_string();
-                }
-            }
-
-            if let Some(notes_start) = line.find("notes:") {
-                if let Some(notes_end) = line[notes_start..].find(",") {
-                    let note_text =
-                        line[notes_start + 6..notes_start + notes_end].trim().to_string();
-                    if !note_text.is_empty() {
-                        notes = Some(note_text);
-                    }
-                }
-            }
-
-            if let Some(completed_start) = line.find("completed:") {
-                if let Some(completed_str) = line[completed_start + 10..].split_whitespace().next()
-                {
-                    completed = completed_str == "true";
-                }
-            }
-
-            if let Some(list_start) = line.find("listName:") {
-                list_name = line[list_start + 9..].trim().to_string();
-            }
-
-            reminders.push(ReminderItem {
-                title,
-                notes,
-                lists: vec![list_name],
-                reminder_time: None, // We don't parse this in this example
-                completed,
-            });
+/// Pick the single reminder a completion request refers to
+///
+/// An exact ID match wins; otherwise the title must match exactly one reminder.
+fn select_reminder(
+    target: &str,
+    mut matches: Vec<ReminderItem>,
+    completed: bool,
+) -> Result<ReminderItem> {
+    if let Some(i) = matches.iter().position(|r| r.id.as_deref() == Some(target)) {
+        return Ok(matches.swap_remove(i));
+    }
+    match matches.len() {
+        0 => Err(anyhow!(ReminderError::ReminderNotFound(format!(
+            "no {} reminder matches '{}'",
+            if completed { "pending" } else { "completed" },
+            target
+        )))),
+        1 => Ok(matches.remove(0)),
+        n => {
+            let found: Vec<String> = matches
+                .iter()
+                .map(|r| format!("{} (id: {})", r.lists.join(", "), r.id.as_deref().unwrap_or("-")))
+                .collect();
+            Err(anyhow!(ReminderError::InvalidInput(format!(
+                "'{}' matches {} reminders: {}. Give a list name or the reminder ID",
+                target,
+                n,
+                found.join("; ")
+            ))))
         }
     }
-
-    debug!("Fetched {} reminders", reminders.len());
-    Ok(reminders)
 }
 
 /// Delete a reminder by title and list
@@ -320,3 +469,58 @@ end tell"#,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reminder::{REMINDER_FIELD_SEPARATOR, REMINDER_RECORD_SEPARATOR, ReminderPriority};
+
+    fn record(fields: &[&str]) -> String {
+        let separator = REMINDER_FIELD_SEPARATOR.to_string();
+        format!("{}{}", fields.join(&separator), REMINDER_RECORD_SEPARATOR)
+    }
+
+    fn matches(records: &[(&str, &str, &str)]) -> Vec<ReminderItem> {
+        let output: String = records
+            .iter()
+            .map(|(id, list, title)| record(&[id, list, title, "false", "", "", "", "0", "false"]))
+            .collect();
+        parse_reminder_list_output(&format!("{}\n", output))
+    }
+
+    #[test]
+    fn test_reminder_properties() {
+        let config = ReminderConfig::new("Pay \"rent\"")
+            .with_due_date("2024-06-03")
+            .with_priority(ReminderPriority::Medium)
+            .with_flagged(true)
+            .with_notes("Landlord".to_string());
+        let properties = reminder_properties(&config).unwrap();
+        assert!(properties.starts_with("name:\"Pay \\\"rent\\\"\", body:\"Landlord\""));
+        assert!(properties.contains("allday due date:date \"6/3/2024 12:00:00 AM\""));
+        assert!(properties.contains("priority:5"));
+        assert!(properties.ends_with("flagged:true"));
+        assert!(!properties.contains("remind me date"));
+
+        let timed = ReminderConfig::new("Call").with_due_date("2024-06-03 15:30");
+        assert!(
+            reminder_properties(&timed)
+                .unwrap()
+                .contains("due date:date \"6/3/2024 3:30:00 PM\"")
+        );
+    }
+
+    #[test]
+    fn test_select_reminder_by_title_or_id() {
+        let single = matches(&[("x-1", "Home", "Buy milk")]);
+        assert_eq!(select_reminder("Buy milk", single, true).unwrap().id.as_deref(), Some("x-1"));
+
+        let several = matches(&[("x-1", "Home", "Buy milk"), ("x-2", "Work", "Buy milk")]);
+        let err = select_reminder("Buy milk", several.clone(), true).unwrap_err().to_string();
+        assert!(err.contains("Home (id: x-1)") && err.contains("Work (id: x-2)"));
+        assert_eq!(select_reminder("x-2", several, true).unwrap().lists, vec!["Work".to_string()]);
+
+        let err = select_reminder("Buy milk", Vec::new(), true).unwrap_err().to_string();
+        assert!(err.contains("no pending reminder"));
+    }
+}
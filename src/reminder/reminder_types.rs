@@ -1,8 +1,13 @@
 //! Type definitions for reminder functionality
 
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::calendar::RecurrencePattern;
+
 /// Configuration for creating a new reminder
 #[derive(Debug, Clone)]
 pub struct ReminderConfig<'a> {
@@ -14,12 +19,29 @@ pub struct ReminderConfig<'a> {
     pub reminder_time: Option<&'a str>,
     /// Optional notes/details for the reminder
     pub notes: Option<String>,
+    /// Optional due date as "YYYY-MM-DD", or "YYYY-MM-DD HH:MM" for a due time
+    pub due_date: Option<&'a str>,
+    /// Optional priority
+    pub priority: Option<ReminderPriority>,
+    /// Whether the reminder is flagged
+    pub flagged: bool,
+    /// Optional repeat rule; the next occurrence is created when the reminder is completed
+    pub recurrence: Option<RecurrencePattern>,
 }
 
 impl<'a> ReminderConfig<'a> {
     /// Create a new ReminderConfig with just a title
     pub fn new(title: &'a str) -> Self {
-        Self { title, lists: Vec::new(), reminder_time: None, notes: None }
+        Self {
+            title,
+            lists: Vec::new(),
+            reminder_time: None,
+            notes: None,
+            due_date: None,
+            priority: None,
+            flagged: false,
+            recurrence: None,
+        }
     }
 
     /// Set the lists for this reminder
@@ -39,11 +61,94 @@ impl<'a> ReminderConfig<'a> {
         self.reminder_time = Some(time);
         self
     }
+
+    /// Set the due date for this reminder
+    pub fn with_due_date(mut self, due_date: &'a str) -> Self {
+        self.due_date = Some(due_date);
+        self
+    }
+
+    /// Set the priority for this reminder
+    pub fn with_priority(mut self, priority: ReminderPriority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Set whether this reminder is flagged
+    pub fn with_flagged(mut self, flagged: bool) -> Self {
+        self.flagged = flagged;
+        self
+    }
+
+    /// Make this reminder repeat
+    pub fn with_recurrence(mut self, recurrence: RecurrencePattern) -> Self {
+        self.recurrence = Some(recurrence);
+        self
+    }
+}
+
+/// Priority of a reminder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReminderPriority {
+    Low,
+    Medium,
+    High,
+}
+
+impl ReminderPriority {
+    /// The value of the `priority` property in Reminders.app
+    pub fn to_applescript(&self) -> u8 {
+        match self {
+            ReminderPriority::High => 1,
+            ReminderPriority::Medium => 5,
+            ReminderPriority::Low => 9,
+        }
+    }
+
+    /// Read the `priority` property of Reminders.app, where 0 means no priority
+    pub fn from_applescript(value: u8) -> Option<Self> {
+        match value {
+            1..=4 => Some(ReminderPriority::High),
+            5 => Some(ReminderPriority::Medium),
+            6..=9 => Some(ReminderPriority::Low),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for ReminderPriority {
+    type Err = ReminderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "low" | "!" => Ok(ReminderPriority::Low),
+            "medium" | "med" | "!!" => Ok(ReminderPriority::Medium),
+            "high" | "!!!" => Ok(ReminderPriority::High),
+            other => Err(ReminderError::InvalidInput(format!(
+                "Invalid priority '{}'. Use low, medium or high",
+                other
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ReminderPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReminderPriority::Low => "low",
+            ReminderPriority::Medium => "medium",
+            ReminderPriority::High => "high",
+        })
+    }
 }
 
 /// Represents a reminder item
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReminderItem {
+    /// Identifier assigned by Reminders.app
+    #[serde(default)]
+    pub id: Option<String>,
     /// The title/name of the reminder item
     pub title: String,
     /// The lists this reminder belongs to
@@ -54,6 +159,50 @@ pub struct ReminderItem {
     pub notes: Option<String>,
     /// Whether the reminder is completed
     pub completed: bool,
+    /// Due date as "YYYY-MM-DD", or "YYYY-MM-DD HH:MM" when it has a time
+    #[serde(default)]
+    pub due_date: Option<String>,
+    /// Priority, if one is set
+    #[serde(default)]
+    pub priority: Option<ReminderPriority>,
+    /// Whether the reminder is flagged
+    #[serde(default)]
+    pub flagged: bool,
+    /// RFC 5545 RRULE value for repeating reminders
+    #[serde(default)]
+    pub recurrence: Option<String>,
+}
+
+/// Which reminders to return when listing, by completion state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReminderFilter {
+    /// Completed and pending reminders
+    #[default]
+    All,
+    /// Only reminders that are not completed
+    Pending,
+    /// Only completed reminders
+    Completed,
+}
+
+impl ReminderFilter {
+    /// Whether a reminder passes the filter
+    pub fn matches(&self, reminder: &ReminderItem) -> bool {
+        match self {
+            ReminderFilter::All => true,
+            ReminderFilter::Pending => !reminder.completed,
+            ReminderFilter::Completed => reminder.completed,
+        }
+    }
+
+    /// AppleScript `whose` clause selecting the same reminders, if any
+    pub fn applescript_condition(&self) -> Option<&'static str> {
+        match self {
+            ReminderFilter::All => None,
+            ReminderFilter::Pending => Some("completed is false"),
+            ReminderFilter::Completed => Some("completed is true"),
+        }
+    }
 }
 
 /// Error types specific to reminder operations
@@ -1,9 +1,25 @@
 //! Utility functions for reminder operations
 
 use anyhow::{Result, anyhow};
-use chrono::{Datelike, Local, NaiveDate, Timelike};
+use chrono::{Datelike, Days, Duration, Local, Months, NaiveDate, Timelike};
+use lazy_static::lazy_static;
+use log::debug;
 use regex::Regex;
 
+use super::{ReminderItem, ReminderPriority};
+use crate::calendar::{RecurrenceFrequency, RecurrencePattern, parse_ical_recurrence};
+
+/// Prefix of the line in a reminder's notes that holds its repeat rule
+///
+/// Reminders.app does not expose recurrence to AppleScript, so DuckTape keeps
+/// the rule in the notes and creates the next occurrence on completion.
+pub const REPEAT_PREFIX: &str = "Repeats: ";
+
+lazy_static! {
+    static ref REPEAT_INTERVAL_RE: Regex =
+        Regex::new(r"^(\d+)\s+(day|week|month|year)s?$").unwrap();
+}
+
 /// Escape a string for use in AppleScript
 pub fn escape_applescript_string(input: &str) -> String {
     input.replace("\"", "\\\"")
@@ -123,15 +139,254 @@ pub fn parse_natural_language_time(time_expr: &str) -> Result<String> {
     }
 }
 
-/// Parse the output of the list command to extract reminder items
-pub fn parse_reminder_list_output(_output: &str) -> Vec<super::ReminderItem> {
-    Vec::new()
+/// Parse a repeat rule such as `daily`, `every 2 weeks`, `every Monday`,
+/// `every Tuesday and Thursday`, `weekdays` or an RRULE like `FREQ=WEEKLY;BYDAY=MO`
+pub fn parse_reminder_recurrence(value: &str) -> Result<RecurrencePattern> {
+    let text = value.trim().to_lowercase();
+    if text.starts_with("freq=") || text.starts_with("rrule:") {
+        return parse_ical_recurrence(value)
+            .ok_or_else(|| anyhow!("Invalid repeat rule '{}'", value));
+    }
+    let text = text.strip_prefix("every").map(str::trim).unwrap_or(&text);
+    match text {
+        "daily" | "day" => return Ok(RecurrencePattern::new(RecurrenceFrequency::Daily)),
+        "weekly" | "week" => return Ok(RecurrencePattern::new(RecurrenceFrequency::Weekly)),
+        "monthly" | "month" => return Ok(RecurrencePattern::new(RecurrenceFrequency::Monthly)),
+        "yearly" | "year" | "annually" => {
+            return Ok(RecurrencePattern::new(RecurrenceFrequency::Yearly));
+        }
+        "weekday" | "weekdays" => {
+            return Ok(RecurrencePattern::new(RecurrenceFrequency::Weekly)
+                .with_days_of_week(&[1, 2, 3, 4, 5]));
+        }
+        _ => {}
+    }
+
+    if let Some(caps) = REPEAT_INTERVAL_RE.captures(text) {
+        let interval: u32 = caps[1].parse()?;
+        if interval == 0 {
+            return Err(anyhow!("Repeat interval must be at least 1"));
+        }
+        let frequency = RecurrenceFrequency::from_str_custom(&caps[2])?;
+        return Ok(RecurrencePattern::new(frequency).with_interval(interval));
+    }
+
+    let days = text
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|word| !word.is_empty() && *word != "and")
+        .map(weekday_number)
+        .collect::<Option<Vec<u8>>>()
+        .filter(|days| !days.is_empty())
+        .ok_or_else(|| {
+            anyhow!("Invalid repeat rule '{}'. Try daily, every 2 weeks or every Monday", value)
+        })?;
+    Ok(RecurrencePattern::new(RecurrenceFrequency::Weekly).with_days_of_week(&days))
+}
+
+/// Day number (0=Sunday) of an English weekday name or abbreviation
+fn weekday_number(name: &str) -> Option<u8> {
+    const NAMES: [&str; 7] =
+        ["sunday", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday"];
+    let name = name.strip_suffix('s').filter(|n| n.len() >= 3).unwrap_or(name);
+    NAMES
+        .iter()
+        .position(|day| name.len() >= 3 && day.starts_with(name))
+        .map(|i| i as u8)
+}
+
+/// The first occurrence of `pattern` on or after `from`
+///
+/// Rules without weekdays start on `from` itself; weekday rules start on the
+/// first matching weekday. Returns None if the rule ends before then.
+pub fn first_recurrence(pattern: &RecurrencePattern, from: NaiveDate) -> Option<NaiveDate> {
+    if pattern.count == Some(0) {
+        return None;
+    }
+    let first =
+        if pattern.frequency == RecurrenceFrequency::Weekly && !pattern.days_of_week.is_empty() {
+            (0..7).map(|offset| from + Duration::days(offset)).find(|date| {
+                pattern.days_of_week.contains(&(date.weekday().num_days_from_sunday() as u8))
+            })?
+        } else {
+            from
+        };
+    if let Some(end_date) = &pattern.end_date {
+        if NaiveDate::parse_from_str(end_date, "%Y-%m-%d").is_ok_and(|end| first > end) {
+            return None;
+        }
+    }
+    Some(first)
+}
+
+/// The first occurrence of `pattern` after `after`, and the rule for the
+/// occurrences that remain
+///
+/// Frequency, interval, weekdays (weeks start on Monday), end date and count
+/// are honoured. Monthly and yearly rules repeat on the same day of the
+/// month, clamped to the month's last day. Returns None once the rule has ended,
+/// or if the next occurrence is beyond the last representable date.
+pub fn next_recurrence(
+    pattern: &RecurrencePattern,
+    after: NaiveDate,
+) -> Option<(NaiveDate, RecurrencePattern)> {
+    if pattern.count.is_some_and(|count| count <= 1) {
+        return None;
+    }
+    let interval = pattern.interval.max(1);
+    let next = match pattern.frequency {
+        RecurrenceFrequency::Daily => after.checked_add_days(Days::new(interval.into()))?,
+        RecurrenceFrequency::Weekly if pattern.days_of_week.is_empty() => {
+            after.checked_add_days(Days::new(u64::from(interval) * 7))?
+        }
+        RecurrenceFrequency::Weekly => {
+            let matches = |date: &NaiveDate| {
+                pattern.days_of_week.contains(&(date.weekday().num_days_from_sunday() as u8))
+            };
+            let week_start = after - Duration::days(after.weekday().num_days_from_monday() as i64);
+            let rest_of_week = (1..7 - after.weekday().num_days_from_monday() as i64)
+                .map(|offset| after + Duration::days(offset));
+            let next_week_start =
+                week_start.checked_add_days(Days::new(u64::from(interval) * 7))?;
+            let next_week = (0..7).map(|offset| next_week_start + Duration::days(offset));
+            rest_of_week.chain(next_week).find(matches)?
+        }
+        RecurrenceFrequency::Monthly => after.checked_add_months(Months::new(interval))?,
+        RecurrenceFrequency::Yearly => {
+            after.checked_add_months(Months::new(interval.checked_mul(12)?))?
+        }
+    };
+    if let Some(end_date) = &pattern.end_date {
+        if NaiveDate::parse_from_str(end_date, "%Y-%m-%d").is_ok_and(|end| next > end) {
+            return None;
+        }
+    }
+    let mut remaining = pattern.clone();
+    remaining.count = pattern.count.map(|count| count - 1);
+    Some((next, remaining))
+}
+
+/// Build the notes stored in Reminders.app, appending the repeat rule if there is one
+pub fn reminder_body(
+    notes: Option<&str>,
+    recurrence: Option<&RecurrencePattern>,
+) -> Result<String> {
+    let notes = notes.unwrap_or_default().trim_end();
+    Ok(match recurrence {
+        Some(pattern) if notes.is_empty() => format!("{}{}", REPEAT_PREFIX, pattern.to_rrule()?),
+        Some(pattern) => format!("{}\n\n{}{}", notes, REPEAT_PREFIX, pattern.to_rrule()?),
+        None => notes.to_string(),
+    })
+}
+
+/// Split notes read from Reminders.app into the user's notes and the repeat rule
+pub fn split_reminder_body(body: &str) -> (Option<String>, Option<String>) {
+    let mut rule = None;
+    let notes: Vec<&str> = body
+        .lines()
+        .filter(|line| match line.strip_prefix(REPEAT_PREFIX) {
+            Some(value) => {
+                rule = Some(value.trim().to_string());
+                false
+            }
+            None => true,
+        })
+        .collect();
+    let notes = notes.join("\n").trim().to_string();
+    ((!notes.is_empty()).then_some(notes), rule)
+}
+
+/// Separates the fields of one reminder in the output of the reminder scripts
+pub const REMINDER_FIELD_SEPARATOR: char = '\u{1f}';
+/// Terminates each reminder in the output of the reminder scripts
+pub const REMINDER_RECORD_SEPARATOR: char = '\u{1e}';
+
+/// Parse the reminder records printed by the fetch and lookup scripts
+///
+/// Each record holds the id, list, title, completed flag, body, due date,
+/// alert time, priority and flagged state, separated by
+/// `REMINDER_FIELD_SEPARATOR` and terminated by `REMINDER_RECORD_SEPARATOR`.
+/// Text fields escape `\` as `\\` and the two separators as `\u` and `\r`,
+/// so titles and notes may contain any character. The repeat rule is read
+/// from the body; records with the wrong number of fields are skipped.
+pub fn parse_reminder_list_output(output: &str) -> Vec<ReminderItem> {
+    // osascript ends its output with a newline
+    let output = output.strip_suffix('\n').unwrap_or(output);
+    output
+        .split_terminator(REMINDER_RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let fields: Vec<String> =
+                record.split(REMINDER_FIELD_SEPARATOR).map(unescape_reminder_field).collect();
+            let [id, list, title, completed, body, due, alert, priority, flagged] =
+                fields.as_slice()
+            else {
+                debug!("Skipping unreadable reminder record: {:?}", record);
+                return None;
+            };
+            let non_empty = |value: &str| (!value.is_empty()).then(|| value.to_string());
+            let (notes, recurrence) = split_reminder_body(body);
+            Some(ReminderItem {
+                id: non_empty(id),
+                title: title.clone(),
+                lists: vec![list.clone()],
+                reminder_time: non_empty(alert),
+                notes,
+                completed: completed.trim() == "true",
+                due_date: non_empty(due),
+                priority: priority.trim().parse().ok().and_then(ReminderPriority::from_applescript),
+                flagged: flagged.trim() == "true",
+                recurrence,
+            })
+        })
+        .collect()
+}
+
+/// Undo the escaping applied to a field by the reminder scripts
+fn unescape_reminder_field(field: &str) -> String {
+    let mut value = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('u') => value.push(REMINDER_FIELD_SEPARATOR),
+            Some('r') => value.push(REMINDER_RECORD_SEPARATOR),
+            Some(other) => value.push(other),
+            None => value.push('\\'),
+        }
+    }
+    value
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Local;
+    use proptest::prelude::*;
+
+    /// Escape a field the way the `escapeField` AppleScript handler does
+    fn escape(value: &str) -> String {
+        value
+            .replace('\\', "\\\\")
+            .replace(REMINDER_FIELD_SEPARATOR, "\\u")
+            .replace(REMINDER_RECORD_SEPARATOR, "\\r")
+    }
+
+    fn record(fields: &[&str]) -> String {
+        let fields: Vec<String> = fields.iter().map(|field| escape(field)).collect();
+        format!(
+            "{}{}",
+            fields.join(&REMINDER_FIELD_SEPARATOR.to_string()),
+            REMINDER_RECORD_SEPARATOR
+        )
+    }
+
+    /// Text that is likely to break a naive parser, mixed with arbitrary strings
+    fn awkward_text() -> impl Strategy<Value = String> {
+        prop_oneof![any::<String>(), r#"[a-zA-Z0-9 ,;:{}"'\\\n\r\t\x{1e}\x{1f}éüß日本語🦆]{0,40}"#]
+    }
 
     #[test]
     fn test_escape_applescript_string() {
@@ -151,6 +406,194 @@ mod tests {
         assert!(result.contains("03:30:00") || result.contains("3:30:00"));
     }
 
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_reminder_recurrence() {
+        let daily = parse_reminder_recurrence("every day").unwrap();
+        assert_eq!(daily.to_rrule().unwrap(), "FREQ=DAILY;INTERVAL=1");
+
+        let fortnightly = parse_reminder_recurrence("every 2 weeks").unwrap();
+        assert_eq!(fortnightly.to_rrule().unwrap(), "FREQ=WEEKLY;INTERVAL=2");
+
+        let monday = parse_reminder_recurrence("every Monday").unwrap();
+        assert_eq!(monday.to_rrule().unwrap(), "FREQ=WEEKLY;INTERVAL=1;BYDAY=MO");
+
+        let twice = parse_reminder_recurrence("every tue and thursdays").unwrap();
+        assert_eq!(twice.days_of_week, vec![2, 4]);
+
+        let rule = parse_reminder_recurrence("FREQ=MONTHLY;INTERVAL=3").unwrap();
+        assert_eq!(rule.interval, 3);
+
+        assert!(parse_reminder_recurrence("every now and then").is_err());
+        assert!(parse_reminder_recurrence("every 0 days").is_err());
+    }
+
+    #[test]
+    fn test_next_recurrence() {
+        // 2024-06-03 is a Monday
+        let weekly = parse_reminder_recurrence("every Monday and Wednesday").unwrap();
+        assert_eq!(next_recurrence(&weekly, date("2024-06-03")).unwrap().0, date("2024-06-05"));
+        assert_eq!(next_recurrence(&weekly, date("2024-06-05")).unwrap().0, date("2024-06-10"));
+
+        let fortnightly = weekly.clone().with_interval(2);
+        assert_eq!(
+            next_recurrence(&fortnightly, date("2024-06-05")).unwrap().0,
+            date("2024-06-17")
+        );
+
+        let monthly = RecurrencePattern::new(RecurrenceFrequency::Monthly);
+        assert_eq!(next_recurrence(&monthly, date("2024-01-31")).unwrap().0, date("2024-02-29"));
+
+        let limited = RecurrencePattern::new(RecurrenceFrequency::Daily).with_count(2);
+        let (next, remaining) = next_recurrence(&limited, date("2024-06-03")).unwrap();
+        assert_eq!(next, date("2024-06-04"));
+        assert!(next_recurrence(&remaining, next).is_none());
+
+        for rule in ["every 4000000000 days", "every 4000000000 weeks", "every 4000000000 years"] {
+            let huge = parse_reminder_recurrence(rule).unwrap();
+            assert!(next_recurrence(&huge, date("2024-06-03")).is_none(), "{}", rule);
+        }
+
+        let until = RecurrencePattern::new(RecurrenceFrequency::Daily).with_end_date("2024-06-03");
+        assert!(next_recurrence(&until, date("2024-06-03")).is_none());
+    }
+
+    #[test]
+    fn test_first_recurrence() {
+        // 2024-06-03 is a Monday
+        let monthly = parse_reminder_recurrence("monthly").unwrap();
+        assert_eq!(first_recurrence(&monthly, date("2024-06-03")), Some(date("2024-06-03")));
+
+        let weekly = parse_reminder_recurrence("weekly").unwrap();
+        assert_eq!(first_recurrence(&weekly, date("2024-06-03")), Some(date("2024-06-03")));
+
+        let every_two_days = parse_reminder_recurrence("every 2 days").unwrap();
+        assert_eq!(first_recurrence(&every_two_days, date("2024-06-03")), Some(date("2024-06-03")));
+
+        let once = RecurrencePattern::new(RecurrenceFrequency::Daily).with_count(1);
+        assert_eq!(first_recurrence(&once, date("2024-06-03")), Some(date("2024-06-03")));
+
+        let monday = parse_reminder_recurrence("every Monday").unwrap();
+        assert_eq!(first_recurrence(&monday, date("2024-06-03")), Some(date("2024-06-03")));
+        let friday = parse_reminder_recurrence("every Friday").unwrap();
+        assert_eq!(first_recurrence(&friday, date("2024-06-03")), Some(date("2024-06-07")));
+
+        let ended = friday.with_end_date("2024-06-05");
+        assert!(first_recurrence(&ended, date("2024-06-03")).is_none());
+    }
+
+    #[test]
+    fn test_reminder_body_round_trip() {
+        let weekly = parse_reminder_recurrence("every Monday").unwrap();
+        let body = reminder_body(Some("Bring the bins in"), Some(&weekly)).unwrap();
+        assert_eq!(body, "Bring the bins in\n\nRepeats: FREQ=WEEKLY;INTERVAL=1;BYDAY=MO");
+        assert_eq!(
+            split_reminder_body(&body),
+            (
+                Some("Bring the bins in".to_string()),
+                Some("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO".to_string())
+            )
+        );
+        assert_eq!(split_reminder_body("Just notes"), (Some("Just notes".to_string()), None));
+        assert_eq!(reminder_body(None, None).unwrap(), "");
+    }
+
+    #[test]
+    fn test_parse_reminder_list_output() {
+        let output = record(&[
+            "x-1",
+            "Home",
+            "Put the bins out, \"again\"",
+            "false",
+            "Both bins\n\nRepeats: FREQ=WEEKLY;INTERVAL=1;BYDAY=MO",
+            "2024-06-03",
+            "2024-06-02 20:00",
+            "1",
+            "true",
+        ]);
+        let reminders = parse_reminder_list_output(&format!("{}\n", output));
+        assert_eq!(reminders.len(), 1);
+        let bins = &reminders[0];
+        assert_eq!(bins.id.as_deref(), Some("x-1"));
+        assert_eq!(bins.lists, vec!["Home".to_string()]);
+        assert_eq!(bins.title, "Put the bins out, \"again\"");
+        assert_eq!(bins.notes.as_deref(), Some("Both bins"));
+        assert_eq!(bins.recurrence.as_deref(), Some("FREQ=WEEKLY;INTERVAL=1;BYDAY=MO"));
+        assert_eq!(bins.due_date.as_deref(), Some("2024-06-03"));
+        assert_eq!(bins.reminder_time.as_deref(), Some("2024-06-02 20:00"));
+        assert_eq!(bins.priority, Some(ReminderPriority::High));
+        assert!(bins.flagged);
+        assert!(!bins.completed);
+
+        let plain = record(&["x-2", "Work", "Buy milk", "true", "", "", "", "0", "false"]);
+        let reminders = parse_reminder_list_output(&format!("{}{}", output, plain));
+        assert_eq!(reminders.len(), 2);
+        assert_eq!(reminders[1].notes, None);
+        assert_eq!(reminders[1].due_date, None);
+        assert_eq!(reminders[1].priority, None);
+        assert!(reminders[1].completed);
+
+        assert!(parse_reminder_list_output("").is_empty());
+        assert!(parse_reminder_list_output("\n").is_empty());
+        assert!(parse_reminder_list_output(&record(&["x-1", "Home"])).is_empty());
+    }
+
+    #[test]
+    fn test_unescape_reminder_field() {
+        assert_eq!(unescape_reminder_field("a\\\\b"), "a\\b");
+        assert_eq!(unescape_reminder_field("a\\ub\\rc"), "a\u{1f}b\u{1e}c");
+        assert_eq!(unescape_reminder_field("trailing\\"), "trailing\\");
+    }
+
+    proptest! {
+        #[test]
+        fn prop_reminder_records_round_trip(
+            titles in prop::collection::vec(awkward_text(), 1..4),
+            list in awkward_text(),
+            notes in awkward_text(),
+            completed in any::<bool>(),
+            flagged in any::<bool>(),
+        ) {
+            let output: String = titles
+                .iter()
+                .enumerate()
+                .map(|(i, title)| {
+                    let id = format!("x-{}", i);
+                    record(&[
+                        &id,
+                        &list,
+                        title,
+                        &completed.to_string(),
+                        &notes,
+                        "",
+                        "",
+                        "0",
+                        &flagged.to_string(),
+                    ])
+                })
+                .collect();
+
+            let reminders = parse_reminder_list_output(&format!("{}\n", output));
+            prop_assert_eq!(reminders.len(), titles.len());
+            for (i, (reminder, title)) in reminders.iter().zip(&titles).enumerate() {
+                prop_assert_eq!(reminder.id.clone(), Some(format!("x-{}", i)));
+                prop_assert_eq!(&reminder.title, title);
+                prop_assert_eq!(&reminder.lists, &vec![list.clone()]);
+                prop_assert_eq!(reminder.notes.clone(), split_reminder_body(&notes).0);
+                prop_assert_eq!(reminder.completed, completed);
+                prop_assert_eq!(reminder.flagged, flagged);
+            }
+        }
+
+        #[test]
+        fn prop_unescape_reverses_escape(value in awkward_text()) {
+            prop_assert_eq!(unescape_reminder_field(&escape(&value)), value);
+        }
+    }
+
     #[test]
     fn test_resolve_relative_date() {
         let now = Local::now();
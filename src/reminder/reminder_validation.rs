@@ -3,9 +3,14 @@
 // This module provides validation for todo/reminder operations
 
 use super::reminder_types::ReminderError;
+use crate::calendar::RecurrencePattern;
 use anyhow::{Result, anyhow};
+use chrono::{NaiveDate, NaiveTime};
 use log::warn;
 
+/// Largest repeat interval accepted for a reminder, such as "every 1000 days"
+pub const MAX_REPEAT_INTERVAL: u32 = 1000;
+
 /// Validate a reminder title
 pub fn validate_title(title: &str) -> Result<()> {
     if title.is_empty() {
@@ -70,6 +75,57 @@ pub fn validate_reminder_time(time: &str) -> Result<()> {
     Ok(())
 }
 
+/// Validate a due date given as YYYY-MM-DD or YYYY-MM-DD HH:MM
+pub fn validate_due_date(due_date: &str) -> Result<()> {
+    let valid = match due_date.trim().split_once(' ') {
+        Some((date, time)) => {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+                && NaiveTime::parse_from_str(time.trim(), "%H:%M").is_ok()
+        }
+        None => NaiveDate::parse_from_str(due_date.trim(), "%Y-%m-%d").is_ok(),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!(ReminderError::InvalidInput(format!(
+            "Invalid due date: '{}'. Expected format: YYYY-MM-DD or YYYY-MM-DD HH:MM",
+            due_date
+        ))))
+    }
+}
+
+/// Validate the repeat rule of a reminder
+pub fn validate_recurrence(recurrence: &RecurrencePattern) -> Result<()> {
+    if recurrence.interval == 0 {
+        return Err(anyhow!(ReminderError::InvalidInput(
+            "Repeat interval must be at least 1".to_string()
+        )));
+    }
+    if recurrence.interval > MAX_REPEAT_INTERVAL {
+        return Err(anyhow!(ReminderError::InvalidInput(format!(
+            "Repeat interval must be at most {}",
+            MAX_REPEAT_INTERVAL
+        ))));
+    }
+    if recurrence.days_of_week.iter().any(|&day| day > 6) {
+        return Err(anyhow!(ReminderError::InvalidInput("Invalid repeat weekday".to_string())));
+    }
+    if let Some(end_date) = &recurrence.end_date {
+        if NaiveDate::parse_from_str(end_date, "%Y-%m-%d").is_err() {
+            return Err(anyhow!(ReminderError::InvalidInput(format!(
+                "Invalid repeat end date: '{}'",
+                end_date
+            ))));
+        }
+    }
+    if recurrence.count == Some(0) {
+        return Err(anyhow!(ReminderError::InvalidInput(
+            "Repeat count must be at least 1".to_string()
+        )));
+    }
+    Ok(())
+}
+
 /// Validate a reminder list name
 pub fn validate_list_name(name: &str) -> Result<()> {
     if name.is_empty() {
@@ -92,6 +148,14 @@ pub fn validate_reminder_config(config: &super::ReminderConfig<'_>) -> Result<()
         validate_reminder_time(time_str)?;
     }
 
+    // Validate due date and repeat rule if provided
+    if let Some(due_date) = config.due_date {
+        validate_due_date(due_date)?;
+    }
+    if let Some(recurrence) = &config.recurrence {
+        validate_recurrence(recurrence)?;
+    }
+
     // Validate list names
     for list in &config.lists {
         validate_list_name(list)?;
@@ -124,6 +188,28 @@ mod tests {
         assert!(validate_reminder_time("2025/04/15 14:30").is_err()); // wrong format
     }
 
+    #[test]
+    fn test_validate_due_date() {
+        assert!(validate_due_date("2025-04-15").is_ok());
+        assert!(validate_due_date("2025-04-15 09:30").is_ok());
+        assert!(validate_due_date("2025-02-30").is_err());
+        assert!(validate_due_date("2025-04-15 25:00").is_err());
+        assert!(validate_due_date("next week").is_err());
+    }
+
+    #[test]
+    fn test_validate_recurrence() {
+        use crate::calendar::RecurrenceFrequency;
+
+        let weekly = RecurrencePattern::new(RecurrenceFrequency::Weekly).with_days_of_week(&[1]);
+        assert!(validate_recurrence(&weekly).is_ok());
+        assert!(validate_recurrence(&weekly.clone().with_interval(0)).is_err());
+        assert!(validate_recurrence(&weekly.clone().with_interval(1000)).is_ok());
+        assert!(validate_recurrence(&weekly.clone().with_interval(4_000_000_000)).is_err());
+        assert!(validate_recurrence(&weekly.clone().with_days_of_week(&[7])).is_err());
+        assert!(validate_recurrence(&weekly.with_end_date("June")).is_err());
+    }
+
     #[test]
     fn test_validate_list_name() {
         assert!(validate_list_name("Work").is_ok());
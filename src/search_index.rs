@@ -0,0 +1,566 @@
+//! Full-text search over local notes, events and reminders.
+//
+// `SearchIndex` is an inverted index from lower-cased word tokens to the
+// positions at which they appear in each document's title and body. Queries
+// are a list of words, quoted phrases and `field:value` filters, all of which a
+// document must match; results are ranked by how often the words occur,
+// weighted by rarity and with title matches counting more than body matches.
+//
+// The process-wide index behind `search` is built lazily from the local note
+// store and the `StateManager` files, then kept current by `index_document`
+// and `sync_source` as those are written.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::state::{CalendarItem, NoteItem, ReminderItem, StateManager};
+use crate::storage::notes::{Note, NotesStorage};
+
+/// Title matches count this many times more than body matches
+const TITLE_WEIGHT: f64 = 2.0;
+
+/// What a search document was built from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentKind {
+    Note,
+    Event,
+    Reminder,
+}
+
+impl DocumentKind {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "note" | "notes" => Some(Self::Note),
+            "event" | "events" | "calendar" => Some(Self::Event),
+            "reminder" | "reminders" | "todo" => Some(Self::Reminder),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DocumentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Note => write!(f, "note"),
+            Self::Event => write!(f, "event"),
+            Self::Reminder => write!(f, "reminder"),
+        }
+    }
+}
+
+/// A searchable item
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchDocument {
+    /// Unique key of the document within the index
+    pub id: String,
+    pub kind: DocumentKind,
+    pub title: String,
+    /// Folders, calendars or lists the item belongs to, matched by `folder:`
+    pub folders: Vec<String>,
+    pub body: String,
+}
+
+impl SearchDocument {
+    /// A document for a note in the local note store
+    pub fn from_note(note: &Note) -> Self {
+        Self {
+            id: format!("note:{}", note.id),
+            kind: DocumentKind::Note,
+            title: note.title.clone(),
+            folders: vec![note.folder_name().to_string()],
+            body: note.content.clone(),
+        }
+    }
+
+    /// A document for a note recorded in the state directory
+    pub fn from_note_item(note: &NoteItem) -> Self {
+        Self {
+            id: String::new(),
+            kind: DocumentKind::Note,
+            title: note.title.clone(),
+            folders: Some(note.folder.clone()).into_iter().filter(|f| !f.is_empty()).collect(),
+            body: note.content.clone().unwrap_or_default(),
+        }
+    }
+
+    /// A document for an event recorded in the state directory
+    pub fn from_event(event: &CalendarItem) -> Self {
+        let body = [&event.location, &event.description, &event.email]
+            .into_iter()
+            .flatten()
+            .chain([&event.date])
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            id: String::new(),
+            kind: DocumentKind::Event,
+            title: event.title.clone(),
+            folders: event.calendars.clone(),
+            body,
+        }
+    }
+
+    /// A document for a reminder recorded in the state directory
+    pub fn from_reminder(reminder: &ReminderItem) -> Self {
+        Self {
+            id: String::new(),
+            kind: DocumentKind::Reminder,
+            title: reminder.title.clone(),
+            folders: reminder.lists.clone(),
+            body: reminder.notes.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// A document that matched a query, with its relevance score
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub document: SearchDocument,
+    pub score: f64,
+}
+
+/// Split text into lower-cased alphanumeric words
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Where a term occurs in one document, as word positions
+#[derive(Debug, Clone, Default)]
+struct Postings {
+    title: Vec<usize>,
+    body: Vec<usize>,
+}
+
+/// The part of a document a query word is matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Title,
+    Any,
+}
+
+/// A word or phrase that must occur in a matching document
+#[derive(Debug, Clone, PartialEq)]
+struct TextClause {
+    field: Field,
+    /// One word, or several for a phrase
+    words: Vec<String>,
+}
+
+/// A parsed search query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    clauses: Vec<TextClause>,
+    folders: Vec<String>,
+    kinds: Vec<DocumentKind>,
+}
+
+impl SearchQuery {
+    /// Parse words, `"quoted phrases"`, `title:word`, `title:"a phrase"`,
+    /// `folder:name` (or `folder:"a name"`) and `type:note|event|reminder`
+    pub fn parse(query: &str) -> Result<Self> {
+        let mut parsed = Self::default();
+        for (field, value) in split_query(query)? {
+            match field.as_deref().map(str::to_lowercase).as_deref() {
+                Some("folder") | Some("list") | Some("calendar") => {
+                    parsed.folders.push(value.trim().to_string())
+                }
+                Some("type") | Some("kind") => {
+                    parsed.kinds.push(DocumentKind::parse(&value).ok_or_else(|| {
+                        anyhow!("Unknown type '{}'. Use note, event or reminder", value)
+                    })?)
+                }
+                Some("title") => parsed.push_text(Field::Title, &value),
+                Some(other) => parsed.push_text(Field::Any, &format!("{}:{}", other, value)),
+                None => parsed.push_text(Field::Any, &value),
+            }
+        }
+        if parsed.clauses.is_empty() && parsed.folders.is_empty() && parsed.kinds.is_empty() {
+            return Err(anyhow!("Search query is empty"));
+        }
+        Ok(parsed)
+    }
+
+    fn push_text(&mut self, field: Field, value: &str) {
+        let words = tokenize(value);
+        if !words.is_empty() {
+            self.clauses.push(TextClause { field, words });
+        }
+    }
+
+    fn accepts(&self, document: &SearchDocument) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&document.kind))
+            && self
+                .folders
+                .iter()
+                .all(|folder| document.folders.iter().any(|f| f.eq_ignore_ascii_case(folder)))
+    }
+}
+
+/// Split a query into optional `field:` prefixes and values, honouring double quotes
+fn split_query(query: &str) -> Result<Vec<(Option<String>, String)>> {
+    let mut parts = Vec::new();
+    let mut chars = query.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(parts);
+        }
+        let mut field = None;
+        let mut value = String::new();
+        let mut quoted = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted => break,
+                '"' if value.is_empty() => quoted = true,
+                ':' if !quoted && field.is_none() && !value.is_empty() => {
+                    field = Some(std::mem::take(&mut value));
+                }
+                c if c.is_whitespace() && !quoted => break,
+                c => value.push(c),
+            }
+            if quoted && chars.peek().is_none() {
+                return Err(anyhow!("Unclosed quote in search query"));
+            }
+        }
+        parts.push((field, value));
+    }
+}
+
+/// An inverted index over search documents
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    documents: HashMap<String, SearchDocument>,
+    terms: HashMap<String, HashMap<String, Postings>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of indexed documents
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Add a document, replacing any with the same ID
+    pub fn add(&mut self, document: SearchDocument) {
+        self.remove(&document.id);
+        for (position, word) in tokenize(&document.title).into_iter().enumerate() {
+            self.postings(word, &document.id).title.push(position);
+        }
+        for (position, word) in tokenize(&document.body).into_iter().enumerate() {
+            self.postings(word, &document.id).body.push(position);
+        }
+        self.documents.insert(document.id.clone(), document);
+    }
+
+    fn postings(&mut self, word: String, id: &str) -> &mut Postings {
+        self.terms.entry(word).or_default().entry(id.to_string()).or_default()
+    }
+
+    /// Remove the document with the given ID, returning whether it was indexed
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(document) = self.documents.remove(id) else {
+            return false;
+        };
+        let mut words = tokenize(&document.title);
+        words.extend(tokenize(&document.body));
+        for word in words {
+            if let Some(postings) = self.terms.get_mut(&word) {
+                postings.remove(id);
+                if postings.is_empty() {
+                    self.terms.remove(&word);
+                }
+            }
+        }
+        true
+    }
+
+    /// Remove every document whose ID starts with `prefix`
+    pub fn remove_prefix(&mut self, prefix: &str) {
+        let ids: Vec<String> =
+            self.documents.keys().filter(|id| id.starts_with(prefix)).cloned().collect();
+        for id in ids {
+            self.remove(&id);
+        }
+    }
+
+    /// Documents matching every part of the query, best match first
+    pub fn search(&self, query: &SearchQuery) -> Vec<SearchHit> {
+        let mut hits: Vec<SearchHit> = self
+            .documents
+            .values()
+            .filter(|document| query.accepts(document))
+            .filter_map(|document| {
+                let mut score = 0.0;
+                for clause in &query.clauses {
+                    score += self.clause_score(clause, &document.id)?;
+                }
+                Some(SearchHit { document: document.clone(), score })
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.document.title.cmp(&b.document.title))
+                .then_with(|| a.document.id.cmp(&b.document.id))
+        });
+        hits
+    }
+
+    /// Inverse document frequency of a word, higher for rarer words
+    fn idf(&self, word: &str) -> f64 {
+        let total = self.documents.len() as f64;
+        let containing = self.terms.get(word).map_or(0, HashMap::len) as f64;
+        (1.0 + (total - containing + 0.5) / (containing + 0.5)).ln()
+    }
+
+    /// Score of one clause for a document, or None if the document does not match it
+    fn clause_score(&self, clause: &TextClause, id: &str) -> Option<f64> {
+        let postings: Vec<&Postings> = clause
+            .words
+            .iter()
+            .map(|word| self.terms.get(word).and_then(|docs| docs.get(id)))
+            .collect::<Option<_>>()?;
+        let title = phrase_count(postings.iter().map(|p| p.title.as_slice()));
+        let body = match clause.field {
+            Field::Title => 0,
+            Field::Any => phrase_count(postings.iter().map(|p| p.body.as_slice())),
+        };
+        if title + body == 0 {
+            return None;
+        }
+        let weight = |count: usize| if count == 0 { 0.0 } else { 1.0 + (count as f64).ln() };
+        let idf: f64 = clause.words.iter().map(|word| self.idf(word)).sum();
+        Some(idf * (TITLE_WEIGHT * weight(title) + weight(body)))
+    }
+}
+
+/// How many times the words occur consecutively, given each word's positions
+fn phrase_count<'a>(mut positions: impl Iterator<Item = &'a [usize]>) -> usize {
+    let Some(first) = positions.next() else {
+        return 0;
+    };
+    let rest: Vec<&[usize]> = positions.collect();
+    first
+        .iter()
+        .filter(|&&start| {
+            rest.iter()
+                .enumerate()
+                .all(|(offset, word)| word.contains(&(start + offset + 1)))
+        })
+        .count()
+}
+
+static INDEX: Lazy<Mutex<Option<SearchIndex>>> = Lazy::new(|| Mutex::new(None));
+
+/// Build an index of the local note store and the state directory files
+pub fn build_index() -> Result<SearchIndex> {
+    let mut index = SearchIndex::new();
+    for note in NotesStorage::new()?.list_notes()? {
+        index.add(SearchDocument::from_note(&note));
+    }
+    let state = StateManager::new()?;
+    add_source(&mut index, &state.load::<CalendarItem>()?);
+    add_source(&mut index, &state.load::<NoteItem>()?);
+    add_source(&mut index, &state.load::<ReminderItem>()?);
+    log::debug!("Built search index of {} documents", index.len());
+    Ok(index)
+}
+
+fn add_source<T: crate::state::Persistent>(index: &mut SearchIndex, items: &[T]) {
+    for (position, item) in items.iter().enumerate() {
+        if let Some(document) = source_document(item, position) {
+            index.add(document);
+        }
+    }
+}
+
+/// The document for the item at `position` in a state file
+fn source_document<T: crate::state::Persistent>(
+    item: &T,
+    position: usize,
+) -> Option<SearchDocument> {
+    let mut document = item.search_document()?;
+    document.id = format!("{}#{}", T::filename(), position);
+    Some(document)
+}
+
+/// Run `f` on the shared index, building it first if needed
+fn with_index<R>(f: impl FnOnce(&mut SearchIndex) -> R) -> Result<R> {
+    if INDEX.lock().unwrap_or_else(|e| e.into_inner()).is_none() {
+        // Build without holding the lock, since loading state can write to it
+        // (migrating old files, or filling a new SQLite database)
+        let index = build_index()?;
+        INDEX.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert(index);
+    }
+    let mut guard = INDEX.lock().unwrap_or_else(|e| e.into_inner());
+    let index = guard.get_or_insert_with(SearchIndex::new);
+    Ok(f(index))
+}
+
+/// Search the local notes, events and reminders
+pub fn search(query: &SearchQuery) -> Result<Vec<SearchHit>> {
+    with_index(|index| index.search(query))
+}
+
+/// Replace the documents whose IDs start with `prefix`, for sources such as
+/// Notes.app that can only be re-read as a whole
+pub fn sync_documents(prefix: &str, documents: Vec<SearchDocument>) -> Result<()> {
+    with_index(|index| {
+        index.remove_prefix(prefix);
+        for document in documents {
+            index.add(document);
+        }
+    })
+}
+
+/// Add or replace a document in the index, if it has been built
+pub fn index_document(document: SearchDocument) {
+    if let Some(index) = INDEX.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        index.add(document);
+    }
+}
+
+/// Remove a document from the index, if it has been built
+pub fn remove_document(id: &str) {
+    if let Some(index) = INDEX.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        index.remove(id);
+    }
+}
+
+/// Replace the indexed items of a state file after it has been saved
+pub fn sync_source<T: crate::state::Persistent>(items: &[T]) {
+    if let Some(index) = INDEX.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        index.remove_prefix(&format!("{}#", T::filename()));
+        add_source(index, items);
+    }
+}
+
+/// Add an item appended to a state file at `position`, if the index has been built
+pub fn index_source_item<T: crate::state::Persistent>(item: &T, position: usize) {
+    if let Some(document) = source_document(item, position) {
+        index_document(document);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(
+        id: &str,
+        kind: DocumentKind,
+        title: &str,
+        folder: &str,
+        body: &str,
+    ) -> SearchDocument {
+        SearchDocument {
+            id: id.to_string(),
+            kind,
+            title: title.to_string(),
+            folders: vec![folder.to_string()],
+            body: body.to_string(),
+        }
+    }
+
+    fn sample_index() -> SearchIndex {
+        let mut index = SearchIndex::new();
+        index.add(document(
+            "1",
+            DocumentKind::Note,
+            "Weekly sync",
+            "Work",
+            "Ship the release notes",
+        ));
+        index.add(document("2", DocumentKind::Note, "Release plan", "Work", "Notes on the plan"));
+        index.add(document("3", DocumentKind::Note, "Groceries", "Home", "Milk, eggs, release"));
+        index.add(document("4", DocumentKind::Event, "Release party", "Team", "Rooftop"));
+        index
+    }
+
+    fn ids(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.document.id.as_str()).collect()
+    }
+
+    fn search(index: &SearchIndex, query: &str) -> Vec<SearchHit> {
+        index.search(&SearchQuery::parse(query).unwrap())
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("Ship v2, then RÉSUMÉ-review!"),
+            ["ship", "v2", "then", "résumé", "review"]
+        );
+        assert!(tokenize(" -- ").is_empty());
+    }
+
+    #[test]
+    fn test_search_ranks_title_matches_first() {
+        let index = sample_index();
+        let hits = search(&index, "release");
+        // Equal scores are ordered by title
+        assert_eq!(ids(&hits)[..2], ["4", "2"]);
+        assert_eq!(hits.len(), 4);
+        assert!(hits[1].score > hits[2].score);
+
+        // All words must match
+        assert_eq!(ids(&search(&index, "release plan")), ["2"]);
+        assert!(search(&index, "release banana").is_empty());
+    }
+
+    #[test]
+    fn test_search_phrases_and_fields() {
+        let index = sample_index();
+        assert_eq!(ids(&search(&index, "\"release notes\"")), ["1"]);
+        assert_eq!(ids(&search(&index, "title:release folder:work")), ["2"]);
+        assert_eq!(ids(&search(&index, "title:\"release party\"")), ["4"]);
+        assert_eq!(ids(&search(&index, "release type:event")), ["4"]);
+        assert_eq!(ids(&search(&index, "folder:home")), ["3"]);
+        assert!(search(&index, "title:notes").is_empty());
+    }
+
+    #[test]
+    fn test_index_updates_incrementally() {
+        let mut index = sample_index();
+        assert!(index.remove("2"));
+        assert!(!index.remove("2"));
+        assert_eq!(ids(&search(&index, "plan")), Vec::<&str>::new());
+
+        index.add(document("1", DocumentKind::Note, "Retro", "Work", "What went well"));
+        assert!(search(&index, "weekly").is_empty());
+        assert_eq!(ids(&search(&index, "retro")), ["1"]);
+        assert_eq!(index.len(), 3);
+
+        index.remove_prefix("");
+        assert!(index.is_empty());
+        assert!(index.terms.is_empty());
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert!(SearchQuery::parse("  ").is_err());
+        assert!(SearchQuery::parse("\"unclosed phrase").is_err());
+        assert!(SearchQuery::parse("type:meeting").is_err());
+
+        let query = SearchQuery::parse("folder:\"Team notes\" http://x.com").unwrap();
+        assert_eq!(query.folders, ["Team notes"]);
+        assert_eq!(query.clauses[0].words, ["http", "x", "com"]);
+    }
+}
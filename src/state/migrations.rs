@@ -0,0 +1,216 @@
+//! Upgrades for state files written by older versions of DuckTape.
+//
+// State files start with a schema version header, `{"schema_version": 1,
+// "items": [...]}`. Files written before the header existed are a bare JSON
+// array and count as version 0. Each `Migration` upgrades the items of one
+// file by one version; `LegacyFile`s are files older versions kept items in
+// that now belong to another state file, such as todos.json.
+
+use std::fmt;
+
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value};
+
+use super::{EVENTS_FILE, NOTES_FILE, REMINDERS_FILE};
+
+// Version of the state files this build writes
+pub const SCHEMA_VERSION: u32 = 1;
+
+// Upgrades the items of one state file from `from_version` to the next version
+pub struct Migration {
+    pub filename: &'static str,
+    pub from_version: u32,
+    pub description: &'static str,
+    upgrade: fn(&mut Map<String, Value>),
+}
+
+// A file from an older version whose items now belong in `into`
+pub struct LegacyFile {
+    pub filename: &'static str,
+    pub into: &'static str,
+    convert: fn(&Value) -> Option<Value>,
+}
+
+static MIGRATIONS: &[Migration] = &[
+    Migration {
+        filename: EVENTS_FILE,
+        from_version: 0,
+        description: "add the schema version header",
+        upgrade: |_| {},
+    },
+    Migration {
+        filename: NOTES_FILE,
+        from_version: 0,
+        description: "rename created_at to created and give notes without a folder an empty one",
+        upgrade: upgrade_note_v0,
+    },
+    Migration {
+        filename: REMINDERS_FILE,
+        from_version: 0,
+        description: "add the schema version header",
+        upgrade: |_| {},
+    },
+];
+
+static LEGACY_FILES: &[LegacyFile] =
+    &[LegacyFile { filename: "todos.json", into: REMINDERS_FILE, convert: todo_to_reminder }];
+
+// A change made, or that would be made, by `StateManager::migrate`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStep {
+    pub filename: String,
+    pub description: String,
+}
+
+impl fmt::Display for MigrationStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.filename, self.description)
+    }
+}
+
+// Upgrade items read from `filename` at `version` to `SCHEMA_VERSION`,
+// returning the steps taken
+pub fn upgrade(filename: &str, version: u32, items: &mut [Value]) -> Result<Vec<MigrationStep>> {
+    if version > SCHEMA_VERSION {
+        return Err(anyhow!(
+            "{} was written by a newer version of DuckTape (schema version {}, this version reads up to {})",
+            filename,
+            version,
+            SCHEMA_VERSION
+        ));
+    }
+
+    let mut steps = Vec::new();
+    for from_version in version..SCHEMA_VERSION {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|m| m.filename == filename && m.from_version == from_version)
+            .ok_or_else(|| {
+                anyhow!("No migration for {} from schema version {}", filename, from_version)
+            })?;
+        for item in items.iter_mut() {
+            let object = item.as_object_mut().ok_or_else(|| {
+                anyhow!("Unexpected item in {}: expected a JSON object", filename)
+            })?;
+            (migration.upgrade)(object);
+        }
+        steps.push(MigrationStep {
+            filename: filename.to_string(),
+            description: format!(
+                "schema version {} to {}: {}",
+                from_version,
+                from_version + 1,
+                migration.description
+            ),
+        });
+    }
+    Ok(steps)
+}
+
+// Legacy files whose items are moved into `filename`
+pub fn legacy_files(filename: &str) -> impl Iterator<Item = &'static LegacyFile> + '_ {
+    LEGACY_FILES.iter().filter(move |legacy| legacy.into == filename)
+}
+
+impl LegacyFile {
+    // Convert the items of the legacy file into items of `into`, skipping
+    // any that cannot be read
+    pub fn convert(&self, items: &[Value]) -> Vec<Value> {
+        items
+            .iter()
+            .filter_map(|item| {
+                let converted = (self.convert)(item);
+                if converted.is_none() {
+                    log::warn!("Skipping unreadable item in {}: {}", self.filename, item);
+                }
+                converted
+            })
+            .collect()
+    }
+}
+
+fn upgrade_note_v0(note: &mut Map<String, Value>) {
+    if let Some(created) = note.remove("created_at") {
+        note.entry("created").or_insert(created);
+    }
+    if note.get("folder").is_none_or(Value::is_null) {
+        note.insert("folder".to_string(), Value::String(String::new()));
+    }
+}
+
+// TodoItem had the same fields as ReminderItem
+fn todo_to_reminder(todo: &Value) -> Option<Value> {
+    let title = todo.get("title")?.as_str()?;
+    let lists: Vec<Value> = todo
+        .get("lists")?
+        .as_array()?
+        .iter()
+        .filter(|list| list.is_string())
+        .cloned()
+        .collect();
+    let text = |key: &str| todo.get(key).and_then(Value::as_str).map(String::from);
+
+    Some(serde_json::json!({
+        "title": title,
+        "notes": text("notes"),
+        "lists": lists,
+        "reminder_time": text("reminder_time"),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_upgrade_old_notes() -> Result<()> {
+        let mut notes = vec![json!({"title": "Plan", "folder": null, "created_at": "2024-06-03"})];
+        let steps = upgrade(NOTES_FILE, 0, &mut notes)?;
+
+        assert_eq!(steps.len(), 1);
+        assert_eq!(notes[0], json!({"title": "Plan", "folder": "", "created": "2024-06-03"}));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upgrade_current_version_does_nothing() -> Result<()> {
+        let mut events = vec![json!({"title": "Standup"})];
+        assert!(upgrade(EVENTS_FILE, SCHEMA_VERSION, &mut events)?.is_empty());
+        assert_eq!(events[0], json!({"title": "Standup"}));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upgrade_rejects_newer_version() {
+        let error = upgrade(REMINDERS_FILE, SCHEMA_VERSION + 1, &mut []).unwrap_err();
+        assert!(error.to_string().contains("newer version"));
+    }
+
+    #[test]
+    fn test_every_file_upgrades_to_current_version() {
+        for filename in [EVENTS_FILE, NOTES_FILE, REMINDERS_FILE] {
+            assert!(upgrade(filename, 0, &mut []).is_ok(), "{} has a gap", filename);
+        }
+    }
+
+    #[test]
+    fn test_convert_todos() {
+        let legacy = legacy_files(REMINDERS_FILE).next().expect("todos.json is registered");
+        let todos = vec![
+            json!({"title": "Buy milk", "lists": ["Home"], "notes": "2%", "completed": false}),
+            json!({"lists": ["Home"]}),
+        ];
+
+        let reminders = legacy.convert(&todos);
+        assert_eq!(
+            reminders,
+            vec![json!({
+                "title": "Buy milk",
+                "notes": "2%",
+                "lists": ["Home"],
+                "reminder_time": null,
+            })]
+        );
+    }
+}
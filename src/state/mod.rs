@@ -0,0 +1,937 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::config::{Config, StateBackend};
+use crate::search_index::SearchDocument;
+
+pub mod migrations;
+pub mod sqlite;
+
+pub use migrations::{MigrationStep, SCHEMA_VERSION};
+use sqlite::SqliteState;
+
+const STATE_DIR: &str = ".ducktape";
+// TODOS_FILE constant removed in version 0.17.0, use REMINDERS_FILE instead
+const EVENTS_FILE: &str = "events.json";
+const NOTES_FILE: &str = "notes.json";
+const REMINDERS_FILE: &str = "reminders.json";
+// Maximum allowed size for state files to prevent DoS attacks (10MB)
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+// Maximum number of items in a state file
+const MAX_ITEMS: usize = 10000;
+
+// Trait for items that can be persisted
+pub trait Persistent: Sized + Serialize + for<'de> Deserialize<'de> {
+    fn filename() -> &'static str;
+
+    // How the item appears in the full-text search index, if at all
+    fn search_document(&self) -> Option<SearchDocument> {
+        None
+    }
+
+    // Title and date the SQLite store indexes the item by
+    fn index_title(&self) -> &str {
+        ""
+    }
+
+    fn index_date(&self) -> Option<NaiveDate> {
+        None
+    }
+}
+
+// The date at the start of a "YYYY-MM-DD..." timestamp
+fn date_prefix(timestamp: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(timestamp.get(..10)?, "%Y-%m-%d").ok()
+}
+
+// TodoItem struct removed in version 0.17.0, use ReminderItem instead
+
+// Make the structs public and cloneable
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReminderItem {
+    pub title: String,
+    pub notes: Option<String>,
+    pub lists: Vec<String>,
+    pub reminder_time: Option<String>,
+}
+
+// Make the structs public and cloneable
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CalendarItem {
+    pub title: String,
+    pub date: String,
+    pub time: String,
+    pub calendars: Vec<String>,
+    pub all_day: bool,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub email: Option<String>,
+    pub reminder: Option<i32>,
+}
+
+// Notes are stored with the same type the notes module uses
+pub use crate::notes::NoteItem;
+
+impl Persistent for CalendarItem {
+    fn filename() -> &'static str {
+        EVENTS_FILE
+    }
+
+    fn search_document(&self) -> Option<SearchDocument> {
+        Some(SearchDocument::from_event(self))
+    }
+
+    fn index_title(&self) -> &str {
+        &self.title
+    }
+
+    fn index_date(&self) -> Option<NaiveDate> {
+        date_prefix(&self.date)
+    }
+}
+
+impl Persistent for NoteItem {
+    fn filename() -> &'static str {
+        NOTES_FILE
+    }
+
+    fn search_document(&self) -> Option<SearchDocument> {
+        Some(SearchDocument::from_note_item(self))
+    }
+
+    fn index_title(&self) -> &str {
+        &self.title
+    }
+
+    fn index_date(&self) -> Option<NaiveDate> {
+        self.created.as_deref().and_then(date_prefix)
+    }
+}
+
+impl Persistent for ReminderItem {
+    fn filename() -> &'static str {
+        REMINDERS_FILE
+    }
+
+    fn search_document(&self) -> Option<SearchDocument> {
+        Some(SearchDocument::from_reminder(self))
+    }
+
+    fn index_title(&self) -> &str {
+        &self.title
+    }
+
+    fn index_date(&self) -> Option<NaiveDate> {
+        self.reminder_time.as_deref().and_then(date_prefix)
+    }
+}
+
+// The header written at the start of every state file
+#[derive(Serialize)]
+struct StateFile<'a, T> {
+    schema_version: u32,
+    items: &'a [T],
+}
+
+static BACKEND: Lazy<RwLock<StateBackend>> = Lazy::new(|| {
+    let backend = Config::load().map(|config| config.state.backend).unwrap_or_else(|e| {
+        log::warn!("Failed to load config, using the default state backend: {}", e);
+        StateBackend::default()
+    });
+    RwLock::new(backend)
+});
+
+// The backend `StateManager::new` uses, from the `state.backend` setting
+pub fn backend() -> StateBackend {
+    match BACKEND.read() {
+        Ok(guard) => *guard,
+        Err(poisoned) => *poisoned.into_inner(),
+    }
+}
+
+// Change the backend used by state managers created after this call
+pub fn set_backend(backend: StateBackend) {
+    match BACKEND.write() {
+        Ok(mut guard) => *guard = backend,
+        Err(poisoned) => *poisoned.into_inner() = backend,
+    }
+}
+
+// Writes go to a temporary file that is renamed over the state file, so readers
+// never see a partial file and a crash leaves the previous version in place.
+// Writers also hold an advisory lock on a `.<file>.lock` file next to it, so the
+// API server and the terminal can update the same state without losing changes.
+//
+// Files from older versions are upgraded the first time they are loaded, using
+// the registry in `migrations`; the previous file is kept as `<file>.v<N>.bak`.
+//
+// With the SQLite backend the items live in `state.db` instead (see `sqlite`),
+// which is filled from the JSON files when it is first created.
+pub struct StateManager {
+    state_dir: PathBuf,
+    database: Option<SqliteState>,
+}
+
+impl StateManager {
+    pub fn new() -> Result<Self> {
+        Self::with_backend(Self::default_dir()?, backend())
+    }
+
+    // The directory state is kept in, ~/.ducktape
+    pub fn default_dir() -> Result<PathBuf> {
+        let home_dir = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
+        let mut state_dir = home_dir;
+        state_dir.push(STATE_DIR);
+        Ok(state_dir)
+    }
+
+    // Keep JSON state files in `state_dir` instead of ~/.ducktape
+    pub fn with_dir(state_dir: PathBuf) -> Result<Self> {
+        Self::with_backend(state_dir, StateBackend::Json)
+    }
+
+    pub fn with_backend(state_dir: PathBuf, backend: StateBackend) -> Result<Self> {
+        std::fs::create_dir_all(&state_dir)?;
+        let database = match backend {
+            StateBackend::Json => None,
+            StateBackend::Sqlite => {
+                let path = state_dir.join(sqlite::DATABASE_FILE);
+                let created = !path.exists();
+                let manager = Self { database: Some(SqliteState::open(&path)?), state_dir };
+                if created {
+                    if let Err(e) = manager.import_json() {
+                        log::warn!(
+                            "Failed to import JSON state into {:?}, run `ducktape state import` to retry: {}",
+                            path,
+                            e
+                        );
+                    }
+                }
+                return Ok(manager);
+            }
+        };
+        Ok(Self { state_dir, database })
+    }
+
+    // Take the exclusive write lock for a state file; it is released when the
+    // returned file is dropped
+    fn lock(&self, filename: &str) -> Result<File> {
+        let path = self.state_dir.join(format!(".{}.lock", filename));
+        let file = OpenOptions::new().create(true).truncate(false).write(true).open(&path)?;
+        file.lock().map_err(|e| anyhow!("Failed to lock {}: {}", filename, e))?;
+        Ok(file)
+    }
+
+    // Replace a state file with the output of `write`, via a synced temporary file
+    fn write_atomic(
+        &self,
+        filename: &str,
+        write: impl FnOnce(&mut BufWriter<File>) -> Result<()>,
+    ) -> Result<()> {
+        let path = self.state_dir.join(filename);
+        let temp_path = self.state_dir.join(format!(".{}.{}.tmp", filename, std::process::id()));
+        let result = (|| {
+            let mut writer = BufWriter::new(File::create(&temp_path)?);
+            write(&mut writer)?;
+            let file = writer.into_inner().map_err(|e| e.into_error())?;
+            file.sync_all()?;
+            std::fs::rename(&temp_path, &path)?;
+            // Persist the rename itself; not every platform can open a directory
+            if let Ok(dir) = File::open(&self.state_dir) {
+                let _ = dir.sync_all();
+            }
+            Ok(())
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        result
+    }
+
+    fn write_items<T: Persistent>(&self, items: &[T]) -> Result<()> {
+        let file = StateFile { schema_version: SCHEMA_VERSION, items };
+        self.write_atomic(T::filename(), |writer| {
+            serde_json::to_writer_pretty(writer, &file)?;
+            Ok(())
+        })?;
+        // With the SQLite backend only migrations write JSON files, which are
+        // not what the search index shows
+        if self.database.is_none() {
+            crate::search_index::sync_source(items);
+        }
+        Ok(())
+    }
+
+    // Read a state file's schema version and items without converting them;
+    // None if the file does not exist
+    fn read_values(&self, filename: &str) -> Result<Option<(u32, Vec<serde_json::Value>)>> {
+        let path = self.state_dir.join(filename);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        // Check file size before loading to prevent DoS attacks
+        let metadata = std::fs::metadata(&path)?;
+        if metadata.len() > MAX_FILE_SIZE {
+            return Err(anyhow!("File {} exceeds security limits", filename));
+        }
+
+        let reader = BufReader::new(File::open(&path)?);
+        let json_value: serde_json::Value = serde_json::from_reader(reader)
+            .map_err(|e| anyhow!("Failed to parse JSON data in {}: {}", filename, e))?;
+
+        // Files written before the schema version header are a bare array
+        let (version, items) = match json_value {
+            serde_json::Value::Array(items) => (0, items),
+            serde_json::Value::Object(mut file) => {
+                let version = file
+                    .get("schema_version")
+                    .and_then(serde_json::Value::as_u64)
+                    .ok_or_else(|| anyhow!("{} has no schema version", filename))?;
+                match file.remove("items") {
+                    Some(serde_json::Value::Array(items)) => (version as u32, items),
+                    _ => return Err(anyhow!("{} has no items list", filename)),
+                }
+            }
+            _ => return Err(anyhow!("Unrecognized state file format in {}", filename)),
+        };
+
+        // Count elements to prevent DoS attacks
+        if items.len() > MAX_ITEMS {
+            return Err(anyhow!("Too many items in {} (maximum {})", filename, MAX_ITEMS));
+        }
+        Ok(Some((version, items)))
+    }
+
+    // Read the items of a kind, upgrading them in memory if the file is older.
+    // Also returns the file's schema version.
+    fn read_items<T: Persistent>(&self) -> Result<(u32, Vec<T>)> {
+        let Some((version, mut values)) = self.read_values(T::filename())? else {
+            return Ok((SCHEMA_VERSION, Vec::new()));
+        };
+        migrations::upgrade(T::filename(), version, &mut values)?;
+        let items = serde_json::from_value(serde_json::Value::Array(values))
+            .map_err(|e| anyhow!("Failed to deserialize data: {}", e))?;
+        Ok((version, items))
+    }
+
+    fn needs_migration<T: Persistent>(&self, version: u32) -> bool {
+        version < SCHEMA_VERSION
+            || migrations::legacy_files(T::filename())
+                .any(|legacy| self.state_dir.join(legacy.filename).exists())
+    }
+
+    // Upgrade a state file to the current schema and move the items of its
+    // legacy files into it. Callers must hold the file's lock unless `dry_run`.
+    fn migrate_file<T: Persistent>(&self, dry_run: bool) -> Result<Vec<MigrationStep>> {
+        let filename = T::filename();
+        let (version, mut values) =
+            self.read_values(filename)?.unwrap_or((SCHEMA_VERSION, Vec::new()));
+        let mut steps = migrations::upgrade(filename, version, &mut values)?;
+
+        let mut moved = Vec::new();
+        for legacy in migrations::legacy_files(filename) {
+            let Some((_, legacy_values)) = self.read_values(legacy.filename)? else {
+                continue;
+            };
+            let mut count = 0;
+            for item in legacy.convert(&legacy_values) {
+                // Items copied by an earlier, interrupted migration are already there
+                if !values.contains(&item) {
+                    values.push(item);
+                    count += 1;
+                }
+            }
+            steps.push(MigrationStep {
+                filename: legacy.filename.to_string(),
+                description: format!(
+                    "move {} {} into {}",
+                    count,
+                    if count == 1 { "item" } else { "items" },
+                    filename
+                ),
+            });
+            moved.push(legacy.filename);
+        }
+
+        // Check that the upgraded items can be read before changing any files
+        let items: Vec<T> = serde_json::from_value(serde_json::Value::Array(values))
+            .map_err(|e| anyhow!("Failed to deserialize upgraded {}: {}", filename, e))?;
+        if dry_run || steps.is_empty() {
+            return Ok(steps);
+        }
+
+        let path = self.state_dir.join(filename);
+        if path.exists() {
+            let backup = self.state_dir.join(format!("{}.v{}.bak", filename, version));
+            std::fs::copy(&path, backup)?;
+        }
+        self.write_items(&items)?;
+        for legacy in moved {
+            let path = self.state_dir.join(legacy);
+            std::fs::rename(&path, self.state_dir.join(format!("{}.bak", legacy)))?;
+        }
+        log::info!("Migrated {} to schema version {}", filename, SCHEMA_VERSION);
+        Ok(steps)
+    }
+
+    // Read the items of a kind while holding its lock, migrating the file first if needed
+    fn load_locked<T: Persistent>(&self) -> Result<Vec<T>> {
+        let (version, items) = self.read_items::<T>()?;
+        if !self.needs_migration::<T>(version) {
+            return Ok(items);
+        }
+        self.migrate_file::<T>(false)?;
+        Ok(self.read_items::<T>()?.1)
+    }
+
+    pub fn load<T: Persistent>(&self) -> Result<Vec<T>> {
+        if let Some(database) = &self.database {
+            return database.load();
+        }
+        let (version, items) = self.read_items::<T>()?;
+        if !self.needs_migration::<T>(version) {
+            return Ok(items);
+        }
+        let _lock = self.lock(T::filename())?;
+        self.load_locked()
+    }
+
+    // Items whose date (see `Persistent::index_date`) is between `from` and
+    // `to`, inclusive, oldest first
+    pub fn load_between<T: Persistent>(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<T>> {
+        if let Some(database) = &self.database {
+            return database.load_between(from, to);
+        }
+        let mut items: Vec<T> = self
+            .load::<T>()?
+            .into_iter()
+            .filter(|item| item.index_date().is_some_and(|date| date >= from && date <= to))
+            .collect();
+        items.sort_by_key(|item| item.index_date());
+        Ok(items)
+    }
+
+    // Items with the given title, ignoring ASCII case
+    pub fn find_by_title<T: Persistent>(&self, title: &str) -> Result<Vec<T>> {
+        if let Some(database) = &self.database {
+            return database.find_by_title(title);
+        }
+        let mut items = self.load::<T>()?;
+        items.retain(|item| item.index_title().eq_ignore_ascii_case(title));
+        Ok(items)
+    }
+
+    // Replace the items in the SQLite database with those in the JSON files,
+    // returning how many items of each file were imported
+    pub fn import_json(&self) -> Result<Vec<(&'static str, usize)>> {
+        let Some(database) = &self.database else {
+            return Err(anyhow!("State is stored as JSON; set state.backend to sqlite first"));
+        };
+        let json = Self::with_dir(self.state_dir.clone())?;
+        Ok(vec![
+            import_kind::<CalendarItem>(database, &json)?,
+            import_kind::<NoteItem>(database, &json)?,
+            import_kind::<ReminderItem>(database, &json)?,
+        ])
+    }
+
+    // Upgrade every state file to the current schema, returning the steps
+    // taken; with `dry_run`, only report what would change
+    pub fn migrate(&self, dry_run: bool) -> Result<Vec<MigrationStep>> {
+        let mut steps = self.migrate_kind::<CalendarItem>(dry_run)?;
+        steps.extend(self.migrate_kind::<NoteItem>(dry_run)?);
+        steps.extend(self.migrate_kind::<ReminderItem>(dry_run)?);
+        Ok(steps)
+    }
+
+    fn migrate_kind<T: Persistent>(&self, dry_run: bool) -> Result<Vec<MigrationStep>> {
+        let _lock = if dry_run { None } else { Some(self.lock(T::filename())?) };
+        self.migrate_file::<T>(dry_run)
+    }
+
+    // Replace all items of a kind. Use `update` to change the stored items, so
+    // that changes made by another writer in between are not lost.
+    pub fn save<T: Persistent>(&self, items: &[T]) -> Result<()> {
+        if let Some(database) = &self.database {
+            database.save(items)?;
+            crate::search_index::sync_source(items);
+            return Ok(());
+        }
+        let _lock = self.lock(T::filename())?;
+        self.write_items(items)
+    }
+
+    // Load, modify and save the items of a kind while holding the write lock
+    pub fn update<T: Persistent, R>(&self, change: impl FnOnce(&mut Vec<T>) -> R) -> Result<R> {
+        if let Some(database) = &self.database {
+            let (result, items) = database.update(change)?;
+            crate::search_index::sync_source(&items);
+            return Ok(result);
+        }
+        let _lock = self.lock(T::filename())?;
+        let mut items = self.load_locked::<T>()?;
+        let result = change(&mut items);
+        self.write_items(&items)?;
+        Ok(result)
+    }
+
+    pub fn add<T: Persistent>(&self, item: T) -> Result<()> {
+        if let Some(database) = &self.database {
+            let position = database.add(&item)?;
+            crate::search_index::index_source_item(&item, position);
+            return Ok(());
+        }
+        self.update(|items: &mut Vec<T>| items.push(item))
+    }
+
+    #[allow(dead_code)]
+    pub fn cleanup_old_items(&self) -> Result<()> {
+        // Clean up old calendar events
+        let now = Local::now();
+        self.update(|events: &mut Vec<CalendarItem>| {
+            events.retain(|event| {
+                if let Ok(event_date) = DateTime::parse_from_str(
+                    &format!("{} {}", event.date, event.time),
+                    "%Y-%m-%d %H:%M",
+                ) {
+                    event_date > now
+                } else {
+                    true // Keep events with invalid dates
+                }
+            })
+        })?;
+
+        // Clean up old reminders
+        let one_month_ago = now - Duration::days(30);
+        self.update(|reminders: &mut Vec<ReminderItem>| {
+            reminders.retain(|reminder| {
+                if let Some(time) = &reminder.reminder_time {
+                    if let Ok(reminder_date) = DateTime::parse_from_str(time, "%Y-%m-%d %H:%M") {
+                        return reminder_date > one_month_ago;
+                    }
+                }
+                true // Keep reminders without dates
+            })
+        })?;
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn vacuum(&self) -> Result<()> {
+        if let Some(database) = &self.database {
+            return database.vacuum();
+        }
+
+        // Bring old files, including todos.json, up to date first
+        self.migrate(false)?;
+
+        // Compact JSON files by removing whitespace
+        for filename in &[EVENTS_FILE, NOTES_FILE, REMINDERS_FILE] {
+            let _lock = self.lock(filename)?;
+            if let Some((version, items)) = self.read_values(filename)? {
+                let file = StateFile { schema_version: version, items: &items };
+                self.write_atomic(filename, |writer| {
+                    serde_json::to_writer(writer, &file)?;
+                    Ok(())
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn import_kind<T: Persistent>(
+    database: &SqliteState,
+    json: &StateManager,
+) -> Result<(&'static str, usize)> {
+    let items = json.load::<T>()?;
+    database.save(&items)?;
+    crate::search_index::sync_source(&items);
+    Ok((T::filename(), items.len()))
+}
+
+// Convenience functions for backward compatibility
+// load_todos function removed in version 0.17.0, use load_reminders instead
+
+#[allow(dead_code)]
+pub fn load_events() -> Result<Vec<CalendarItem>> {
+    let manager = StateManager::new()?;
+    manager.cleanup_old_items()?;
+    manager.load()
+}
+
+#[allow(dead_code)] // Add this attribute since we might use this function later
+pub fn load_notes() -> Result<Vec<NoteItem>> {
+    StateManager::new()?.load()
+}
+
+#[allow(dead_code)]
+pub fn load_reminders() -> Result<Vec<ReminderItem>> {
+    let manager = StateManager::new()?;
+    manager.load()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_state_manager() -> Result<()> {
+        // Create a temporary directory for testing
+        let temp_dir = tempdir()?;
+        env::set_var("HOME", temp_dir.path());
+
+        let manager = StateManager::new()?;
+
+        // Test calendar events
+        let event = CalendarItem {
+            title: "Test Event".to_string(),
+            date: "2024-02-21".to_string(),
+            time: "14:30".to_string(),
+            calendars: vec!["Test Calendar".to_string()],
+            all_day: false,
+            location: None,
+            description: None,
+            email: None,
+            reminder: None,
+        };
+        manager.add(event)?;
+
+        let events: Vec<CalendarItem> = manager.load()?;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].title, "Test Event");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reminder_state() -> Result<()> {
+        // Create a temporary directory for testing
+        let temp_dir = tempdir()?;
+        env::set_var("HOME", temp_dir.path());
+
+        let manager = StateManager::new()?;
+
+        // Test reminders
+        let reminder = ReminderItem {
+            title: "Test Reminder".to_string(),
+            notes: Some("Reminder Notes".to_string()),
+            lists: vec!["Reminders".to_string()],
+            reminder_time: Some("2025-05-10 15:30".to_string()),
+        };
+        manager.add(reminder)?;
+
+        let reminders: Vec<ReminderItem> = manager.load()?;
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].title, "Test Reminder");
+        assert_eq!(reminders[0].lists[0], "Reminders");
+        assert_eq!(reminders[0].reminder_time, Some("2025-05-10 15:30".to_string()));
+
+        Ok(())
+    }
+
+    fn reminder(title: &str) -> ReminderItem {
+        ReminderItem {
+            title: title.to_string(),
+            notes: None,
+            lists: vec!["Reminders".to_string()],
+            reminder_time: None,
+        }
+    }
+
+    #[test]
+    fn test_concurrent_writers_keep_every_item() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let dir = temp_dir.path().to_path_buf();
+
+        let writers: Vec<_> = (0..8)
+            .map(|writer| {
+                let dir = dir.clone();
+                std::thread::spawn(move || -> Result<()> {
+                    // Each writer has its own manager, like separate processes would
+                    let manager = StateManager::with_dir(dir)?;
+                    for n in 0..25 {
+                        manager.add(reminder(&format!("writer {} item {}", writer, n)))?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().expect("writer thread panicked")?;
+        }
+
+        let reminders: Vec<ReminderItem> = StateManager::with_dir(dir.clone())?.load()?;
+        assert_eq!(reminders.len(), 200);
+        let leftovers: Vec<_> = std::fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_returns_result_and_saves() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let manager = StateManager::with_dir(temp_dir.path().to_path_buf())?;
+        manager.save(&[reminder("One"), reminder("Two")])?;
+
+        let removed = manager.update(|items: &mut Vec<ReminderItem>| {
+            let before = items.len();
+            items.retain(|item| item.title != "One");
+            before - items.len()
+        })?;
+        assert_eq!(removed, 1);
+        let reminders: Vec<ReminderItem> = manager.load()?;
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].title, "Two");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_write_keeps_previous_file() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let manager = StateManager::with_dir(temp_dir.path().to_path_buf())?;
+        manager.save(&[reminder("Keep me")])?;
+
+        let failed = manager.write_atomic(REMINDERS_FILE, |writer| {
+            use std::io::Write;
+            writer.write_all(b"[{\"title\": \"Half")?;
+            Err(anyhow!("simulated crash"))
+        });
+        assert!(failed.is_err());
+
+        let reminders: Vec<ReminderItem> = manager.load()?;
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].title, "Keep me");
+        assert_eq!(std::fs::read_dir(temp_dir.path())?.count(), 2); // reminders.json and its lock
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_upgrades_old_file_and_keeps_backup() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let old = r#"[{"title":"Buy milk","notes":null,"lists":["Home"],"reminder_time":null}]"#;
+        std::fs::write(temp_dir.path().join(REMINDERS_FILE), old)?;
+        let manager = StateManager::with_dir(temp_dir.path().to_path_buf())?;
+
+        let reminders: Vec<ReminderItem> = manager.load()?;
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].title, "Buy milk");
+
+        let file: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(temp_dir.path().join(REMINDERS_FILE))?)?;
+        assert_eq!(file["schema_version"], SCHEMA_VERSION);
+        assert_eq!(file["items"][0]["title"], "Buy milk");
+        let backup = std::fs::read_to_string(temp_dir.path().join("reminders.json.v0.bak"))?;
+        assert_eq!(backup, old);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_moves_todos_into_reminders() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let manager = StateManager::with_dir(temp_dir.path().to_path_buf())?;
+        manager.save(&[reminder("Call Sam")])?;
+        let todos = r#"[{"title":"Call Sam","notes":null,"lists":["Reminders"],"reminder_time":null},
+                        {"title":"Water plants","notes":"Balcony","lists":["Home"],"reminder_time":null}]"#;
+        std::fs::write(temp_dir.path().join("todos.json"), todos)?;
+
+        let planned = manager.migrate(true)?;
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].to_string(), "todos.json: move 1 item into reminders.json");
+        assert!(temp_dir.path().join("todos.json").exists());
+        assert_eq!(manager.read_values(REMINDERS_FILE)?.map(|(_, items)| items.len()), Some(1));
+
+        assert_eq!(manager.migrate(false)?, planned);
+        let reminders: Vec<ReminderItem> = manager.load()?;
+        let titles: Vec<_> = reminders.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, ["Call Sam", "Water plants"]);
+        assert_eq!(reminders[1].notes.as_deref(), Some("Balcony"));
+        assert!(!temp_dir.path().join("todos.json").exists());
+        assert!(temp_dir.path().join("todos.json.bak").exists());
+        assert!(manager.migrate(false)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_refuses_newer_schema() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let newer = format!(r#"{{"schema_version":{},"items":[]}}"#, SCHEMA_VERSION + 1);
+        std::fs::write(temp_dir.path().join(EVENTS_FILE), &newer)?;
+        let manager = StateManager::with_dir(temp_dir.path().to_path_buf())?;
+
+        assert!(manager.load::<CalendarItem>().is_err());
+        assert!(
+            manager
+                .add(CalendarItem {
+                    title: "Standup".to_string(),
+                    date: "2024-02-21".to_string(),
+                    time: "09:00".to_string(),
+                    calendars: Vec::new(),
+                    all_day: false,
+                    location: None,
+                    description: None,
+                    email: None,
+                    reminder: None,
+                })
+                .is_err()
+        );
+        assert_eq!(std::fs::read_to_string(temp_dir.path().join(EVENTS_FILE))?, newer);
+
+        Ok(())
+    }
+
+    fn event(title: &str, date: &str) -> CalendarItem {
+        CalendarItem {
+            title: title.to_string(),
+            date: date.to_string(),
+            time: "09:00".to_string(),
+            calendars: vec!["Work".to_string()],
+            all_day: false,
+            location: None,
+            description: None,
+            email: None,
+            reminder: None,
+        }
+    }
+
+    fn titles(events: &[CalendarItem]) -> Vec<&str> {
+        events.iter().map(|event| event.title.as_str()).collect()
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_sqlite_backend_stores_items() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let manager =
+            StateManager::with_backend(temp_dir.path().to_path_buf(), StateBackend::Sqlite)?;
+        manager.add(event("Retro", "2024-03-08"))?;
+        manager.add(event("Standup", "2024-03-04"))?;
+        manager.add(event("Planning", "2024-02-26"))?;
+        manager.add(reminder("Book room"))?;
+
+        let events: Vec<CalendarItem> = manager.load()?;
+        assert_eq!(titles(&events), ["Retro", "Standup", "Planning"]);
+        let reminders: Vec<ReminderItem> = manager.load()?;
+        assert_eq!(reminders.len(), 1);
+
+        let march = manager.load_between::<CalendarItem>(date("2024-03-01"), date("2024-03-31"))?;
+        assert_eq!(titles(&march), ["Standup", "Retro"]);
+        assert_eq!(titles(&manager.find_by_title::<CalendarItem>("standup")?), ["Standup"]);
+
+        let removed = manager.update(|events: &mut Vec<CalendarItem>| {
+            events.retain(|event| event.title != "Retro");
+            1
+        })?;
+        assert_eq!(removed, 1);
+        let events: Vec<CalendarItem> = manager.load()?;
+        assert_eq!(titles(&events), ["Standup", "Planning"]);
+        assert!(!temp_dir.path().join(EVENTS_FILE).exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_backend_imports_json_files() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let json = StateManager::with_dir(temp_dir.path().to_path_buf())?;
+        json.save(&[event("Standup", "2024-03-04"), event("Retro", "2024-03-08")])?;
+        json.save(&[reminder("Book room")])?;
+
+        let manager =
+            StateManager::with_backend(temp_dir.path().to_path_buf(), StateBackend::Sqlite)?;
+        let events: Vec<CalendarItem> = manager.load()?;
+        assert_eq!(titles(&events), ["Standup", "Retro"]);
+
+        // Importing again replaces what is in the database rather than adding to it
+        manager.add(reminder("Only in SQLite"))?;
+        let imported = manager.import_json()?;
+        assert_eq!(imported, [(EVENTS_FILE, 2), (NOTES_FILE, 0), (REMINDERS_FILE, 1)]);
+        let reminders: Vec<ReminderItem> = manager.load()?;
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].title, "Book room");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_concurrent_writers_keep_every_item() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let dir = temp_dir.path().to_path_buf();
+        // Create the database before the writers start
+        StateManager::with_backend(dir.clone(), StateBackend::Sqlite)?;
+
+        let writers: Vec<_> = (0..4)
+            .map(|writer| {
+                let dir = dir.clone();
+                std::thread::spawn(move || -> Result<()> {
+                    let manager = StateManager::with_backend(dir, StateBackend::Sqlite)?;
+                    for n in 0..25 {
+                        manager.add(reminder(&format!("writer {} item {}", writer, n)))?;
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().expect("writer thread panicked")?;
+        }
+
+        let manager = StateManager::with_backend(dir, StateBackend::Sqlite)?;
+        assert_eq!(manager.load::<ReminderItem>()?.len(), 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_backend_range_and_title_queries() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let manager = StateManager::with_dir(temp_dir.path().to_path_buf())?;
+        manager.save(&[
+            event("Retro", "2024-03-08"),
+            event("Undated", "someday"),
+            event("Standup", "2024-03-04"),
+            event("Planning", "2024-02-26"),
+        ])?;
+
+        let march = manager.load_between::<CalendarItem>(date("2024-03-01"), date("2024-03-31"))?;
+        assert_eq!(titles(&march), ["Standup", "Retro"]);
+        assert_eq!(titles(&manager.find_by_title::<CalendarItem>("RETRO")?), ["Retro"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_note_items_read_older_format() -> Result<()> {
+        let old =
+            r#"[{"title":"Plan","content":"Ship v2","folder":null,"created_at":"2024-06-03"}]"#;
+        let notes: Vec<NoteItem> = serde_json::from_str(old)?;
+        assert_eq!(notes[0].title, "Plan");
+        assert_eq!(notes[0].folder, "");
+        assert_eq!(notes[0].created.as_deref(), Some("2024-06-03"));
+        assert_eq!(notes[0].content.as_deref(), Some("Ship v2"));
+
+        Ok(())
+    }
+}
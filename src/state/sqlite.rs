@@ -0,0 +1,165 @@
+//! SQLite storage for `StateManager`, selected with `state.backend = "sqlite"`.
+//
+// Items of every kind share one `items` table. Each row keeps the item as JSON
+// next to its kind (the name of the JSON file it replaces) and the title and
+// date the item reports through `Persistent`, which are indexed so lookups by
+// title or date range do not read every item. Rows come back in the order they
+// were added, like the items of a JSON file.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use rusqlite::{Connection, Params, Transaction, TransactionBehavior, params};
+
+use super::{Persistent, SCHEMA_VERSION};
+
+// Database file inside the state directory
+pub const DATABASE_FILE: &str = "state.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS items (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        kind TEXT NOT NULL,
+        title TEXT NOT NULL,
+        date TEXT,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS items_kind_date ON items (kind, date);
+    CREATE INDEX IF NOT EXISTS items_kind_title ON items (kind, title COLLATE NOCASE);
+";
+
+pub struct SqliteState {
+    connection: Connection,
+}
+
+impl SqliteState {
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path)?;
+        // The API server and the terminal may use the database at the same time
+        connection.busy_timeout(Duration::from_secs(5))?;
+        connection.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))?;
+
+        let version: u32 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(anyhow!(
+                "{} was written by a newer version of DuckTape (schema version {}, this version reads up to {})",
+                path.display(),
+                version,
+                SCHEMA_VERSION
+            ));
+        }
+        // Items are always stored in the current schema; a later schema change
+        // would upgrade the rows of older databases here
+        connection.execute_batch(SCHEMA)?;
+        connection.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
+        Ok(Self { connection })
+    }
+
+    pub fn load<T: Persistent>(&self) -> Result<Vec<T>> {
+        query(
+            &self.connection,
+            "SELECT data FROM items WHERE kind = ?1 ORDER BY id",
+            [T::filename()],
+        )
+    }
+
+    // Items whose date is between `from` and `to`, inclusive, oldest first
+    pub fn load_between<T: Persistent>(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<T>> {
+        query(
+            &self.connection,
+            "SELECT data FROM items WHERE kind = ?1 AND date BETWEEN ?2 AND ?3 ORDER BY date, id",
+            params![T::filename(), from.to_string(), to.to_string()],
+        )
+    }
+
+    // Items with the given title, ignoring ASCII case
+    pub fn find_by_title<T: Persistent>(&self, title: &str) -> Result<Vec<T>> {
+        query(
+            &self.connection,
+            "SELECT data FROM items WHERE kind = ?1 AND title = ?2 COLLATE NOCASE ORDER BY id",
+            params![T::filename(), title],
+        )
+    }
+
+    // Add an item, returning its position among the items of its kind
+    pub fn add<T: Persistent>(&self, item: &T) -> Result<usize> {
+        let transaction = self.transaction()?;
+        insert(&transaction, item)?;
+        let count: usize = transaction.query_row(
+            "SELECT COUNT(*) FROM items WHERE kind = ?1",
+            [T::filename()],
+            |row| row.get(0),
+        )?;
+        transaction.commit()?;
+        Ok(count - 1)
+    }
+
+    pub fn save<T: Persistent>(&self, items: &[T]) -> Result<()> {
+        let transaction = self.transaction()?;
+        replace(&transaction, items)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    // Load, change and save the items of a kind in one transaction, returning
+    // the result of `change` and the saved items
+    pub fn update<T: Persistent, R>(
+        &self,
+        change: impl FnOnce(&mut Vec<T>) -> R,
+    ) -> Result<(R, Vec<T>)> {
+        let transaction = self.transaction()?;
+        let mut items: Vec<T> = query(
+            &transaction,
+            "SELECT data FROM items WHERE kind = ?1 ORDER BY id",
+            [T::filename()],
+        )?;
+        let result = change(&mut items);
+        replace(&transaction, &items)?;
+        transaction.commit()?;
+        Ok((result, items))
+    }
+
+    pub fn vacuum(&self) -> Result<()> {
+        self.connection.execute_batch("VACUUM")?;
+        Ok(())
+    }
+
+    // Start a transaction that takes the write lock straight away, so no other
+    // writer can change the items between reading and writing them
+    fn transaction(&self) -> Result<Transaction<'_>> {
+        Ok(Transaction::new_unchecked(&self.connection, TransactionBehavior::Immediate)?)
+    }
+}
+
+fn query<T: Persistent>(connection: &Connection, sql: &str, params: impl Params) -> Result<Vec<T>> {
+    let mut statement = connection.prepare_cached(sql)?;
+    let rows = statement.query_map(params, |row| row.get::<_, String>(0))?;
+    rows.map(|data| {
+        serde_json::from_str(&data?)
+            .map_err(|e| anyhow!("Failed to deserialize {} item: {}", T::filename(), e))
+    })
+    .collect()
+}
+
+fn insert<T: Persistent>(transaction: &Transaction, item: &T) -> Result<()> {
+    transaction
+        .prepare_cached("INSERT INTO items (kind, title, date, data) VALUES (?1, ?2, ?3, ?4)")?
+        .execute(params![
+            T::filename(),
+            item.index_title(),
+            item.index_date().map(|date| date.to_string()),
+            serde_json::to_string(item)?,
+        ])?;
+    Ok(())
+}
+
+fn replace<T: Persistent>(transaction: &Transaction, items: &[T]) -> Result<()> {
+    transaction.execute("DELETE FROM items WHERE kind = ?1", [T::filename()])?;
+    for item in items {
+        insert(transaction, item)?;
+    }
+    Ok(())
+}
@@ -4,15 +4,44 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+/// Folder given to local notes that were saved without one
+pub const DEFAULT_FOLDER: &str = "Notes";
+
 /// Represents a note in the system
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Note {
     pub id: String,
+    /// Title of the note; empty for notes saved before titles were stored
+    #[serde(default)]
+    pub title: String,
+    /// Folder containing the note
+    #[serde(default)]
+    pub folder: Option<String>,
     pub content: String,
     pub created_at: DateTime<Local>,
     pub updated_at: DateTime<Local>,
 }
 
+impl Note {
+    /// Create a note with a new UUID, timestamped now
+    pub fn new(title: &str, content: &str, folder: Option<&str>) -> Self {
+        let now = Local::now();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: title.to_string(),
+            folder: folder.map(str::to_string),
+            content: content.to_string(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// The note's folder, or `DEFAULT_FOLDER` if it has none
+    pub fn folder_name(&self) -> &str {
+        self.folder.as_deref().unwrap_or(DEFAULT_FOLDER)
+    }
+}
+
 /// Storage for managing notes
 pub struct NotesStorage {
     storage_path: PathBuf,
@@ -22,8 +51,11 @@ impl NotesStorage {
     /// Creates a new NotesStorage instance
     pub fn new() -> Result<Self> {
         let storage_dir = get_storage_dir()?;
-        let notes_dir = storage_dir.join("notes");
+        Self::with_dir(storage_dir.join("notes"))
+    }
 
+    /// Creates a NotesStorage that keeps its notes.json in `notes_dir`
+    pub fn with_dir(notes_dir: PathBuf) -> Result<Self> {
         if !notes_dir.exists() {
             log::debug!("Creating notes directory: {:?}", notes_dir);
             fs::create_dir_all(&notes_dir)
@@ -39,6 +71,17 @@ impl NotesStorage {
         self.storage_path.join("notes.json")
     }
 
+    fn write_notes(&self, notes: &[Note]) -> Result<()> {
+        let file_path = self.get_notes_file();
+        log::debug!("Writing {} notes to: {:?}", notes.len(), file_path);
+
+        let content =
+            serde_json::to_string_pretty(notes).context("Failed to serialize notes to JSON")?;
+
+        fs::write(&file_path, content)
+            .context(format!("Failed to write notes file at {:?}", file_path))
+    }
+
     /// Lists all notes in storage
     pub fn list_notes(&self) -> Result<Vec<Note>> {
         let file_path = self.get_notes_file();
@@ -68,15 +111,7 @@ impl NotesStorage {
     pub fn add_note(&self, note: &Note) -> Result<()> {
         let mut notes = self.list_notes()?;
         notes.push(note.clone());
-
-        let file_path = self.get_notes_file();
-        log::debug!("Writing {} notes to: {:?}", notes.len(), file_path);
-
-        let content =
-            serde_json::to_string_pretty(&notes).context("Failed to serialize notes to JSON")?;
-
-        fs::write(&file_path, content)
-            .context(format!("Failed to write notes file at {:?}", file_path))?;
+        self.write_notes(&notes)?;
 
         log::debug!("Note added successfully with ID: {}", note.id);
         Ok(())
@@ -94,14 +129,7 @@ impl NotesStorage {
             return Ok(false);
         }
 
-        let file_path = self.get_notes_file();
-        log::debug!("Writing {} notes after deletion to: {:?}", notes.len(), file_path);
-
-        let content =
-            serde_json::to_string_pretty(&notes).context("Failed to serialize notes to JSON")?;
-
-        fs::write(&file_path, content)
-            .context(format!("Failed to write notes file after deletion at {:?}", file_path))?;
+        self.write_notes(&notes)?;
 
         log::debug!("Note with ID {} deleted successfully", id);
         Ok(true)
@@ -123,33 +151,26 @@ impl NotesStorage {
 
     /// Updates a note content by ID
     pub fn update_note(&self, id: &str, content: &str) -> Result<bool> {
-        let mut notes = self.list_notes()?;
-        let mut found = false;
-
-        for note in &mut notes {
-            if note.id == id {
-                note.content = content.to_string();
-                note.updated_at = Local::now();
-                found = true;
-                break;
-            }
-        }
-
-        if !found {
+        let Some(mut note) = self.get_note(id)? else {
             log::debug!("Note with ID {} not found for update", id);
             return Ok(false);
-        }
-
-        let file_path = self.get_notes_file();
-        log::debug!("Writing updated notes to: {:?}", file_path);
+        };
+        note.content = content.to_string();
+        self.save_note(&note)
+    }
 
-        let content = serde_json::to_string_pretty(&notes)
-            .context("Failed to serialize updated notes to JSON")?;
+    /// Replaces the stored note that has the same ID as `note`, updating its timestamp
+    pub fn save_note(&self, note: &Note) -> Result<bool> {
+        let mut notes = self.list_notes()?;
+        let Some(stored) = notes.iter_mut().find(|n| n.id == note.id) else {
+            log::debug!("Note with ID {} not found for update", note.id);
+            return Ok(false);
+        };
+        *stored = Note { updated_at: Local::now(), ..note.clone() };
 
-        fs::write(&file_path, content)
-            .context(format!("Failed to write updated notes file at {:?}", file_path))?;
+        self.write_notes(&notes)?;
 
-        log::debug!("Note with ID {} updated successfully", id);
+        log::debug!("Note with ID {} updated successfully", note.id);
         Ok(true)
     }
 }
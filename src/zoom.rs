@@ -1,5 +1,8 @@
 use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+use lazy_static::lazy_static;
 use log::{debug, error, info};
+use regex::Regex;
 use reqwest::Client;
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
@@ -121,6 +124,38 @@ pub struct ZoomMeetingOptions {
     pub agenda: Option<String>,
 }
 
+/// Fields to change on an existing meeting; `None` fields are left as they are
+#[derive(Debug, Default, Serialize)]
+pub struct ZoomMeetingUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agenda: Option<String>,
+}
+
+impl ZoomMeetingUpdate {
+    /// Move a meeting to the local times `start`..`end`
+    ///
+    /// The start is sent in UTC, since Zoom reads a `Z` suffix as UTC whatever
+    /// the meeting's own time zone is.
+    pub fn reschedule(start: NaiveDateTime, end: NaiveDateTime) -> Result<Self> {
+        let start_utc = Local
+            .from_local_datetime(&start)
+            .earliest()
+            .ok_or_else(|| anyhow!("Time {} does not exist in the local time zone", start))?
+            .with_timezone(&Utc);
+        Ok(Self {
+            start_time: Some(start_utc.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+            duration: Some((end - start).num_minutes() as u32),
+            ..Default::default()
+        })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ZoomMeetingResponse {
     pub id: u64,
@@ -243,7 +278,49 @@ impl ZoomClient {
         Ok(meeting)
     }
 
-    #[allow(dead_code)]
+    // Update a scheduled Zoom meeting
+    pub async fn update_meeting(
+        &mut self,
+        meeting_id: u64,
+        update: ZoomMeetingUpdate,
+    ) -> Result<()> {
+        debug!("Updating Zoom meeting {}: {:?}", meeting_id, update);
+
+        // Get access token
+        let token = self.credentials.get_access_token().await?;
+
+        // Sanitize input data
+        let update = ZoomMeetingUpdate {
+            topic: update.topic.as_deref().map(|t| sanitize_zoom_field(t, 200)),
+            agenda: update.agenda.as_deref().map(|a| sanitize_zoom_field(a, 2000)),
+            ..update
+        };
+
+        // Make the API call
+        let url = format!("{}/meetings/{}", ZOOM_API_BASE, meeting_id);
+        let response = self
+            .client
+            .patch(&url)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .json(&update)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to send Zoom API request: {}", e))?;
+
+        // Check for errors
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text =
+                response.text().await.unwrap_or_else(|_| "Unable to get error response".into());
+            error!("Zoom API error: {} - {}", status, error_text);
+            return Err(anyhow!("Zoom API error ({}): {}", status, error_text));
+        }
+
+        info!("Successfully updated Zoom meeting: {}", meeting_id);
+        Ok(())
+    }
+
     pub async fn delete_meeting(&mut self, meeting_id: u64) -> Result<()> {
         debug!("Deleting Zoom meeting: {}", meeting_id);
 
@@ -360,10 +437,64 @@ pub fn calculate_meeting_duration(start_time: &str, end_time: &str) -> Result<u3
     Ok(std::cmp::max(duration_minutes, 15))
 }
 
+lazy_static! {
+    static ref MEETING_URL_RE: Regex = Regex::new(r"zoom\.us/(?:j|w|s)/(\d{9,11})").unwrap();
+    static ref MEETING_ID_RE: Regex =
+        Regex::new(r"(?i)meeting id:?\s*(\d{3}[\d ]{6,12}\d)").unwrap();
+}
+
+// Helper function to find a Zoom meeting ID in event notes, either from a
+// join URL (https://zoom.us/j/123456789) or a "Meeting ID: 123 456 789" line
+pub fn extract_meeting_id(text: &str) -> Option<u64> {
+    MEETING_URL_RE
+        .captures(text)
+        .or_else(|| MEETING_ID_RE.captures(text))
+        .and_then(|caps| caps[1].replace(' ', "").parse().ok())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_meeting_update_only_sends_set_fields() {
+        let update = ZoomMeetingUpdate {
+            start_time: Some("2024-06-05T10:00:00Z".to_string()),
+            duration: Some(30),
+            ..Default::default()
+        };
+        let body = serde_json::to_value(&update).unwrap();
+        assert_eq!(body, serde_json::json!({"start_time": "2024-06-05T10:00:00Z", "duration": 30}));
+    }
+
+    #[test]
+    fn test_reschedule_sends_utc_start() {
+        let start = chrono::NaiveDate::from_ymd_opt(2024, 6, 5)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap();
+        let update =
+            ZoomMeetingUpdate::reschedule(start, start + chrono::Duration::minutes(45)).unwrap();
+
+        let start_utc = Local.from_local_datetime(&start).unwrap().with_timezone(&Utc);
+        let body = serde_json::to_value(&update).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "start_time": start_utc.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                "duration": 45
+            })
+        );
+    }
+
+    #[test]
+    fn test_extract_meeting_id() {
+        let notes = "Agenda\n\n--------------------\nZoom Meeting\n--------------------\nJoin URL: https://us02web.zoom.us/j/81234567890?pwd=abc\nPassword: 123";
+        assert_eq!(extract_meeting_id(notes), Some(81234567890));
+        assert_eq!(extract_meeting_id("Meeting ID: 812 3456 7890"), Some(81234567890));
+        assert_eq!(extract_meeting_id("Call me on 555-1234"), None);
+    }
+
     #[test]
     fn test_format_zoom_time() {
         let result = format_zoom_time("2023-12-25", "14:30").unwrap();
@@ -0,0 +1,474 @@
+// CommandProcessor is deprecated in favour of the parser module but still drives the CLI.
+#![allow(deprecated)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use ducktape::calendar::{self, EventConfig, InMemoryBackend};
+use ducktape::command_processor::{CommandArgs, CommandProcessor};
+use tempfile::TempDir;
+use tokio::sync::Mutex;
+
+// The calendar backend and HOME are process-wide, so tests in this file run one at a time.
+static TEST_LOCK: Mutex<()> = Mutex::const_new(());
+
+fn install_backend(calendars: &[&str]) -> Result<(TempDir, Arc<InMemoryBackend>)> {
+    let temp_dir = tempfile::tempdir()?;
+    std::env::set_var("HOME", temp_dir.path());
+    let backend = Arc::new(InMemoryBackend::with_calendars(calendars.iter().copied()));
+    calendar::set_backend(backend.clone());
+    Ok((temp_dir, backend))
+}
+
+#[tokio::test]
+async fn test_calendar_create_through_command_processor() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work", "Home"])?;
+
+    let mut flags = HashMap::new();
+    flags.insert("location".to_string(), Some("Room 1".to_string()));
+    flags.insert("email".to_string(), Some("joe@example.com".to_string()));
+    let args = CommandArgs {
+        command: "calendar".to_string(),
+        args: vec![
+            "create".to_string(),
+            "Planning".to_string(),
+            "2024-06-03".to_string(),
+            "10:00".to_string(),
+            "11:00".to_string(),
+            "Work".to_string(),
+        ],
+        flags,
+    };
+
+    CommandProcessor::new().execute(args).await?;
+
+    let events = backend.events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].title, "Planning");
+    assert_eq!(events[0].start_date, "2024-06-03");
+    assert_eq!(events[0].end_time.as_deref(), Some("11:00"));
+    assert_eq!(events[0].calendars, vec!["Work".to_string()]);
+    assert_eq!(events[0].location.as_deref(), Some("Room 1"));
+    assert_eq!(events[0].emails, vec!["joe@example.com".to_string()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_event_in_multiple_calendars() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work", "Home"])?;
+
+    let mut config = EventConfig::new("Offsite", "2024-06-04", "09:00");
+    config.end_time = Some("17:00".to_string());
+    config.calendars = vec!["Work".to_string(), "Home".to_string(), "Missing".to_string()];
+    calendar::create_event(config).await?;
+
+    let calendars: Vec<String> =
+        backend.events().into_iter().flat_map(|event| event.calendars).collect();
+    assert_eq!(calendars, vec!["Work".to_string(), "Home".to_string()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_event_fails_without_known_calendars() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work"])?;
+
+    let mut config = EventConfig::new("Offsite", "2024-06-04", "09:00");
+    config.calendars = vec!["Missing".to_string()];
+
+    assert!(calendar::create_event(config).await.is_err());
+    assert!(backend.events().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_available_calendars_come_from_backend() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work"])?;
+    backend.add_calendar("Family");
+
+    assert_eq!(calendar::get_available_calendars().await?, vec!["Work", "Family"]);
+    calendar::list_calendars().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_calendar_export_writes_ics_file() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (home, _backend) = install_backend(&["Work", "Home"])?;
+
+    for (title, date, calendar) in [
+        ("Planning", "2024-06-03", "Work"),
+        ("Dinner", "2024-06-04", "Home"),
+        ("Retro", "2024-07-15", "Work"),
+    ] {
+        let mut config = EventConfig::new(title, date, "10:00");
+        config.end_time = Some("11:00".to_string());
+        config.calendars = vec![calendar.to_string()];
+        config.emails = vec!["joe@example.com".to_string()];
+        calendar::create_event(config).await?;
+    }
+
+    let output = home.path().join("export.ics");
+    let mut flags = HashMap::new();
+    flags.insert("from".to_string(), Some("2024-06-01".to_string()));
+    flags.insert("to".to_string(), Some("2024-06-30".to_string()));
+    flags.insert("calendar".to_string(), Some("Work".to_string()));
+    flags.insert("output".to_string(), Some(output.to_string_lossy().to_string()));
+    let args =
+        CommandArgs { command: "calendar".to_string(), args: vec!["export".to_string()], flags };
+    CommandProcessor::new().execute(args).await?;
+
+    let ics = std::fs::read_to_string(&output)?;
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    assert!(ics.contains("SUMMARY:Planning\r\n"));
+    assert!(ics.contains("ATTENDEE;RSVP=TRUE:mailto:joe@example.com\r\n"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_calendar_export_includes_earlier_recurring_series() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, _backend) = install_backend(&["Work"])?;
+    for (title, date, rule) in [
+        ("Standup", "2024-05-01", "FREQ=WEEKLY;BYDAY=MO"),
+        ("Kickoff", "2024-05-01", "FREQ=DAILY;COUNT=3"),
+        ("Planning", "2024-06-03", ""),
+    ] {
+        let mut config = EventConfig::new(title, date, "10:00");
+        config.calendars = vec!["Work".to_string()];
+        config.recurrence = calendar::parse_ical_recurrence(rule);
+        if let Some(recurrence) = config.recurrence.as_mut().filter(|_| title == "Standup") {
+            recurrence.exception_dates = vec!["2024-06-10".to_string()];
+        }
+        calendar::create_event(config).await?;
+    }
+
+    let from = chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+    let to = chrono::NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+    let events = calendar::export_events(&[], from, to).await?;
+    let titles: Vec<&str> = events.iter().map(|event| event.title.as_str()).collect();
+    assert_eq!(titles, ["Planning", "Standup"]);
+
+    let ics = calendar::events_to_ics(&events)?;
+    assert!(ics.contains("RRULE:FREQ=WEEKLY;INTERVAL=1;BYDAY=MO\r\n"));
+    assert!(ics.contains("EXDATE:"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_events_rejects_inverted_range() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, _backend) = install_backend(&["Work"])?;
+
+    let from = chrono::NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+    let to = chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+    assert!(calendar::list_events(&[], from, to).await.is_err());
+    assert!(calendar::resolve_date_range(Some("2024-06-30"), Some("2024-06-01"), 30).is_err());
+    Ok(())
+}
+
+async fn create_standups(backend: &InMemoryBackend) -> Result<()> {
+    for (time, calendar) in [("09:00", "Work"), ("16:00", "Home")] {
+        let mut config = EventConfig::new("Standup", "2024-06-05", time);
+        config.calendars = vec![calendar.to_string()];
+        calendar::create_event(config).await?;
+    }
+    assert_eq!(backend.events().len(), 2);
+    Ok(())
+}
+
+fn delete_args(positional: &[&str], flags: &[(&str, Option<&str>)]) -> CommandArgs {
+    calendar_args("delete", positional, flags)
+}
+
+fn update_args(positional: &[&str], flags: &[(&str, Option<&str>)]) -> CommandArgs {
+    calendar_args("update", positional, flags)
+}
+
+fn calendar_args(action: &str, positional: &[&str], flags: &[(&str, Option<&str>)]) -> CommandArgs {
+    let mut args = vec![action.to_string()];
+    args.extend(positional.iter().map(|a| a.to_string()));
+    CommandArgs {
+        command: "calendar".to_string(),
+        args,
+        flags: flags.iter().map(|(k, v)| (k.to_string(), v.map(String::from))).collect(),
+    }
+}
+
+#[tokio::test]
+async fn test_calendar_delete_requires_disambiguation() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work", "Home"])?;
+    create_standups(&backend).await?;
+
+    let processor = CommandProcessor::new();
+    processor.execute(delete_args(&["standup", "2024-06-05"], &[])).await?;
+    assert_eq!(backend.events().len(), 2);
+
+    processor
+        .execute(delete_args(&["Standup", "2024-06-05"], &[("index", Some("2"))]))
+        .await?;
+    let remaining = backend.events();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].start_time, "09:00");
+
+    assert!(
+        processor
+            .execute(delete_args(&["Standup", "2024-06-05"], &[("index", Some("5"))]))
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_calendar_delete_all_matches_in_calendar() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work", "Home"])?;
+    create_standups(&backend).await?;
+
+    let processor = CommandProcessor::new();
+    processor
+        .execute(delete_args(&["Standup", "2024-06-05"], &[("calendar", Some("Home"))]))
+        .await?;
+    assert_eq!(backend.events().len(), 1);
+    assert_eq!(backend.events()[0].calendars, vec!["Work".to_string()]);
+
+    let mut config = EventConfig::new("Standup", "2024-06-05", "17:00");
+    config.calendars = vec!["Home".to_string()];
+    calendar::create_event(config).await?;
+    processor
+        .execute(delete_args(&["Standup", "2024-06-05"], &[("all", None)]))
+        .await?;
+    assert!(backend.events().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_calendar_delete_by_uid() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work", "Home"])?;
+    create_standups(&backend).await?;
+    let uid = backend.events()[1].uid.clone().unwrap();
+
+    let processor = CommandProcessor::new();
+    processor.execute(delete_args(&[&uid], &[])).await?;
+    assert_eq!(backend.events().len(), 1);
+    assert!(processor.execute(delete_args(&[&uid], &[])).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_calendar_update_by_title_keeps_attendees() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work", "Home"])?;
+    let mut config = EventConfig::new("Planning", "2024-06-03", "10:00");
+    config.end_time = Some("11:00".to_string());
+    config.calendars = vec!["Work".to_string()];
+    config.emails = vec!["joe@example.com".to_string()];
+    calendar::create_event(config).await?;
+
+    let processor = CommandProcessor::new();
+    processor
+        .execute(update_args(
+            &["planning", "2024-06-03"],
+            &[
+                ("date", Some("2024-06-04")),
+                ("start", Some("14:30")),
+                ("location", Some("Room 2")),
+                ("add_email", Some("ann@example.com,joe@example.com")),
+            ],
+        ))
+        .await?;
+
+    let events = backend.events();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].start_date, "2024-06-04");
+    assert_eq!(events[0].start_time, "14:30");
+    assert_eq!(events[0].end_time.as_deref(), Some("15:30"));
+    assert_eq!(events[0].location.as_deref(), Some("Room 2"));
+    assert_eq!(events[0].emails, ["joe@example.com", "ann@example.com"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_calendar_update_by_uid_and_disambiguation() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work", "Home"])?;
+    create_standups(&backend).await?;
+
+    let processor = CommandProcessor::new();
+    processor
+        .execute(update_args(&["Standup", "2024-06-05"], &[("start", Some("08:00"))]))
+        .await?;
+    assert_eq!(backend.events()[0].start_time, "09:00");
+    assert_eq!(backend.events()[1].start_time, "16:00");
+
+    let uid = backend.events()[1].uid.clone().unwrap();
+    processor.execute(update_args(&[&uid], &[("end", Some("16:45"))])).await?;
+    assert_eq!(backend.events()[1].end_time.as_deref(), Some("16:45"));
+
+    // Nothing to change, and unknown UIDs, are errors
+    assert!(processor.execute(update_args(&[&uid], &[])).await.is_err());
+    assert!(
+        processor
+            .execute(update_args(&["missing"], &[("start", Some("08:00"))]))
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_calendar_agenda_groups_backend_events() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work", "Home"])?;
+    create_standups(&backend).await?;
+    let mut review = EventConfig::new("Review", "2024-06-07", "14:00");
+    review.calendars = vec!["Work".to_string()];
+    calendar::create_event(review).await?;
+
+    let from = chrono::NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+    let to = chrono::NaiveDate::from_ymd_opt(2024, 6, 9).unwrap();
+    let agenda = calendar::get_agenda(&["Work".to_string()], from, to).await?;
+    assert_eq!(agenda.event_count(), 2);
+    assert_eq!(agenda.days.len(), 2);
+    assert_eq!(agenda.days[1].events[0].title, "Review");
+
+    let mut flags = HashMap::new();
+    flags.insert("from".to_string(), Some("2024-06-03".to_string()));
+    flags.insert("json".to_string(), None);
+    let args =
+        CommandArgs { command: "calendar".to_string(), args: vec!["agenda".to_string()], flags };
+    CommandProcessor::new().execute(args).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_calendar_agenda_expands_recurring_series() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, _backend) = install_backend(&["Work"])?;
+    let mut standup = EventConfig::new("Standup", "2024-05-01", "09:00");
+    standup.calendars = vec!["Work".to_string()];
+    standup.recurrence = Some(
+        calendar::RecurrencePattern::new(calendar::RecurrenceFrequency::Weekly)
+            .with_days_of_week(&[1, 3]),
+    );
+    calendar::create_event(standup).await?;
+    let mut review = EventConfig::new("Review", "2024-06-06", "14:00");
+    review.calendars = vec!["Work".to_string()];
+    review.recurrence =
+        Some(calendar::RecurrencePattern::new(calendar::RecurrenceFrequency::Daily));
+    calendar::create_event(review).await?;
+
+    let from = chrono::NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+    let to = chrono::NaiveDate::from_ymd_opt(2024, 6, 9).unwrap();
+    let agenda = calendar::get_agenda(&[], from, to).await?;
+    let entries: Vec<(String, &str)> = agenda
+        .days
+        .iter()
+        .flat_map(|day| day.events.iter().map(move |e| (day.date.to_string(), e.title.as_str())))
+        .collect();
+    assert_eq!(
+        entries,
+        [
+            ("2024-06-03".to_string(), "Standup"),
+            ("2024-06-05".to_string(), "Standup"),
+            ("2024-06-06".to_string(), "Review"),
+            ("2024-06-07".to_string(), "Review"),
+            ("2024-06-08".to_string(), "Review"),
+            ("2024-06-09".to_string(), "Review"),
+        ]
+    );
+    Ok(())
+}
+
+fn create_args(title: &str, start: &str, end: &str, flags: &[&str]) -> CommandArgs {
+    CommandArgs {
+        command: "calendar".to_string(),
+        args: ["create", title, "2024-06-05", start, end, "Work"].map(String::from).to_vec(),
+        flags: flags.iter().map(|f| (f.to_string(), Some("true".to_string()))).collect(),
+    }
+}
+
+#[tokio::test]
+async fn test_calendar_create_checks_conflicts() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, backend) = install_backend(&["Work", "Home"])?;
+    let processor = CommandProcessor::new();
+    processor.execute(create_args("Planning", "10:00", "11:00", &[])).await?;
+
+    processor
+        .execute(create_args("Review", "10:30", "11:30", &["check_conflicts"]))
+        .await?;
+    assert_eq!(backend.events().len(), 1);
+
+    processor
+        .execute(create_args("Retro", "11:00", "12:00", &["check_conflicts"]))
+        .await?;
+    assert_eq!(backend.events().len(), 2);
+
+    processor
+        .execute(create_args("Review", "10:30", "11:30", &["check_conflicts", "force"]))
+        .await?;
+    assert_eq!(backend.events().len(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_find_free_slots_skips_busy_time() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, _backend) = install_backend(&["Work", "Home"])?;
+    for (title, start, end, calendar) in
+        [("Planning", "10:00", "11:00", "Work"), ("Dentist", "13:00", "14:30", "Home")]
+    {
+        let mut config = EventConfig::new(title, "2024-06-05", start);
+        config.end_time = Some(end.to_string());
+        config.calendars = vec![calendar.to_string()];
+        calendar::create_event(config).await?;
+    }
+
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 6, 5).unwrap();
+    let hours = calendar::WorkingHours::default();
+    let slots: Vec<String> = calendar::find_free_slots(date, 60, &[], &hours)
+        .await?
+        .iter()
+        .map(|slot| slot.to_string())
+        .collect();
+    assert_eq!(slots, vec!["09:00-10:00", "11:00-13:00", "14:30-17:00"]);
+
+    let work_only: Vec<String> = calendar::find_free_slots(date, 60, &["Work".to_string()], &hours)
+        .await?
+        .iter()
+        .map(|slot| slot.to_string())
+        .collect();
+    assert_eq!(work_only, vec!["09:00-10:00", "11:00-17:00"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_find_free_slots_skips_earlier_recurring_series() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let (_home, _backend) = install_backend(&["Work"])?;
+    let mut standup = EventConfig::new("Standup", "2024-05-01", "09:00");
+    standup.end_time = Some("10:00".to_string());
+    standup.calendars = vec!["Work".to_string()];
+    standup.recurrence =
+        Some(calendar::RecurrencePattern::new(calendar::RecurrenceFrequency::Daily));
+    calendar::create_event(standup).await?;
+
+    let date = chrono::NaiveDate::from_ymd_opt(2024, 6, 5).unwrap();
+    let hours = calendar::WorkingHours::default();
+    let slots: Vec<String> = calendar::find_free_slots(date, 60, &[], &hours)
+        .await?
+        .iter()
+        .map(|slot| slot.to_string())
+        .collect();
+    assert_eq!(slots, vec!["10:00-17:00"]);
+    Ok(())
+}
@@ -69,3 +69,21 @@ fn test_command_args_parse_errors() {
     // Test missing ducktape prefix
     assert!(CommandArgs::parse("invalid command").is_err());
 }
+
+#[test]
+#[allow(deprecated)]
+fn test_calendar_import_format_is_only_set_when_given() {
+    use clap::Parser;
+    use ducktape::cli::{Cli, convert_to_command_args};
+
+    let cli = Cli::try_parse_from(["ducktape", "calendar", "import", "events.csv"]).unwrap();
+    let args = convert_to_command_args(&cli).unwrap();
+    assert_eq!(args.args, vec!["import", "events.csv", "Work"]);
+    assert!(!args.flags.contains_key("format"));
+
+    let cli =
+        Cli::try_parse_from(["ducktape", "calendar", "import", "events.txt", "--format", "csv"])
+            .unwrap();
+    let args = convert_to_command_args(&cli).unwrap();
+    assert_eq!(args.flags.get("format"), Some(&Some("csv".to_string())));
+}
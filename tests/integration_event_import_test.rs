@@ -4,6 +4,9 @@ use std::fs::File;
 use std::io::Write;
 use tempfile::tempdir;
 
+// Import tests swap the process-wide calendar backend and HOME, so they run one at a time.
+static BACKEND_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
 #[test]
 fn test_csv_header_validation() -> Result<()> {
     // Test that CSV headers are properly validated
@@ -42,3 +45,106 @@ fn test_calendar_email_validation() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_ics_import_reports_per_event_results() -> Result<()> {
+    use ducktape::calendar::InMemoryBackend;
+    use std::sync::Arc;
+
+    let _guard = BACKEND_LOCK.lock().await;
+    let home = tempdir()?;
+    std::env::set_var("HOME", home.path());
+    let backend = Arc::new(InMemoryBackend::with_calendars(["Team"]));
+    calendar::set_backend(backend.clone());
+
+    let dir = tempdir()?;
+    let file_path = dir.path().join("export.ics");
+    std::fs::write(
+        &file_path,
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         PRODID:-//Google Inc//Google Calendar 70.9054//EN\r\n\
+         BEGIN:VEVENT\r\n\
+         SUMMARY:Design review\r\n\
+         DTSTART:20250320T100000\r\n\
+         DTEND:20250320T110000\r\n\
+         ATTENDEE;CN=Test:mailto:test@example.com\r\n\
+         END:VEVENT\r\n\
+         BEGIN:VEVENT\r\n\
+         SUMMARY:Broken\r\n\
+         DTSTART:not-a-date\r\n\
+         END:VEVENT\r\n\
+         BEGIN:VEVENT\r\n\
+         SUMMARY:Company holiday\r\n\
+         DTSTART;VALUE=DATE:20250321\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n",
+    )?;
+
+    let report = calendar::import_ics_events(&file_path, Some("Team".to_string())).await?;
+
+    assert_eq!(report.imported, vec!["Design review", "Company holiday"]);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].position, 2);
+    assert_eq!(report.failed[0].title.as_deref(), Some("Broken"));
+
+    let events = backend.events();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].calendars, vec!["Team".to_string()]);
+    assert_eq!(events[0].emails, vec!["test@example.com".to_string()]);
+    assert!(events[1].all_day);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_csv_import_with_mapping_and_dry_run() -> Result<()> {
+    use ducktape::calendar::{CsvImportOptions, InMemoryBackend};
+    use std::sync::Arc;
+
+    let _guard = BACKEND_LOCK.lock().await;
+    let home = tempdir()?;
+    std::env::set_var("HOME", home.path());
+    let backend = Arc::new(InMemoryBackend::with_calendars(["Work", "Team"]));
+    calendar::set_backend(backend.clone());
+
+    let dir = tempdir()?;
+    let file_path = dir.path().join("events.csv");
+    let mut file = File::create(&file_path)?;
+    writeln!(file, "Subject,Day,Begins,Ends,Guests,Calendar,Notes")?;
+    writeln!(
+        file,
+        "Kickoff,2025-03-20,10:00,11:00,test@example.com,Team,\"Agenda:\n- scope\n- dates\""
+    )?;
+    writeln!(file, "Bad date,2025-13-40,10:00,11:00,,")?;
+    writeln!(file, "\"Retro, sprint 4\",2025-03-21,3:00 PM,4:00 PM,,")?;
+    writeln!(file, "Bad email,2025-03-22,09:00,10:00,not-an-email,")?;
+    drop(file);
+
+    let options = CsvImportOptions::default()
+        .with_mapping_spec("title=Subject,date=Day,start_time=Begins,end_time=Ends,emails=Guests")?
+        .with_dry_run(true);
+    let report =
+        calendar::import_csv_events(&file_path, Some("Work".to_string()), &options).await?;
+
+    assert!(report.dry_run);
+    assert_eq!(report.imported, vec!["Kickoff", "Retro, sprint 4"]);
+    let failed_rows: Vec<usize> = report.failed.iter().map(|f| f.position).collect();
+    assert_eq!(failed_rows, vec![2, 4], "failures are numbered by data row");
+    assert!(backend.events().is_empty(), "dry run must not create events");
+
+    let report = calendar::import_csv_events(
+        &file_path,
+        Some("Work".to_string()),
+        &options.clone().with_dry_run(false),
+    )
+    .await?;
+    assert_eq!(report.imported.len(), 2);
+
+    let events = backend.events();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].calendars, vec!["Team".to_string()]);
+    assert_eq!(events[0].emails, vec!["test@example.com".to_string()]);
+    assert_eq!(events[1].calendars, vec!["Work".to_string()]);
+    assert_eq!(events[1].start_time, "15:00");
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use ducktape::notes::{self, LocalNotesStore, NoteConfig, NotesStore};
+use tokio::sync::Mutex;
+
+// The note store is process-wide, so tests in this file run one at a time.
+static TEST_LOCK: Mutex<()> = Mutex::const_new(());
+
+// Two notes with the same title in one folder are exported to separate files
+// and must come back as two notes rather than one overwriting the other.
+#[tokio::test]
+async fn test_import_keeps_notes_with_duplicate_titles() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let source = tempfile::tempdir()?;
+    notes::set_store(Arc::new(LocalNotesStore::with_dir(source.path())));
+    notes::create_note(NoteConfig::with_folder("Plan", "first", "Work")).await?;
+    notes::create_note(NoteConfig::with_folder("Plan", "second", "Work")).await?;
+
+    let export_dir = tempfile::tempdir()?;
+    let exported = notes::export_notes(export_dir.path(), None).await?;
+    assert_eq!(exported.written.len(), 2);
+
+    let target = tempfile::tempdir()?;
+    notes::set_store(Arc::new(LocalNotesStore::with_dir(target.path())));
+    let report = notes::import_notes(export_dir.path(), None).await?;
+    assert_eq!(report.created, ["Plan", "Plan (2)"]);
+    assert_eq!(report.renamed.len(), 1);
+
+    let mut contents: Vec<String> =
+        notes::list_notes().await?.into_iter().filter_map(|note| note.content).collect();
+    contents.sort();
+    assert_eq!(contents, ["first", "second"]);
+
+    // Importing again updates the same two notes
+    let report = notes::import_notes(export_dir.path(), None).await?;
+    assert_eq!(report.updated, ["Plan", "Plan (2)"]);
+    assert_eq!(notes::list_notes().await?.len(), 2);
+    Ok(())
+}
+
+// Re-importing a backup into the store it came from must restore each note
+// in place, even when notes share a title and their files sort out of order.
+#[tokio::test]
+async fn test_reimport_into_same_store_matches_notes_by_id() -> Result<()> {
+    let _guard = TEST_LOCK.lock().await;
+    let store_dir = tempfile::tempdir()?;
+    let store = Arc::new(LocalNotesStore::with_dir(store_dir.path()));
+    store.create_note(NoteConfig::with_folder("Plan", "first", "Work")).await?;
+    store.create_note(NoteConfig::with_folder("Plan", "second", "Work")).await?;
+    let export_dir = tempfile::tempdir()?;
+    notes::set_store(store.clone());
+    notes::export_notes(export_dir.path(), None).await?;
+
+    // Edit both notes after the backup, then restore it
+    for note in store.list_notes().await? {
+        let id = note.id.unwrap();
+        store.update_note(&id, notes::NoteUpdate::replace("edited")).await?;
+    }
+    notes::set_store(store.clone());
+    let report = notes::import_notes(export_dir.path(), None).await?;
+    assert_eq!(report.updated, ["Plan", "Plan"]);
+    assert!(report.created.is_empty());
+    assert!(report.renamed.is_empty());
+
+    let restored: Vec<(String, Option<String>)> = store
+        .list_notes()
+        .await?
+        .into_iter()
+        .map(|note| (note.title, note.content))
+        .collect();
+    assert_eq!(
+        restored,
+        [
+            ("Plan".to_string(), Some("first".to_string())),
+            ("Plan".to_string(), Some("second".to_string())),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anyhow::Result;
+use ducktape::notes::{LocalNotesStore, NoteConfig, NoteUpdate, NotesStore};
+
+fn titles(notes: &[ducktape::notes::NoteItem]) -> Vec<&str> {
+    notes.iter().map(|note| note.title.as_str()).collect()
+}
+
+// The default local store searches through the shared search index, which
+// reads the notes under HOME once and is then kept current by the store.
+#[tokio::test]
+async fn test_local_store_searches_shared_index() -> Result<()> {
+    let home = tempfile::tempdir()?;
+    std::env::set_var("HOME", home.path());
+    let store = LocalNotesStore::default();
+    store.create_note(NoteConfig::new("Groceries", "- milk\n- eggs")).await?;
+
+    assert_eq!(titles(&store.search_notes("milk").await?), ["Groceries"]);
+
+    store
+        .create_note(NoteConfig::with_folder("Milk run", "Friday", "Errands"))
+        .await?;
+    assert_eq!(titles(&store.search_notes("milk").await?), ["Milk run", "Groceries"]);
+
+    let id = store.find_note_id("Groceries", None).await?;
+    store.update_note(&id, NoteUpdate::replace("- bread")).await?;
+    assert_eq!(titles(&store.search_notes("milk").await?), ["Milk run"]);
+    assert_eq!(titles(&store.search_notes("bread").await?), ["Groceries"]);
+
+    store.delete_note("Milk run", Some("Errands")).await?;
+    assert!(store.search_notes("milk").await?.is_empty());
+    Ok(())
+}